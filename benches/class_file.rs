@@ -0,0 +1,28 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use robusta::class_file::ClassFile;
+use std::fs::File;
+use std::hint::black_box;
+use std::io::Read;
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Reads a class compiled by javac out of the test jar, so parsing isn't measured with the inflating.
+fn read_test_class(name: &str) -> Vec<u8> {
+    let jar = Path::new(env!("CARGO_MANIFEST_DIR")).join("data/robusta-java-tests-0.1.0.jar");
+    let mut archive = ZipArchive::new(File::open(jar).unwrap()).unwrap();
+    let mut bytes = vec![];
+    archive.by_name(name).unwrap().read_to_end(&mut bytes).unwrap();
+    bytes
+}
+
+fn read_class(c: &mut Criterion) {
+    // Has a static initializer, branches and an exception handler
+    let class = read_test_class("com/jkitch/robusta/test/Assertions.class");
+
+    c.bench_function("ClassFile::read_from", |b| {
+        b.iter(|| ClassFile::read_from(&mut black_box(class.as_slice())).unwrap())
+    });
+}
+
+criterion_group!(benches, read_class);
+criterion_main!(benches);
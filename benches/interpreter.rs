@@ -0,0 +1,47 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use robusta::{Value, Vm};
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+
+fn test_vm() -> Vm {
+    let mut vm = Vm::new();
+    for entry in fs::read_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("data")).unwrap() {
+        vm.load(&entry.unwrap().path()).unwrap();
+    }
+    vm
+}
+
+fn arithmetic_loop(c: &mut Criterion) {
+    let mut vm = test_vm();
+
+    c.bench_function("sumOfSquares(10000)", |b| {
+        b.iter(|| vm.invoke_static("com.jkitch.robusta.test.Loops", "sumOfSquares", "(I)I",
+            vec![black_box(Value::Int(10_000))]).unwrap())
+    });
+}
+
+fn constructor_loop(c: &mut Criterion) {
+    let mut vm = test_vm();
+
+    c.bench_function("constructEmpty(10000)", |b| {
+        b.iter(|| vm.invoke_static("com.jkitch.robusta.test.Calls", "constructEmpty", "(I)I",
+            vec![black_box(Value::Int(10_000))]).unwrap())
+    });
+}
+
+fn allocation_loop(c: &mut Criterion) {
+    let mut vm = test_vm();
+
+    c.bench_function("constructPoints(10000)", |b| {
+        b.iter(|| vm.invoke_static("com.jkitch.robusta.test.Calls", "constructPoints", "(I)I",
+            vec![black_box(Value::Int(10_000))]).unwrap())
+    });
+}
+
+fn load_jars(c: &mut Criterion) {
+    c.bench_function("load data jars", |b| b.iter(test_vm));
+}
+
+criterion_group!(benches, arithmetic_loop, constructor_loop, allocation_loop, load_jars);
+criterion_main!(benches);
@@ -0,0 +1,35 @@
+package java.io;
+
+/**
+ * A stream printing to one of the process's standard streams, identified by its file descriptor.
+ */
+public class PrintStream {
+
+    private final int fd;
+
+    PrintStream(int fd) {
+        this.fd = fd;
+    }
+
+    public native void print(boolean b);
+
+    public native void print(char c);
+
+    public native void print(int i);
+
+    public native void print(long l);
+
+    public native void print(String s);
+
+    public native void println();
+
+    public native void println(boolean x);
+
+    public native void println(char x);
+
+    public native void println(int x);
+
+    public native void println(long x);
+
+    public native void println(String x);
+}
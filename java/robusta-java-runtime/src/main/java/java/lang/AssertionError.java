@@ -0,0 +1,7 @@
+package java.lang;
+
+public class AssertionError extends Error {
+
+    public AssertionError() {
+    }
+}
@@ -0,0 +1,14 @@
+package java.lang;
+
+public final class Class<T> {
+
+    private final String name;
+
+    private Class(String name) {
+        this.name = name;
+    }
+
+    public native String getName();
+
+    public native boolean desiredAssertionStatus();
+}
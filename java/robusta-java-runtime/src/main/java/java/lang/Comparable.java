@@ -0,0 +1,6 @@
+package java.lang;
+
+public interface Comparable<T> {
+
+    int compareTo(T other);
+}
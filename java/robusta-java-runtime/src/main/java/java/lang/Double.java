@@ -0,0 +1,20 @@
+package java.lang;
+
+public final class Double extends Number {
+
+    private final double value;
+
+    public Double(double value) {
+        this.value = value;
+    }
+
+    public static native Double valueOf(double value);
+
+    public native int intValue();
+
+    public native long longValue();
+
+    public native float floatValue();
+
+    public native double doubleValue();
+}
@@ -0,0 +1,20 @@
+package java.lang;
+
+public final class Float extends Number {
+
+    private final float value;
+
+    public Float(float value) {
+        this.value = value;
+    }
+
+    public static native Float valueOf(float value);
+
+    public native int intValue();
+
+    public native long longValue();
+
+    public native float floatValue();
+
+    public native double doubleValue();
+}
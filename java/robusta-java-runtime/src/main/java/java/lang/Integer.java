@@ -0,0 +1,30 @@
+package java.lang;
+
+public final class Integer extends Number {
+
+    private final int value;
+
+    public Integer(int value) {
+        this.value = value;
+    }
+
+    public static native Integer valueOf(int value);
+
+    public static native int bitCount(int i);
+
+    public static native int highestOneBit(int i);
+
+    public static native int lowestOneBit(int i);
+
+    public static native int numberOfLeadingZeros(int i);
+
+    public static native int numberOfTrailingZeros(int i);
+
+    public native int intValue();
+
+    public native long longValue();
+
+    public native float floatValue();
+
+    public native double doubleValue();
+}
@@ -0,0 +1,30 @@
+package java.lang;
+
+public final class Long extends Number {
+
+    private final long value;
+
+    public Long(long value) {
+        this.value = value;
+    }
+
+    public static native Long valueOf(long value);
+
+    public static native int bitCount(long i);
+
+    public static native long highestOneBit(long i);
+
+    public static native long lowestOneBit(long i);
+
+    public static native int numberOfLeadingZeros(long i);
+
+    public static native int numberOfTrailingZeros(long i);
+
+    public native int intValue();
+
+    public native long longValue();
+
+    public native float floatValue();
+
+    public native double doubleValue();
+}
@@ -0,0 +1,11 @@
+package java.lang;
+
+public class NegativeArraySizeException extends RuntimeException {
+
+    public NegativeArraySizeException() {
+    }
+
+    public NegativeArraySizeException(String message) {
+        super(message);
+    }
+}
@@ -0,0 +1,14 @@
+package java.lang;
+
+public class Object {
+
+    protected native Object clone() throws CloneNotSupportedException;
+
+    public final native void notify();
+
+    public final native void notifyAll();
+
+    public final native void wait() throws InterruptedException;
+
+    public final native void wait(long timeout) throws InterruptedException;
+}
@@ -0,0 +1,22 @@
+package java.lang;
+
+public class String {
+
+    private final char[] value;
+
+    public String() {
+        this.value = new char[0];
+    }
+
+    public String(char[] value) {
+        this.value = value.clone();
+    }
+
+    public native int length();
+
+    public native char charAt(int index);
+
+    public native boolean equals(Object other);
+
+    public native String intern();
+}
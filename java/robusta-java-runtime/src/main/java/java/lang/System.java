@@ -0,0 +1,26 @@
+package java.lang;
+
+import java.io.PrintStream;
+
+/**
+ * The standard streams, created when the class is initialized by the first access to one of them, and the system
+ * properties, set with -D.
+ */
+public final class System {
+
+    public static final PrintStream out = standardStream(1);
+
+    public static final PrintStream err = standardStream(2);
+
+    private System() {
+    }
+
+    public static native String getProperty(String key);
+
+    public static native String getProperty(String key, String def);
+
+    /**
+     * Creates a stream for one of the process's standard streams, which only java.io can construct itself.
+     */
+    private static native PrintStream standardStream(int fd);
+}
@@ -0,0 +1,17 @@
+package java.lang;
+
+public class Throwable {
+
+    private String detailMessage;
+
+    public Throwable() {
+    }
+
+    public Throwable(String message) {
+        this.detailMessage = message;
+    }
+
+    public String getMessage() {
+        return detailMessage;
+    }
+}
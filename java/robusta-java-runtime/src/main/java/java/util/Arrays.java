@@ -0,0 +1,31 @@
+package java.util;
+
+public final class Arrays {
+
+    private Arrays() {
+    }
+
+    public static native String toString(boolean[] a);
+
+    public static native String toString(byte[] a);
+
+    public static native String toString(char[] a);
+
+    public static native String toString(short[] a);
+
+    public static native String toString(int[] a);
+
+    public static native String toString(Object[] a);
+
+    public static native void fill(boolean[] a, boolean val);
+
+    public static native void fill(byte[] a, byte val);
+
+    public static native void fill(char[] a, char val);
+
+    public static native void fill(short[] a, short val);
+
+    public static native void fill(int[] a, int val);
+
+    public static native void fill(Object[] a, Object val);
+}
@@ -0,0 +1,11 @@
+package java.util;
+
+public final class Objects {
+
+    private Objects() {
+    }
+
+    public static native <T> T requireNonNull(T obj);
+
+    public static native <T> T requireNonNull(T obj, String message);
+}
@@ -0,0 +1,21 @@
+package com.jkitch.robusta.test;
+
+/**
+ * A class, field and method carrying annotations retained for runtime, and one discarded at compile time.
+ */
+@Marker
+@Tag("example")
+public class Annotated {
+
+    @Tag(value = "field", priority = 2)
+    public int count;
+
+    @Marker
+    @Deprecated
+    @SuppressWarnings("unused")
+    public static void run() {
+    }
+
+    public static void plain() {
+    }
+}
@@ -0,0 +1,25 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Anonymous and local classes, whose EnclosingMethod attributes name this class and, when declared in a method
+ * rather than an initializer, that method.
+ */
+public class Anonymous {
+
+    static Object initialized = new Object() {
+    };
+
+    public static Object inMethod() {
+        return new Object() {
+        };
+    }
+
+    public static Object local(int value) {
+        class Local {
+            int value;
+        }
+        Local local = new Local();
+        local.value = value;
+        return local;
+    }
+}
@@ -0,0 +1,32 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Cloning arrays, which copies their elements into a new array.
+ */
+public class ArrayClones {
+
+    public static boolean cloneIsDistinct() {
+        int[] original = {1, 2, 3};
+        int[] clone = original.clone();
+        return clone != original && clone.length == 3 && clone[0] == 1 && clone[1] == 2 && clone[2] == 3;
+    }
+
+    public static int mutateClone() {
+        int[] original = {1, 2, 3};
+        int[] clone = original.clone();
+        clone[0] = 10;
+        return original[0] * 100 + clone[0];
+    }
+
+    public static boolean cloneIsShallow() {
+        Object element = new Object();
+        Object[] original = {element};
+        Object[] clone = original.clone();
+        return clone != original && clone[0] == element;
+    }
+
+    public static Object cloneNull() {
+        int[] array = null;
+        return array.clone();
+    }
+}
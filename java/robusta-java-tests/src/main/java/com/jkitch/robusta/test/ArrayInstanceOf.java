@@ -0,0 +1,42 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Subtype checks where the object or the target type is an array.
+ */
+public class ArrayInstanceOf {
+
+    public static boolean stringArrayIsObjectArray() {
+        Object strings = new String[1];
+        return strings instanceof Object[];
+    }
+
+    public static boolean intArrayIsObjectArray() {
+        Object ints = new int[1];
+        return ints instanceof Object[];
+    }
+
+    public static boolean intArrayIsLongArray() {
+        Object ints = new int[1];
+        return ints instanceof long[];
+    }
+
+    public static boolean stringArrayIsObject() {
+        Object strings = new String[1];
+        return strings instanceof Object;
+    }
+
+    public static boolean nestedArrayIsObjectArray() {
+        Object nested = new String[1][];
+        return nested instanceof Object[][];
+    }
+
+    public static Object[] castStringArray() {
+        Object strings = new String[1];
+        return (Object[]) strings;
+    }
+
+    public static Object[] castIntArray() {
+        Object ints = new int[1];
+        return (Object[]) ints;
+    }
+}
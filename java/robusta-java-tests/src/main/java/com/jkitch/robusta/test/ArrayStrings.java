@@ -0,0 +1,51 @@
+package com.jkitch.robusta.test;
+
+import java.util.Arrays;
+
+/**
+ * Formatting and filling arrays with {@link Arrays}.
+ */
+public class ArrayStrings {
+
+    public static String intArray() {
+        return Arrays.toString(new int[]{1, 2, 3});
+    }
+
+    public static String emptyArray() {
+        return Arrays.toString(new int[0]);
+    }
+
+    public static String nullArray() {
+        return Arrays.toString((int[]) null);
+    }
+
+    public static String charArray() {
+        return Arrays.toString(new char[]{'a', 'b'});
+    }
+
+    public static String booleanArray() {
+        return Arrays.toString(new boolean[]{true, false});
+    }
+
+    public static String objectArray() {
+        return Arrays.toString(new Object[]{"a", null, 42});
+    }
+
+    public static int fillInts() {
+        int[] values = new int[3];
+        Arrays.fill(values, 7);
+        return values[0] + values[1] + values[2];
+    }
+
+    public static String fillBytes() {
+        byte[] values = new byte[2];
+        Arrays.fill(values, (byte) -1);
+        return Arrays.toString(values);
+    }
+
+    public static String fillObjects() {
+        String[] values = new String[2];
+        Arrays.fill(values, "x");
+        return Arrays.toString(values);
+    }
+}
@@ -0,0 +1,17 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Assert statements, which only run when assertions are enabled.
+ */
+public class Assertions {
+
+    public static void main(String[] args) {
+        assert args.length > 0;
+    }
+
+    public static boolean assertionsEnabled() {
+        boolean enabled = false;
+        assert enabled = true;
+        return enabled;
+    }
+}
@@ -0,0 +1,20 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Boxing through valueOf, which shares small values, and unboxing back.
+ */
+public class Boxing {
+
+    public static Integer boxHundred() {
+        return Integer.valueOf(100);
+    }
+
+    public static Integer boxTwoHundred() {
+        return Integer.valueOf(200);
+    }
+
+    public static int unbox() {
+        Object value = Integer.valueOf(200);
+        return ((Number) value).intValue();
+    }
+}
@@ -0,0 +1,73 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Static method calls, returning to the caller with and without a value, and constructor calls.
+ */
+public class Calls {
+
+    static class Empty {
+    }
+
+    static class Point {
+        int x;
+
+        Point(int x) {
+            this.x = x;
+        }
+    }
+
+    public static int callAndReturn() {
+        doNothing();
+        doNothingWith(3L);
+        return identity(identity(5));
+    }
+
+    public static boolean catchFromCallee() {
+        try {
+            dereferenceNull();
+            return false;
+        } catch (NullPointerException e) {
+            return true;
+        }
+    }
+
+    public static int constructEmpty(int count) {
+        int constructed = 0;
+        for (int i = 0; i < count; i++) {
+            new Empty();
+            constructed++;
+        }
+        return constructed;
+    }
+
+    public static int constructPoints(int count) {
+        int total = 0;
+        for (int i = 0; i < count; i++) {
+            total += new Point(i).x;
+        }
+        return total;
+    }
+
+    public static int constructPoint() {
+        return new Point(7).x;
+    }
+
+    public static int add(int left, int right) {
+        return left + right;
+    }
+
+    private static void doNothing() {
+    }
+
+    private static void doNothingWith(long value) {
+    }
+
+    private static int identity(int value) {
+        return value;
+    }
+
+    private static void dereferenceNull() {
+        int[] array = null;
+        int length = array.length;
+    }
+}
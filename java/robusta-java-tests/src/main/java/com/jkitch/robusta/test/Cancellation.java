@@ -0,0 +1,34 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Methods that never return on their own, so run until the runtime's cancellation flag stops them.
+ */
+public class Cancellation {
+
+    static class Spinning {
+        static boolean spinning = true;
+
+        static {
+            while (spinning) {
+                // Spins in the initializer, below whatever triggered it
+            }
+        }
+
+        static void touch() {
+        }
+    }
+
+    public static void throwAndCatchForever() {
+        while (true) {
+            try {
+                throw new RuntimeException();
+            } catch (RuntimeException e) {
+                // Caught every iteration
+            }
+        }
+    }
+
+    public static synchronized void initializeSpinning() {
+        Spinning.touch();
+    }
+}
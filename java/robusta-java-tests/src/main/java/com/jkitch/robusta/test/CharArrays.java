@@ -0,0 +1,24 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Stores to and loads from char arrays, which hold unsigned 16-bit values.
+ */
+public class CharArrays {
+
+    public static char storeAboveByteRange() {
+        char[] chars = new char[1];
+        chars[0] = 200;
+        return chars[0];
+    }
+
+    public static char storeEuroSign() {
+        char[] chars = new char[2];
+        chars[1] = '€';
+        return chars[1];
+    }
+
+    public static char loadOutOfBounds() {
+        char[] chars = new char[1];
+        return chars[1];
+    }
+}
@@ -0,0 +1,15 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Class literals, loaded as Class constants.
+ */
+public class ClassLiterals {
+
+    public static String objectClassName() {
+        return Object.class.getName();
+    }
+
+    public static String intArrayClassName() {
+        return int[].class.getName();
+    }
+}
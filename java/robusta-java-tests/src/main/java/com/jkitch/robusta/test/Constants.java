@@ -0,0 +1,25 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Long, float and double constants small enough to have their own push instructions.
+ */
+public class Constants {
+
+    public static long longOne() {
+        return 1L;
+    }
+
+    public static float floatTwo() {
+        return 2.0f;
+    }
+
+    public static double doubleOne() {
+        return 1.0;
+    }
+
+    public static long longZeroInLocal() {
+        long zero = 0L;
+        int after = 1;
+        return zero;
+    }
+}
@@ -0,0 +1,44 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Conversions between longs and floating point values, read from fields so that javac doesn't fold
+ * them into constants.
+ */
+public class Conversions {
+
+    private static long floatPrecisionLoss = (1L << 24) + 1;
+    private static long floatTie = (1L << 24) + 3;
+    private static long doublePrecisionLoss = (1L << 53) + 1;
+    private static float hugeFloat = 1e30f;
+    private static float nanFloat = Float.NaN;
+    private static double hugeNegativeDouble = -1e300;
+    private static double negativeFraction = -2.9;
+
+    public static float longToFloat() {
+        return floatPrecisionLoss;
+    }
+
+    public static float longToFloatTie() {
+        return floatTie;
+    }
+
+    public static double longToDouble() {
+        return doublePrecisionLoss;
+    }
+
+    public static long hugeFloatToLong() {
+        return (long) hugeFloat;
+    }
+
+    public static long nanToLong() {
+        return (long) nanFloat;
+    }
+
+    public static long hugeNegativeDoubleToLong() {
+        return (long) hugeNegativeDouble;
+    }
+
+    public static long fractionToLong() {
+        return (long) negativeFraction;
+    }
+}
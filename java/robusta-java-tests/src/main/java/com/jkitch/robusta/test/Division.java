@@ -0,0 +1,57 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Int division and remainder, which throw an ArithmeticException for a zero divisor.
+ */
+public class Division {
+
+    private static int divide(int dividend, int divisor) {
+        return dividend / divisor;
+    }
+
+    private static int remainder(int dividend, int divisor) {
+        return dividend % divisor;
+    }
+
+    public static int negativeQuotient() {
+        return divide(-7, 2);
+    }
+
+    public static int negativeRemainder() {
+        return remainder(-7, 2);
+    }
+
+    public static int minValueByMinusOne() {
+        return divide(Integer.MIN_VALUE, -1);
+    }
+
+    public static String divideByZeroMessage() {
+        try {
+            divide(1, 0);
+            return null;
+        } catch (ArithmeticException e) {
+            return e.getMessage();
+        }
+    }
+
+    public static int remainderByZeroCaughtAsRuntimeException() {
+        try {
+            return remainder(1, 0);
+        } catch (RuntimeException e) {
+            return -1;
+        }
+    }
+
+    /**
+     * Throws from the middle of an expression, with the operands already computed for it left on
+     * the operand stack for the handler to discard.
+     */
+    public static int caughtMidExpression() {
+        int base = 100;
+        try {
+            return base + 2 * divide(base, 0);
+        } catch (ArithmeticException e) {
+            return base + 1;
+        }
+    }
+}
@@ -0,0 +1,11 @@
+package com.jkitch.robusta.test;
+
+/**
+ * The smallest possible program: a main method that immediately returns.
+ */
+public class EmptyMain {
+
+    public static void main(String[] args) {
+
+    }
+}
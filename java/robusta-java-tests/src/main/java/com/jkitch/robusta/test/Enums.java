@@ -0,0 +1,32 @@
+package com.jkitch.robusta.test;
+
+/**
+ * A two-constant enum, whose constants are built by its static initializer.
+ */
+public class Enums {
+
+    enum Color {
+        RED,
+        GREEN
+    }
+
+    public static int redOrdinal() {
+        return Color.RED.ordinal();
+    }
+
+    public static int greenOrdinal() {
+        return Color.GREEN.ordinal();
+    }
+
+    public static int valueCount() {
+        return Color.values().length;
+    }
+
+    public static boolean valueOfName() {
+        return Color.valueOf("GREEN") == Color.GREEN;
+    }
+
+    public static Color valueOfMissing() {
+        return Color.valueOf("BLUE");
+    }
+}
@@ -0,0 +1,39 @@
+package com.jkitch.robusta.test;
+
+/**
+ * {@code finally} blocks, which the exception table covers with catch-all handlers.
+ */
+public class Finally {
+
+    private static int finallyCount;
+
+    public static int finallyRunsOnReturn() {
+        finallyCount = 0;
+        try {
+            finallyCount += 10;
+        } finally {
+            finallyCount++;
+        }
+        return finallyCount;
+    }
+
+    public static int finallyRunsOnThrow() {
+        finallyCount = 0;
+        try {
+            throwThroughFinally();
+        } catch (IllegalArgumentException e) {
+            finallyCount += 10;
+        }
+        return finallyCount;
+    }
+
+    private static void throwThroughFinally() {
+        try {
+            throw new IllegalArgumentException();
+        } catch (ArrayIndexOutOfBoundsException e) {
+            finallyCount += 100;
+        } finally {
+            finallyCount++;
+        }
+    }
+}
@@ -0,0 +1,59 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Float and double comparisons, which compile to fcmpl, fcmpg, dcmpl or dcmpg followed by an int branch on the
+ * result. Any comparison with NaN is false.
+ */
+public class FloatComparisons {
+
+    public static int nanEqualsItself() {
+        float f = Float.NaN;
+        if (f == f) {
+            return 1;
+        }
+        return 0;
+    }
+
+    public static int nanNotEqualsItself() {
+        float f = Float.NaN;
+        return f != f ? 1 : 0;
+    }
+
+    public static int nanLessThan() {
+        float f = Float.NaN;
+        return f < 1.0f ? 1 : 0;
+    }
+
+    public static int nanGreaterThan() {
+        float f = Float.NaN;
+        return f > 1.0f ? 1 : 0;
+    }
+
+    public static int equalFloats() {
+        float f = 1.5f;
+        float g = 1.5f;
+        return f == g ? 1 : 0;
+    }
+
+    public static int lessFloat() {
+        float f = -2.5f;
+        float g = 1.5f;
+        return f < g ? 1 : 0;
+    }
+
+    public static int doubleNanEqualsItself() {
+        double d = Double.NaN;
+        return d == d ? 1 : 0;
+    }
+
+    public static int doubleNanGreaterOrEqual() {
+        double d = Double.NaN;
+        return d >= 0.5 ? 1 : 0;
+    }
+
+    public static int lessDouble() {
+        double d = 0.25;
+        double e = 0.5;
+        return d < e ? 1 : 0;
+    }
+}
@@ -0,0 +1,24 @@
+package com.jkitch.robusta.test;
+
+/**
+ * A builder whose methods return this, for calls to chain on the same object.
+ */
+public class Fluent {
+
+    private int total;
+
+    public static Fluent create() {
+        return new Fluent();
+    }
+
+    public Fluent add(int value) {
+        total += value;
+        return this;
+    }
+
+    public static int chainedTotal() {
+        Fluent builder = new Fluent();
+        Fluent chained = builder.add(1).add(2).add(3);
+        return chained == builder ? builder.total : -1;
+    }
+}
@@ -0,0 +1,20 @@
+package com.jkitch.robusta.test;
+
+/**
+ * A generic class, field and method, whose Signature attributes keep the type parameters their
+ * erased descriptors drop.
+ */
+public class Generics<T extends Comparable<T>> {
+
+    T value;
+
+    int count;
+
+    public static <E> E identity(E value) {
+        return value;
+    }
+
+    public static int plain(int value) {
+        return value;
+    }
+}
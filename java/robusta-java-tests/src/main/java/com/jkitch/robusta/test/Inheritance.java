@@ -0,0 +1,47 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Instance fields declared by superclasses, inherited and hidden.
+ */
+public class Inheritance {
+
+    static class Counter {
+        int count;
+    }
+
+    static class NamedCounter extends Counter {
+        String name;
+
+        void bump() {
+            count++;
+        }
+    }
+
+    static class Base {
+        int value = 1;
+
+        int baseValue() {
+            return value;
+        }
+    }
+
+    static class Hiding extends Base {
+        int value = 2;
+    }
+
+    public static NamedCounter create() {
+        return new NamedCounter();
+    }
+
+    public static int inheritedField() {
+        NamedCounter counter = new NamedCounter();
+        counter.bump();
+        counter.bump();
+        return counter.count;
+    }
+
+    public static int hiddenField() {
+        Hiding hiding = new Hiding();
+        return hiding.value * 10 + hiding.baseValue();
+    }
+}
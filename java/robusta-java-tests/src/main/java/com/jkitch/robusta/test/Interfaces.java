@@ -0,0 +1,58 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Type checks against interfaces, which may be implemented by a superclass or extended by another
+ * interface.
+ */
+public class Interfaces {
+
+    interface Sized extends Comparable<Sized> {
+    }
+
+    static class Base implements Comparable<Base> {
+        @Override
+        public int compareTo(Base other) {
+            return 0;
+        }
+    }
+
+    static class Derived extends Base {
+    }
+
+    static class Box implements Sized {
+        @Override
+        public int compareTo(Sized other) {
+            return 0;
+        }
+    }
+
+    public static boolean directInstanceOf() {
+        Object value = new Base();
+        return value instanceof Comparable;
+    }
+
+    public static boolean inheritedInstanceOf() {
+        Object value = new Derived();
+        return value instanceof Comparable;
+    }
+
+    public static boolean superInterfaceInstanceOf() {
+        Object value = new Box();
+        return value instanceof Comparable && value instanceof Sized;
+    }
+
+    public static boolean notInstanceOf() {
+        Object value = new Derived();
+        return value instanceof Sized;
+    }
+
+    public static Comparable<?> castInherited() {
+        Object value = new Derived();
+        return (Comparable<?>) value;
+    }
+
+    public static Sized castNotImplemented() {
+        Object value = new Derived();
+        return (Sized) value;
+    }
+}
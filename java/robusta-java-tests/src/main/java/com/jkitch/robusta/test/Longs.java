@@ -0,0 +1,44 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Longs returned from calls, passed to them and kept in local variables, where each takes two slots.
+ */
+public class Longs {
+
+    public static long storeAndLoad() {
+        long value = fortyBits();
+        int after = 7;
+        long copy = value;
+        return copy;
+    }
+
+    public static int intAfterLong() {
+        long value = fortyBits();
+        int after = 7;
+        long copy = value;
+        return after;
+    }
+
+    public static int intAfterLongParameter() {
+        keepLast(1, fortyBits(), 3);
+        return last;
+    }
+
+    public static long longParameter() {
+        return middle(1, fortyBits(), 3);
+    }
+
+    private static int last;
+
+    private static void keepLast(int first, long middle, int last) {
+        Longs.last = last;
+    }
+
+    private static long middle(int first, long middle, int last) {
+        return middle;
+    }
+
+    private static long fortyBits() {
+        return 1L << 40;
+    }
+}
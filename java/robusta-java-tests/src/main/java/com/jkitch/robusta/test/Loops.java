@@ -0,0 +1,19 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Arithmetic in a loop, for benchmarking the interpreter.
+ */
+public class Loops {
+
+    public static int sumOfSquaresToTen() {
+        return sumOfSquares(10);
+    }
+
+    public static int sumOfSquares(int count) {
+        int sum = 0;
+        for (int i = 0; i < count; i++) {
+            sum += i * i;
+        }
+        return sum;
+    }
+}
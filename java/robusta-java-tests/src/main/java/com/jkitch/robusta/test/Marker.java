@@ -0,0 +1,11 @@
+package com.jkitch.robusta.test;
+
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+/**
+ * An annotation with no elements, retained for runtime.
+ */
+@Retention(RetentionPolicy.RUNTIME)
+public @interface Marker {
+}
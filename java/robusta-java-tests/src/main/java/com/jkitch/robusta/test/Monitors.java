@@ -0,0 +1,90 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Object monitors, which must be held to notify or wait, and are released however a synchronized
+ * block or method completes.
+ */
+public class Monitors {
+
+    private synchronized void throwSynchronized() {
+        throw new RuntimeException();
+    }
+
+    private synchronized void notifySynchronized() {
+        notify();
+    }
+
+    private static synchronized void notifyStaticSynchronized() {
+        Monitors.class.notify();
+    }
+
+    private static boolean holdsMonitor(Object lock) {
+        try {
+            lock.notify();
+            return true;
+        } catch (IllegalMonitorStateException e) {
+            return false;
+        }
+    }
+
+    public static boolean notifyOutsideSynchronized() {
+        Object lock = new Object();
+        try {
+            lock.notify();
+            return false;
+        } catch (IllegalMonitorStateException e) {
+            return true;
+        }
+    }
+
+    public static boolean notifyInsideSynchronized() {
+        Object lock = new Object();
+        synchronized (lock) {
+            lock.notify();
+            lock.notifyAll();
+        }
+        return true;
+    }
+
+    public static boolean waitInsideSynchronized() throws InterruptedException {
+        Object lock = new Object();
+        synchronized (lock) {
+            lock.wait();
+        }
+        return true;
+    }
+
+    public static boolean notifyInsideSynchronizedMethods() {
+        new Monitors().notifySynchronized();
+        notifyStaticSynchronized();
+        return true;
+    }
+
+    public static boolean releasedAfterSynchronizedMethod() {
+        Monitors monitors = new Monitors();
+        monitors.notifySynchronized();
+        return !holdsMonitor(monitors);
+    }
+
+    public static boolean releasedAfterThrowFromSynchronizedMethod() {
+        Monitors monitors = new Monitors();
+        try {
+            monitors.throwSynchronized();
+        } catch (RuntimeException e) {
+            // Expected
+        }
+        return !holdsMonitor(monitors);
+    }
+
+    public static boolean releasedAfterThrowFromSynchronizedBlock() {
+        Object lock = new Object();
+        try {
+            synchronized (lock) {
+                throw new RuntimeException();
+            }
+        } catch (RuntimeException e) {
+            // Expected
+        }
+        return !holdsMonitor(lock);
+    }
+}
@@ -0,0 +1,40 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Stores to and loads from byte, short, char and boolean arrays, which truncate stored ints and
+ * extend loaded elements back to ints.
+ */
+public class NarrowArrays {
+
+    public static byte storeOverflowingByte() {
+        int value = 300;
+        byte[] bytes = new byte[1];
+        bytes[0] = (byte) value;
+        return bytes[0];
+    }
+
+    public static byte storeNegativeByte() {
+        byte[] bytes = new byte[1];
+        bytes[0] = -100;
+        return bytes[0];
+    }
+
+    public static short storeNegativeShort() {
+        short[] shorts = new short[1];
+        shorts[0] = -12345;
+        return shorts[0];
+    }
+
+    public static char storeNegativeChar() {
+        int value = -1;
+        char[] chars = new char[1];
+        chars[0] = (char) value;
+        return chars[0];
+    }
+
+    public static boolean storeBoolean() {
+        boolean[] booleans = new boolean[1];
+        booleans[0] = true;
+        return booleans[0];
+    }
+}
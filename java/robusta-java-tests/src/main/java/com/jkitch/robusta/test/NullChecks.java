@@ -0,0 +1,22 @@
+package com.jkitch.robusta.test;
+
+import java.util.Objects;
+
+/**
+ * Null checks through {@link Objects#requireNonNull}.
+ */
+public class NullChecks {
+
+    public static boolean passThrough() {
+        String value = "value";
+        return Objects.requireNonNull(value) == value && Objects.requireNonNull(value, "value is null") == value;
+    }
+
+    public static Object requireNull() {
+        return Objects.requireNonNull(null);
+    }
+
+    public static Object requireNullWithMessage() {
+        return Objects.requireNonNull(null, "value is null");
+    }
+}
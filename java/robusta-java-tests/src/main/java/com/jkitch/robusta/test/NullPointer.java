@@ -0,0 +1,22 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Dereferences of null that should raise a catchable NullPointerException.
+ */
+public class NullPointer {
+
+    public static boolean catchArrayLength() {
+        int[] array = null;
+        try {
+            int length = array.length;
+            return false;
+        } catch (NullPointerException e) {
+            return true;
+        }
+    }
+
+    public static int uncaughtArrayLength() {
+        int[] array = null;
+        return array.length;
+    }
+}
@@ -0,0 +1,71 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Assignments whose value is used again, which javac compiles to the dup_x instructions, with the
+ * value a newly constructed object, and their dup2 forms, which copy a long or two ints.
+ */
+public class StackOps {
+
+    static class Node {
+        final int value;
+
+        Node(int value) {
+            this.value = value;
+        }
+    }
+
+    private Node last;
+
+    private long total;
+
+    private Node remember(int value) {
+        return last = new Node(value);
+    }
+
+    public static int fieldAssignment() {
+        StackOps ops = new StackOps();
+        Node node = ops.remember(7);
+        return node == ops.last ? node.value : -1;
+    }
+
+    public static int arrayAssignment() {
+        Node[] nodes = new Node[2];
+        Node node = nodes[1] = new Node(9);
+        return node == nodes[1] && nodes[0] == null ? node.value : -1;
+    }
+
+    public static int intArrayAssignment() {
+        int[] values = new int[1];
+        int value = values[0] = 5;
+        return value * 10 + values[0];
+    }
+
+    private static long fortyBits() {
+        return 1L << 40;
+    }
+
+    private long setTotal(long value) {
+        return total = value;
+    }
+
+    public static int discardLong() {
+        fortyBits();
+        return 3;
+    }
+
+    public static long chainedLongLocals() {
+        long first;
+        long second = first = fortyBits();
+        return second;
+    }
+
+    public static long longFieldAssignment() {
+        return new StackOps().setTotal(fortyBits());
+    }
+
+    public static int incrementInArray() {
+        int[] values = new int[] {4};
+        values[0]++;
+        return values[0];
+    }
+}
@@ -0,0 +1,38 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Static fields inherited by a subclass, accessed through references naming the subclass.
+ */
+public class Statics {
+
+    static boolean subInitialized;
+
+    static class Super {
+        static int shared;
+
+        static int read() {
+            return shared;
+        }
+    }
+
+    static class Sub extends Super {
+        static {
+            subInitialized = true;
+        }
+    }
+
+    public static int inheritedStatic() {
+        Sub.shared = 5;
+        return Super.read();
+    }
+
+    public static int readThroughSubclass() {
+        Super.shared = 7;
+        return Sub.shared;
+    }
+
+    public static boolean subclassInitializedByInheritedStatic() {
+        Sub.shared = 1;
+        return subInitialized;
+    }
+}
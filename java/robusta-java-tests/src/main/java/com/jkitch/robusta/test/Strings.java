@@ -0,0 +1,53 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Identity of string literals, which are interned, and of strings built at runtime, and the
+ * String methods reading their chars.
+ */
+public class Strings {
+
+    public static boolean literalsShared() {
+        return literal() == "robusta";
+    }
+
+    public static boolean builtDistinct() {
+        return build() != "robusta";
+    }
+
+    public static boolean internedBuiltShared() {
+        return build().intern() == "robusta";
+    }
+
+    public static int literalLength() {
+        return "robusta".length();
+    }
+
+    public static char literalCharAt() {
+        return "robusta".charAt(2);
+    }
+
+    public static String charAtOutOfRange() {
+        try {
+            "robusta".charAt(7);
+            return null;
+        } catch (StringIndexOutOfBoundsException e) {
+            return e.getMessage();
+        }
+    }
+
+    public static boolean equalToBuilt() {
+        return build().equals("robusta");
+    }
+
+    public static boolean equalToOtherValues() {
+        return "robusta".equals("robust") || "robusta".equals(new Object()) || "robusta".equals(null);
+    }
+
+    private static String literal() {
+        return "robusta";
+    }
+
+    private static String build() {
+        return new String(new char[]{'r', 'o', 'b', 'u', 's', 't', 'a'});
+    }
+}
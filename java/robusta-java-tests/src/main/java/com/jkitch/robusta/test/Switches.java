@@ -0,0 +1,42 @@
+package com.jkitch.robusta.test;
+
+/**
+ * Dense switches, which compile to tableswitch, and sparse ones, which compile to lookupswitch.
+ */
+public class Switches {
+
+    public static int tableMatch() {
+        return table(3);
+    }
+
+    public static int tableDefault() {
+        return table(7);
+    }
+
+    public static int lookupMatch() {
+        return lookup(-40);
+    }
+
+    public static int lookupDefault() {
+        return lookup(41);
+    }
+
+    private static int table(int value) {
+        switch (value) {
+            case 1: return 10;
+            case 2: return 20;
+            case 3: return 30;
+            case 4: return 40;
+            default: return -1;
+        }
+    }
+
+    private static int lookup(int value) {
+        switch (value) {
+            case -40: return 1;
+            case 40: return 2;
+            case 4000: return 3;
+            default: return -1;
+        }
+    }
+}
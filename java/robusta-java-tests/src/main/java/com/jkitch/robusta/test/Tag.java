@@ -0,0 +1,14 @@
+package com.jkitch.robusta.test;
+
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+/**
+ * An annotation with a string element, retained for runtime.
+ */
+@Retention(RetentionPolicy.RUNTIME)
+public @interface Tag {
+    String value();
+
+    int priority() default 0;
+}
@@ -0,0 +1,15 @@
+package com.jkitch.robusta.test;
+
+import java.io.IOException;
+
+/**
+ * Methods declaring checked exceptions in their Exceptions attribute.
+ */
+public class Throws {
+
+    public static void readNothing() throws IOException {
+    }
+
+    public static void throwsNothing() {
+    }
+}
@@ -0,0 +1,205 @@
+//! `--check`: loading every class on a class path to report those Robusta can't load yet, and
+//! `--validate-only`: checking that a class and those it references load, without running it, and
+//! `--eager`: resolving the references of a class and those it uses before running it.
+
+use crate::class_file::{is_class_file_name, ClassFile, Const};
+use crate::descriptor::FieldType;
+#[cfg(feature = "jar")]
+use crate::jar::Jar;
+use crate::runtime::{sorted_dir_entries, Runtime, RuntimeClass};
+use crate::string_concat::STRING_CONCAT_FACTORY;
+use anyhow::{anyhow, Context, Error};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// A class file's path within its class path entry, and the result of parsing it
+type ReadClass = (String, Result<ClassFile, Error>);
+
+/// Loads every class of the class path entries, carrying on past those that fail, and prints each
+/// failure followed by a summary counting them by category. Fails if any class did.
+pub fn check_class_path(runtime: &mut Runtime, entries: &[PathBuf]) -> Result<(), Error> {
+    let mut loaded = 0;
+    let mut failures = BTreeMap::<String, usize>::new();
+    for entry in entries {
+        for (file, class_file) in read_entry(runtime, entry)? {
+            match class_file.and_then(|class_file| runtime.insert_class(class_file, &entry.display().to_string())) {
+                Ok(_) => loaded += 1,
+                Err(error) => {
+                    println!("FAILED {} ({}): {:#}", file, entry.display(), error);
+                    *failures.entry(category(&error)).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    let failed = failures.values().sum::<usize>();
+    println!("Checked {} classes: {} loaded, {} failed", loaded + failed, loaded, failed);
+    for (category, count) in &failures {
+        println!("{:>6} {}", count, category);
+    }
+    if failed > 0 {
+        Err(anyhow!("{} of {} classes failed to load", failed, loaded + failed))?
+    }
+    Ok(())
+}
+
+/// Checks that the class, which has been loaded and so parsed and version checked, can link: that
+/// every class it references is loaded too, and so on for those. Each of their methods gets the
+/// checks of its jumps and switches `-Xverify` makes before running it. Prints the number of
+/// classes checked.
+pub fn validate_class(runtime: &Runtime, class_name: &str) -> Result<(), Error> {
+    let classes = referenced_classes(runtime, class_name)?;
+    for class in &classes {
+        for method in &class.methods {
+            method.verify_jumps().with_context(|| format!("verifying {}", class.this_class.replace('/', ".")))?;
+        }
+    }
+    println!("Validated {} classes", classes.len());
+    Ok(())
+}
+
+/// Resolves every field and method reference of the class and those it references, and so on for
+/// those, as `--eager` does before running the main class, failing on the first that doesn't
+/// resolve rather than when an instruction using it runs.
+pub fn link_classes(runtime: &Runtime, class_name: &str) -> Result<(), Error> {
+    for class in referenced_classes(runtime, class_name)? {
+        link_class(runtime, &class)
+            .with_context(|| format!("linking {}", class.this_class.replace('/', ".")))?;
+    }
+    Ok(())
+}
+
+/// The class and every class it references, and so on for those, failing on the first that isn't loaded.
+fn referenced_classes(runtime: &Runtime, class_name: &str) -> Result<Vec<Rc<RuntimeClass>>, Error> {
+    let mut validated = HashSet::new();
+    let mut classes = vec![];
+    let mut pending = vec![(class_name.to_string(), None)];
+    while let Some((class_name, referrer)) = pending.pop() {
+        if !validated.insert(class_name.clone()) {
+            continue;
+        }
+        let class = match (runtime.get_class(&class_name), referrer) {
+            (Ok(class), _) => class,
+            (Err(_), Some(referrer)) => Err(anyhow!("java.lang.NoClassDefFoundError: {} (referenced by {})",
+                class_name.replace('/', "."), referrer))?,
+            (Err(error), None) => Err(error)?,
+        };
+        for (_, constant) in class.const_pool.iter() {
+            if let Const::Class(class_const) = constant {
+                let name = &class.const_pool.get_utf8(class_const.name_idx)?.bytes;
+                if let Some(referenced) = referenced_class(name)? {
+                    pending.push((referenced, Some(class.this_class.replace('/', "."))));
+                }
+            }
+        }
+        classes.push(class.clone());
+    }
+    Ok(classes)
+}
+
+/// Resolves the field and method references of a class whose referenced classes are all loaded.
+fn link_class(runtime: &Runtime, class: &RuntimeClass) -> Result<(), Error> {
+    let const_pool = &class.const_pool;
+    for (_, constant) in const_pool.iter() {
+        match constant {
+            Const::Fieldref(fieldref) => {
+                let class_name = const_pool.resolve_class_name(fieldref.class_idx)?;
+                let name_and_type = const_pool.get_name_and_type(fieldref.name_and_type_idx)?;
+                let name = &const_pool.get_utf8(name_and_type.name_idx)?.bytes;
+                if runtime.resolve_field(class_name, name)?.is_none() {
+                    Err(anyhow!("java.lang.NoSuchFieldError: {}.{}", class_name.replace('/', "."), name))?
+                }
+            }
+            Const::Methodref(methodref) => {
+                let class_name = const_pool.resolve_class_name(methodref.class_idx)?;
+                // The bootstrap methods of string concatenation are implemented by the interpreter
+                if class_name == STRING_CONCAT_FACTORY {
+                    continue;
+                }
+                // Arrays have Object's methods
+                let class_name = if class_name.starts_with('[') { "java/lang/Object" } else { class_name };
+                let name_and_type = const_pool.get_name_and_type(methodref.name_and_type_idx)?;
+                runtime.resolve_method(class_name, &const_pool.get_utf8(name_and_type.name_idx)?.bytes,
+                    &const_pool.get_utf8(name_and_type.descriptor_idx)?.bytes)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// The class that a class constant names, which for an array class is that of its elements, if any.
+/// String concatenation's bootstrap class isn't loaded, as the interpreter implements it.
+fn referenced_class(name: &str) -> Result<Option<String>, Error> {
+    if name == STRING_CONCAT_FACTORY {
+        return Ok(None);
+    }
+    if !name.starts_with('[') {
+        return Ok(Some(name.to_string()));
+    }
+    let mut field_type = FieldType::parse(name)?;
+    while let FieldType::Array(component) = field_type {
+        field_type = *component;
+    }
+    match field_type {
+        FieldType::Object(class_name) => Ok(Some(class_name)),
+        _ => Ok(None),
+    }
+}
+
+/// Reads each class file of a class path entry, as loading it would, with the checks verifying
+/// makes of the class file if the runtime is. Jars and jmods are skipped without the `jar` feature,
+/// as they are when loading.
+fn read_entry(runtime: &Runtime, entry: &Path) -> Result<Vec<ReadClass>, Error> {
+    if entry.is_dir() {
+        let mut class_files = vec![];
+        read_dir(runtime, entry, entry, &mut class_files)?;
+        return Ok(class_files);
+    }
+    match entry.extension() {
+        #[cfg(feature = "jar")]
+        Some(extension) if extension.eq("jar") => Ok(read_jar(runtime, &mut Jar::open(entry)?)),
+        #[cfg(feature = "jar")]
+        Some(extension) if extension.eq("jmod") => Ok(read_jar(runtime, &mut Jar::open_jmod(entry)?)),
+        #[cfg(not(feature = "jar"))]
+        Some(extension) if extension.eq("jar") || extension.eq("jmod") => Ok(vec![]),
+        _ => Err(anyhow!("unsupported class path entry {}", entry.display())),
+    }
+}
+
+/// Reads each class file of a jar or jmod, which leaves out a jmod's `module-info.class`.
+#[cfg(feature = "jar")]
+fn read_jar(runtime: &Runtime, jar: &mut Jar) -> Vec<ReadClass> {
+    jar.class_file_names().into_iter()
+        .map(|file| {
+            let class_file = jar.class_file(&file).and_then(|mut class_file| runtime.read_class(&mut class_file));
+            (file, class_file)
+        })
+        .collect()
+}
+
+/// Reads each class file in a directory tree, skipping a `module-info.class` as loading does.
+fn read_dir(runtime: &Runtime, root: &Path, dir: &Path, class_files: &mut Vec<ReadClass>) -> Result<(), Error> {
+    for path in sorted_dir_entries(dir)? {
+        if path.is_dir() {
+            read_dir(runtime, root, &path, class_files)?;
+        } else if is_class_file_name(&path.to_string_lossy()) && !path.ends_with("module-info.class") {
+            let class_file = File::open(&path).map_err(Error::from)
+                .and_then(|mut file| runtime.read_class(&mut file));
+            class_files.push((path.strip_prefix(root)?.display().to_string(), class_file));
+        }
+    }
+    Ok(())
+}
+
+/// Groups failures by what went wrong rather than where: the Java error a message names, if any, or
+/// else the underlying cause, such as a truncated read or an unimplemented constant pool tag.
+fn category(error: &Error) -> String {
+    let cause = error.root_cause().to_string();
+    match cause.split_once(':') {
+        Some((error_class, _)) if error_class.starts_with("java.") => error_class.to_string(),
+        _ => cause,
+    }
+}
@@ -1,33 +1,42 @@
-use anyhow::{anyhow, Error};
+//! Parsing of class files, independent of loading them into a runtime. Tools that only want to
+//! inspect a class can read one with [`ClassFile::read_from`] and look up its constant pool entries
+//! through [`ConstPool`].
+
+use crate::instruction::Instructions;
+use anyhow::{anyhow, Context, Error};
 use std::io::Read;
 
+/// Whether a file or jar entry name is a class file's, ending in `.class` in any case. Names from
+/// case-insensitive filesystems needn't match the case of the class inside, which is named by its
+/// own `this_class` rather than its path.
+pub(crate) fn is_class_file_name(name: &str) -> bool {
+    name.get(name.len().saturating_sub(".class".len())..).is_some_and(|extension| extension.eq_ignore_ascii_case(".class"))
+}
+
 impl ClassFile {
+    /// Parses a whole class file, such as a `.class` file on disk or an entry of a jar.
     pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, Error> {
-        // Skip unused fields
-        read_length(reader, 8)?;
+        let reader = &mut CountingReader::new(reader);
+        read_length(reader, 4)?; // magic
+        let minor_version = read_u16(reader)?;
+        let major_version = read_u16(reader)?;
 
         let const_pool = ConstPool::from_reader(reader)?;
 
-        read_u16(reader)?; // access flags
+        let access_flags = read_u16(reader)?;
         let this_class = read_u16(reader)?;
-        read_u16(reader)?; // super class
+        let super_class = read_u16(reader)?;
 
         let interface_count = read_u16(reader)?;
+        let mut interfaces = Vec::with_capacity(interface_count as usize);
         for _ in 0..interface_count {
-            read_u16(reader)?; // interface
+            interfaces.push(read_u16(reader)?);
         }
 
         let field_count = read_u16(reader)?;
+        let mut fields = Vec::with_capacity(field_count as usize);
         for _ in 0..field_count {
-            read_u16(reader)?; // access flags
-            read_u16(reader)?; // name index
-            read_u16(reader)?; // descriptor index
-            let attribute_count = read_u16(reader)?; // attribute count
-            for _ in 0..attribute_count {
-                read_u16(reader)?; // name index
-                let length = read_u32(reader)?;
-                read_length(reader, length as usize)?;
-            }
+            fields.push(Field::from_reader(reader)?);
         }
 
         let method_count = read_u16(reader)?;
@@ -42,38 +51,142 @@ impl ClassFile {
             attributes.push(Attribute::from_reader(reader)?);
         }
 
+        let mut inner_classes = vec![];
+        let mut enclosing_method = None;
+        let mut bootstrap_methods = vec![];
+        let mut record_components = vec![];
+        for attribute in &attributes {
+            match const_pool.get_utf8(attribute.name_idx)?.bytes.as_str() {
+                "InnerClasses" => {
+                    inner_classes = InnerClassEntry::read_all(&mut CountingReader::within(attribute.info.as_slice(), "the InnerClasses attribute"))
+                        .context("failed to read InnerClasses attribute")?;
+                }
+                "EnclosingMethod" => {
+                    enclosing_method = Some(EnclosingMethod::from_reader(&mut CountingReader::within(attribute.info.as_slice(), "the EnclosingMethod attribute"))
+                        .context("failed to read EnclosingMethod attribute")?);
+                }
+                "BootstrapMethods" => {
+                    bootstrap_methods = BootstrapMethod::read_all(&mut CountingReader::within(attribute.info.as_slice(), "the BootstrapMethods attribute"))
+                        .context("failed to read BootstrapMethods attribute")?;
+                }
+                "Record" => {
+                    record_components = RecordComponent::read_all(&mut CountingReader::within(attribute.info.as_slice(), "the Record attribute"))
+                        .context("failed to read Record attribute")?;
+                }
+                _ => {}
+            }
+        }
+
         Ok(ClassFile {
+            minor_version,
+            major_version,
             const_pool,
+            access_flags,
             this_class,
+            super_class,
+            interfaces,
+            fields,
             methods,
+            inner_classes,
+            enclosing_method,
+            bootstrap_methods,
+            record_components,
             _attributes: attributes,
         })
     }
+
+    /// Parses a whole class file as [`ClassFile::read_from`] does, then checks that nothing follows
+    /// it, failing with a ClassFormatError for trailing bytes that padding or corruption left.
+    pub fn read_exactly_from<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let class_file = Self::read_from(reader)?;
+        let trailing = std::io::copy(reader, &mut std::io::sink())?;
+        if trailing > 0 {
+            Err(anyhow!("java.lang.ClassFormatError: Extra bytes at the end of class file, {} after the last attribute", trailing))?
+        }
+        Ok(class_file)
+    }
+
+    /// The class's generic signature, such as `<T:Ljava/lang/Object;>Ljava/lang/Object;`, if its
+    /// Signature attribute gives one.
+    pub fn signature(&self) -> Result<Option<&str>, Error> {
+        signature(&self._attributes, &self.const_pool)
+    }
+
+    /// The annotations of the class retained for runtime, from its RuntimeVisibleAnnotations attribute.
+    pub fn annotations(&self) -> Result<Vec<Annotation>, Error> {
+        annotations(&self._attributes, &self.const_pool)
+    }
 }
 
 #[derive(Debug)]
 pub struct ClassFile {
+    pub minor_version: u16,
+    /// The class file format version, 52 for Java 8 and one more for each release since
+    pub major_version: u16,
     pub const_pool: ConstPool,
+    pub access_flags: u16,
     pub this_class: u16,
+    pub super_class: u16,
+    /// The class constants of the interfaces the class directly implements, or an interface extends
+    pub interfaces: Vec<u16>,
+    pub fields: Vec<Field>,
     pub methods: Vec<Method>,
+    /// The nested classes this class refers to, from its InnerClasses attribute
+    pub inner_classes: Vec<InnerClassEntry>,
+    /// For a local or anonymous class, the class and method it's declared in, from its EnclosingMethod attribute
+    pub enclosing_method: Option<EnclosingMethod>,
+    /// The bootstrap methods of the class's invokedynamic call sites, from its BootstrapMethods attribute
+    pub bootstrap_methods: Vec<BootstrapMethod>,
+    /// For a record class, its components in declaration order, from its Record attribute
+    pub record_components: Vec<RecordComponent>,
     pub _attributes: Vec<Attribute>,
 }
 
-#[derive(Debug)]
+/// The constant pool of a class, indexed from 1 as in the class file. Each accessor fails if the
+/// index is out of range or holds a different kind of constant.
+#[derive(Debug, Default)]
 pub struct ConstPool {
     consts: Vec<Const>,
 }
 
 impl ConstPool {
-    fn from_reader<R: Read>(reader: &mut R) -> Result<Self, Error> {
+    fn from_reader<R: Read>(reader: &mut CountingReader<R>) -> Result<Self, Error> {
         let const_pool_count = read_u16(reader)?;
+        if const_pool_count == 0 {
+            // The count is one more than the number of entries, so can never be zero
+            Err(anyhow!("java.lang.ClassFormatError: invalid constant pool count 0"))?
+        }
         let mut pool = Vec::with_capacity(const_pool_count as usize - 1);
-        for _ in 1..const_pool_count {
-            pool.push(read_const(reader)?);
+        while pool.len() + 1 < const_pool_count as usize {
+            let const_item = read_const(reader)?;
+            let wide = matches!(const_item, Const::Long(_) | Const::Double(_));
+            pool.push(const_item);
+            if wide {
+                // Longs and doubles take up two entries, the second of which is unusable
+                pool.push(Const::Padding);
+            }
         }
         Ok(ConstPool { consts: pool })
     }
 
+    /// Iterates over the pool's constants with their indexes, skipping the unusable entry after each
+    /// long and double.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &Const)> {
+        self.consts.iter().zip(1..)
+            .filter(|(const_item, _)| !matches!(const_item, Const::Padding))
+            .map(|(const_item, idx)| (idx, const_item))
+    }
+
+    /// Adds a utf8 constant to the end of the pool, such as for a transformer to rename a member
+    /// with, and gives its index.
+    pub fn push_utf8(&mut self, value: &str) -> Result<u16, Error> {
+        if self.consts.len() + 1 >= u16::MAX as usize {
+            Err(anyhow!("java.lang.ClassFormatError: constant pool full"))?
+        }
+        self.consts.push(Const::Utf8(Utf8 { bytes: value.to_string() }));
+        Ok(self.consts.len() as u16)
+    }
+
     pub fn get_utf8(&self, idx: u16) -> Result<&Utf8, Error> {
         let const_item = self.get_const(idx)?;
         match const_item {
@@ -90,16 +203,80 @@ impl ConstPool {
         }
     }
 
-    fn get_const(&self, idx: u16) -> Result<&Const, Error> {
-        self.consts.get(idx as usize - 1).ok_or(anyhow!("const pool does not have an item at index {}", idx))
+    /// The internal name of the class constant at `idx`, such as `java/lang/String`, or a descriptor
+    /// such as `[I` for an array class.
+    pub fn resolve_class_name(&self, idx: u16) -> Result<&str, Error> {
+        Ok(&self.get_utf8(self.get_class(idx)?.name_idx)?.bytes)
+    }
+
+    pub fn get_fieldref(&self, idx: u16) -> Result<&Fieldref, Error> {
+        let const_item = self.get_const(idx)?;
+        match const_item {
+            Const::Fieldref(fieldref) => Ok(fieldref),
+            _ => Err(anyhow!("expected fieldref, got {:?}", const_item))
+        }
+    }
+
+    pub fn get_methodref(&self, idx: u16) -> Result<&Methodref, Error> {
+        let const_item = self.get_const(idx)?;
+        match const_item {
+            Const::Methodref(methodref) => Ok(methodref),
+            _ => Err(anyhow!("expected methodref, got {:?}", const_item))
+        }
+    }
+
+    pub fn get_method_handle(&self, idx: u16) -> Result<&MethodHandle, Error> {
+        let const_item = self.get_const(idx)?;
+        match const_item {
+            Const::MethodHandle(method_handle) => Ok(method_handle),
+            _ => Err(anyhow!("expected method handle, got {:?}", const_item))
+        }
+    }
+
+    pub fn get_invoke_dynamic(&self, idx: u16) -> Result<&InvokeDynamic, Error> {
+        let const_item = self.get_const(idx)?;
+        match const_item {
+            Const::InvokeDynamic(invoke_dynamic) => Ok(invoke_dynamic),
+            _ => Err(anyhow!("expected invoke dynamic, got {:?}", const_item))
+        }
+    }
+
+    pub fn get_name_and_type(&self, idx: u16) -> Result<&NameAndType, Error> {
+        let const_item = self.get_const(idx)?;
+        match const_item {
+            Const::NameAndType(name_and_type) => Ok(name_and_type),
+            _ => Err(anyhow!("expected name and type, got {:?}", const_item))
+        }
+    }
+
+    pub fn get_const(&self, idx: u16) -> Result<&Const, Error> {
+        // Index 0 is reserved as an invalid index
+        match idx.checked_sub(1).and_then(|idx| self.consts.get(idx as usize)) {
+            Some(Const::Padding) => Err(anyhow!("const pool index {} refers to the second slot of a long/double constant", idx)),
+            Some(const_item) => Ok(const_item),
+            None => Err(anyhow!("const pool does not have an item at index {}", idx)),
+        }
     }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum Const {
     Utf8(Utf8),
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
     Class(Class),
+    String(StringConst),
+    Fieldref(Fieldref),
+    Methodref(Methodref),
+    NameAndType(NameAndType),
+    MethodHandle(MethodHandle),
+    MethodType(MethodType),
+    InvokeDynamic(InvokeDynamic),
     Unimplemented,
+    /// The unusable entry following each long and double, which take up two
+    Padding,
 }
 
 #[derive(Debug, PartialEq)]
@@ -112,16 +289,122 @@ pub struct Class {
     pub name_idx: u16,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct StringConst {
+    pub string_idx: u16,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Fieldref {
+    pub class_idx: u16,
+    pub name_and_type_idx: u16,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Methodref {
+    pub class_idx: u16,
+    pub name_and_type_idx: u16,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NameAndType {
+    pub name_idx: u16,
+    pub descriptor_idx: u16,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MethodHandle {
+    /// What the handle does with the member it references, such as 6 (`REF_invokeStatic`) to call a
+    /// static method
+    pub reference_kind: u8,
+    pub reference_idx: u16,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MethodType {
+    pub descriptor_idx: u16,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvokeDynamic {
+    /// An index into the class's bootstrap methods, rather than its constant pool
+    pub bootstrap_method_attr_idx: u16,
+    pub name_and_type_idx: u16,
+}
+
+pub const ACC_PUBLIC: u16 = 0x0001;
+pub const ACC_PRIVATE: u16 = 0x0002;
+pub const ACC_PROTECTED: u16 = 0x0004;
+pub const ACC_STATIC: u16 = 0x0008;
+pub const ACC_FINAL: u16 = 0x0010;
+/// On a class, that invokespecial selects superclass methods from the current class's superclass, as
+/// every class since Java 1.0.2 does
+pub const ACC_SUPER: u16 = 0x0020;
+/// On a method, that calls hold the monitor of the receiver, or of the class for static methods
+pub const ACC_SYNCHRONIZED: u16 = 0x0020;
+pub const ACC_NATIVE: u16 = 0x0100;
+pub const ACC_INTERFACE: u16 = 0x0200;
+pub const ACC_ABSTRACT: u16 = 0x0400;
+pub const ACC_ENUM: u16 = 0x4000;
+
+#[derive(Debug)]
+pub struct Field {
+    pub access_flags: u16,
+    pub name_idx: u16,
+    pub descriptor_idx: u16,
+    pub attributes: Vec<Attribute>,
+}
+
+impl Field {
+    fn from_reader<R: Read>(reader: &mut CountingReader<R>) -> Result<Self, Error> {
+        let access_flags = read_u16(reader)?;
+        let name_idx = read_u16(reader)?;
+        let descriptor_idx = read_u16(reader)?;
+        let attributes_count = read_u16(reader)?;
+        let mut attributes = Vec::with_capacity(attributes_count as usize);
+        for _ in 0..attributes_count {
+            attributes.push(Attribute::from_reader(reader)?);
+        }
+        Ok(Self { access_flags, name_idx, descriptor_idx, attributes })
+    }
+
+    /// The field's generic type, such as `Ljava/util/List<Ljava/lang/String;>;`, if its Signature
+    /// attribute gives one.
+    pub fn signature<'a>(&self, const_pool: &'a ConstPool) -> Result<Option<&'a str>, Error> {
+        signature(&self.attributes, const_pool)
+    }
+
+    /// The annotations retained for runtime, from the RuntimeVisibleAnnotations attribute.
+    pub fn annotations(&self, const_pool: &ConstPool) -> Result<Vec<Annotation>, Error> {
+        annotations(&self.attributes, const_pool)
+    }
+
+    /// The index of the constant a static final field is initialized to, if its ConstantValue
+    /// attribute gives one.
+    pub fn constant_value_idx(&self, const_pool: &ConstPool) -> Result<Option<u16>, Error> {
+        for attribute in &self.attributes {
+            if const_pool.get_utf8(attribute.name_idx)?.bytes == "ConstantValue" {
+                let [high, low] = attribute.info[..] else {
+                    Err(anyhow!("java.lang.ClassFormatError: ConstantValue attribute of length {}", attribute.info.len()))?
+                };
+                return Ok(Some(u16::from_be_bytes([high, low])));
+            }
+        }
+        Ok(None)
+    }
+}
+
 #[derive(Debug)]
 pub struct Method {
+    pub access_flags: u16,
     pub name_idx: u16,
     pub descriptor_idx: u16,
     pub attributes: Vec<Attribute>,
 }
 
 impl Method {
-    fn from_reader<R: Read>(reader: &mut R) -> Result<Self, Error> {
-        let _access_flags = read_u16(reader)?;
+    fn from_reader<R: Read>(reader: &mut CountingReader<R>) -> Result<Self, Error> {
+        let access_flags = read_u16(reader)?;
         let name_idx = read_u16(reader)?;
         let descriptor_idx = read_u16(reader)?;
         let attributes_count = read_u16(reader)?;
@@ -129,8 +412,137 @@ impl Method {
         for _ in 0..attributes_count {
             attributes.push(Attribute::from_reader(reader)?);
         }
-        Ok(Self { name_idx, descriptor_idx, attributes })
+        Ok(Self { access_flags, name_idx, descriptor_idx, attributes })
     }
+
+    /// The method's generic signature, such as `<T:Ljava/lang/Object;>(TT;)TT;`, if its Signature
+    /// attribute gives one.
+    pub fn signature<'a>(&self, const_pool: &'a ConstPool) -> Result<Option<&'a str>, Error> {
+        signature(&self.attributes, const_pool)
+    }
+
+    /// The annotations retained for runtime, from the RuntimeVisibleAnnotations attribute.
+    pub fn annotations(&self, const_pool: &ConstPool) -> Result<Vec<Annotation>, Error> {
+        annotations(&self.attributes, const_pool)
+    }
+}
+
+/// The generic signature given by a Signature attribute among a class's, field's or method's
+/// attributes, which holds the index of a Utf8 constant.
+fn signature<'a>(attributes: &[Attribute], const_pool: &'a ConstPool) -> Result<Option<&'a str>, Error> {
+    for attribute in attributes {
+        if const_pool.get_utf8(attribute.name_idx)?.bytes == "Signature" {
+            let [high, low] = attribute.info[..] else {
+                Err(anyhow!("java.lang.ClassFormatError: Signature attribute of length {}", attribute.info.len()))?
+            };
+            return Ok(Some(&const_pool.get_utf8(u16::from_be_bytes([high, low]))?.bytes));
+        }
+    }
+    Ok(None)
+}
+
+/// The annotations a RuntimeVisibleAnnotations attribute among a class's, field's or method's
+/// attributes lists, or none without one.
+fn annotations(attributes: &[Attribute], const_pool: &ConstPool) -> Result<Vec<Annotation>, Error> {
+    for attribute in attributes {
+        if const_pool.get_utf8(attribute.name_idx)?.bytes == "RuntimeVisibleAnnotations" {
+            let reader = &mut CountingReader::within(attribute.info.as_slice(), "the RuntimeVisibleAnnotations attribute");
+            let count = read_u16(reader)?;
+            return (0..count)
+                .map(|_| Annotation::from_reader(reader, const_pool))
+                .collect::<Result<_, _>>()
+                .context("failed to read RuntimeVisibleAnnotations attribute");
+        }
+    }
+    Ok(vec![])
+}
+
+/// An annotation of a class, field or method, with its constants resolved from the constant pool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// The annotation interface's internal name, such as `java/lang/Deprecated`
+    pub type_name: String,
+    /// The values given for the annotation's elements, by element name. Elements left to their
+    /// defaults are absent.
+    pub element_value_pairs: Vec<(String, ElementValue)>,
+}
+
+impl Annotation {
+    fn from_reader<R: Read>(reader: &mut CountingReader<R>, const_pool: &ConstPool) -> Result<Self, Error> {
+        let type_name = descriptor_class_name(&const_pool.get_utf8(read_u16(reader)?)?.bytes);
+        let pair_count = read_u16(reader)?;
+        let mut element_value_pairs = Vec::with_capacity(pair_count as usize);
+        for _ in 0..pair_count {
+            let name = const_pool.get_utf8(read_u16(reader)?)?.bytes.clone();
+            element_value_pairs.push((name, ElementValue::from_reader(reader, const_pool)?));
+        }
+        Ok(Annotation { type_name, element_value_pairs })
+    }
+
+    /// The value given for the named element.
+    pub fn element(&self, name: &str) -> Option<&ElementValue> {
+        self.element_value_pairs.iter().find(|(element, _)| element == name).map(|(_, value)| value)
+    }
+}
+
+/// The value of an annotation element.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementValue {
+    Byte(i8),
+    Char(u16),
+    Double(f64),
+    Float(f32),
+    Int(i32),
+    Long(i64),
+    Short(i16),
+    Boolean(bool),
+    String(String),
+    /// An enum constant, by the enum's internal name and the constant's name
+    Enum { type_name: String, const_name: String },
+    /// A class literal, as the descriptor of its type, such as `Ljava/lang/String;` or `V` for `void.class`
+    Class(String),
+    Annotation(Annotation),
+    Array(Vec<ElementValue>),
+}
+
+impl ElementValue {
+    fn from_reader<R: Read>(reader: &mut CountingReader<R>, const_pool: &ConstPool) -> Result<Self, Error> {
+        let tag = read_u8(reader)?;
+        Ok(match tag {
+            b'e' => {
+                let type_name = descriptor_class_name(&const_pool.get_utf8(read_u16(reader)?)?.bytes);
+                let const_name = const_pool.get_utf8(read_u16(reader)?)?.bytes.clone();
+                ElementValue::Enum { type_name, const_name }
+            }
+            b'@' => ElementValue::Annotation(Annotation::from_reader(reader, const_pool)?),
+            b'[' => {
+                let count = read_u16(reader)?;
+                ElementValue::Array((0..count)
+                    .map(|_| ElementValue::from_reader(reader, const_pool))
+                    .collect::<Result<_, _>>()?)
+            }
+            _ => match (tag, const_pool.get_const(read_u16(reader)?)?) {
+                (b'B', Const::Integer(value)) => ElementValue::Byte(*value as i8),
+                (b'C', Const::Integer(value)) => ElementValue::Char(*value as u16),
+                (b'S', Const::Integer(value)) => ElementValue::Short(*value as i16),
+                (b'Z', Const::Integer(value)) => ElementValue::Boolean(*value != 0),
+                (b'I', Const::Integer(value)) => ElementValue::Int(*value),
+                (b'J', Const::Long(value)) => ElementValue::Long(*value),
+                (b'F', Const::Float(value)) => ElementValue::Float(*value),
+                (b'D', Const::Double(value)) => ElementValue::Double(*value),
+                (b's', Const::Utf8(value)) => ElementValue::String(value.bytes.clone()),
+                (b'c', Const::Utf8(value)) => ElementValue::Class(value.bytes.clone()),
+                (tag, const_item) => Err(anyhow!("java.lang.ClassFormatError: element value tag {:?} with constant {:?}",
+                    tag as char, const_item))?,
+            },
+        })
+    }
+}
+
+/// The internal name of the class a field descriptor such as `Ljava/lang/Deprecated;` names, or the
+/// descriptor itself if it isn't a class type.
+fn descriptor_class_name(descriptor: &str) -> String {
+    descriptor.strip_prefix('L').and_then(|name| name.strip_suffix(';')).unwrap_or(descriptor).to_string()
 }
 
 #[derive(Debug)]
@@ -140,7 +552,7 @@ pub struct Attribute {
 }
 
 impl Attribute {
-    fn from_reader<R: Read>(reader: &mut R) -> Result<Self, Error> {
+    fn from_reader<R: Read>(reader: &mut CountingReader<R>) -> Result<Self, Error> {
         let name_idx = read_u16(reader)?;
         let length = read_u32(reader)?;
         let info = read_length(reader, length as usize)?;
@@ -148,50 +560,314 @@ impl Attribute {
     }
 }
 
+/// A class nested in another, as listed by the InnerClasses attribute of the classes that refer to it.
+#[derive(Debug, PartialEq)]
+pub struct InnerClassEntry {
+    pub inner_class_idx: u16,
+    /// 0 for local and anonymous classes, which aren't members of a class
+    pub outer_class_idx: u16,
+    /// 0 for anonymous classes
+    pub inner_name_idx: u16,
+    pub access_flags: u16,
+}
+
+impl InnerClassEntry {
+    fn read_all<R: Read>(reader: &mut CountingReader<R>) -> Result<Vec<Self>, Error> {
+        let count = read_u16(reader)?;
+        let mut entries = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            entries.push(InnerClassEntry {
+                inner_class_idx: read_u16(reader)?,
+                outer_class_idx: read_u16(reader)?,
+                inner_name_idx: read_u16(reader)?,
+                access_flags: read_u16(reader)?,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// The internal name of the nested class, such as `com/example/Outer$Inner`.
+    pub fn inner_class<'a>(&self, const_pool: &'a ConstPool) -> Result<&'a str, Error> {
+        const_pool.resolve_class_name(self.inner_class_idx)
+    }
+
+    /// The internal name of the class the nested class is a member of.
+    pub fn outer_class<'a>(&self, const_pool: &'a ConstPool) -> Result<Option<&'a str>, Error> {
+        if self.outer_class_idx == 0 {
+            return Ok(None);
+        }
+        Ok(Some(const_pool.resolve_class_name(self.outer_class_idx)?))
+    }
+
+    /// The nested class's simple name as written in its source, such as `Inner`.
+    pub fn inner_name<'a>(&self, const_pool: &'a ConstPool) -> Result<Option<&'a str>, Error> {
+        if self.inner_name_idx == 0 {
+            return Ok(None);
+        }
+        Ok(Some(&const_pool.get_utf8(self.inner_name_idx)?.bytes))
+    }
+}
+
+/// The class and method a local or anonymous class is declared in, as given by its EnclosingMethod
+/// attribute.
+#[derive(Debug, PartialEq)]
+pub struct EnclosingMethod {
+    pub class_idx: u16,
+    /// A NameAndType constant, or 0 for classes declared in an initializer rather than a method
+    pub method_idx: u16,
+}
+
+impl EnclosingMethod {
+    fn from_reader<R: Read>(reader: &mut CountingReader<R>) -> Result<Self, Error> {
+        Ok(EnclosingMethod { class_idx: read_u16(reader)?, method_idx: read_u16(reader)? })
+    }
+
+    /// The internal name of the innermost class enclosing the declaration.
+    pub fn class<'a>(&self, const_pool: &'a ConstPool) -> Result<&'a str, Error> {
+        const_pool.resolve_class_name(self.class_idx)
+    }
+
+    /// The name and descriptor of the method enclosing the declaration.
+    pub fn method<'a>(&self, const_pool: &'a ConstPool) -> Result<Option<(&'a str, &'a str)>, Error> {
+        if self.method_idx == 0 {
+            return Ok(None);
+        }
+        let name_and_type = const_pool.get_name_and_type(self.method_idx)?;
+        let name = &const_pool.get_utf8(name_and_type.name_idx)?.bytes;
+        let descriptor = &const_pool.get_utf8(name_and_type.descriptor_idx)?.bytes;
+        Ok(Some((name, descriptor)))
+    }
+}
+
+/// A component of a record class, as listed by its Record attribute. The fields, accessors and
+/// `equals`, `hashCode` and `toString` methods the compiler generates for it are ordinary members.
+#[derive(Debug)]
+pub struct RecordComponent {
+    pub name_idx: u16,
+    pub descriptor_idx: u16,
+    pub attributes: Vec<Attribute>,
+}
+
+impl RecordComponent {
+    fn read_all<R: Read>(reader: &mut CountingReader<R>) -> Result<Vec<Self>, Error> {
+        let count = read_u16(reader)?;
+        let mut components = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let name_idx = read_u16(reader)?;
+            let descriptor_idx = read_u16(reader)?;
+            let attribute_count = read_u16(reader)?;
+            let mut attributes = Vec::with_capacity(attribute_count as usize);
+            for _ in 0..attribute_count {
+                attributes.push(Attribute::from_reader(reader)?);
+            }
+            components.push(RecordComponent { name_idx, descriptor_idx, attributes });
+        }
+        Ok(components)
+    }
+
+    /// The component's name, which is also that of its field and accessor.
+    pub fn name<'a>(&self, const_pool: &'a ConstPool) -> Result<&'a str, Error> {
+        Ok(&const_pool.get_utf8(self.name_idx)?.bytes)
+    }
+
+    /// The component's field descriptor, such as `I` or `Ljava/lang/String;`.
+    pub fn descriptor<'a>(&self, const_pool: &'a ConstPool) -> Result<&'a str, Error> {
+        Ok(&const_pool.get_utf8(self.descriptor_idx)?.bytes)
+    }
+
+    /// The component's generic signature, if its Signature attribute gives one.
+    pub fn signature<'a>(&self, const_pool: &'a ConstPool) -> Result<Option<&'a str>, Error> {
+        signature(&self.attributes, const_pool)
+    }
+}
+
+/// The method that links an invokedynamic call site, with the constants passed to it, as listed by the
+/// BootstrapMethods attribute.
+#[derive(Debug, PartialEq)]
+pub struct BootstrapMethod {
+    /// A method handle constant
+    pub method_ref_idx: u16,
+    pub argument_idxs: Vec<u16>,
+}
+
+impl BootstrapMethod {
+    fn read_all<R: Read>(reader: &mut CountingReader<R>) -> Result<Vec<Self>, Error> {
+        let count = read_u16(reader)?;
+        let mut bootstrap_methods = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let method_ref_idx = read_u16(reader)?;
+            let argument_count = read_u16(reader)?;
+            let mut argument_idxs = Vec::with_capacity(argument_count as usize);
+            for _ in 0..argument_count {
+                argument_idxs.push(read_u16(reader)?);
+            }
+            bootstrap_methods.push(BootstrapMethod { method_ref_idx, argument_idxs });
+        }
+        Ok(bootstrap_methods)
+    }
+}
+
 #[derive(Debug)]
 pub struct Code {
-    pub _max_stack: u16,
-    pub _max_locals: u16,
+    /// The most operand stack slots the code uses at once, counting two for longs and doubles
+    pub max_stack: u16,
+    /// The number of local variable slots, parameters included
+    pub max_locals: u16,
     pub code: Vec<u8>,
+    pub exception_table: Vec<ExceptionHandler>,
 }
 
 impl Code {
     pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        Self::read(&mut CountingReader::new(reader))
+    }
+
+    /// Reads the contents of a Code attribute, whose errors name the attribute, such as "the Code
+    /// attribute of main", with offsets into it.
+    pub fn read_within<R: Read>(reader: &mut R, within: &str) -> Result<Self, Error> {
+        Self::read(&mut CountingReader::within(reader, within))
+    }
+
+    fn read<R: Read>(reader: &mut CountingReader<R>) -> Result<Self, Error> {
         let max_stack = read_u16(reader)?;
         let max_locals = read_u16(reader)?;
         let code_length = read_u32(reader)?;
         let code = read_length(reader, code_length as usize)?;
         let ex_table_length = read_u16(reader)?;
-        let _ex_table = read_length(reader, ex_table_length as usize * 8)?;
+        let mut exception_table = Vec::with_capacity(ex_table_length as usize);
+        for _ in 0..ex_table_length {
+            exception_table.push(ExceptionHandler::from_reader(reader)?);
+        }
         let attributes_length = read_u16(reader)?;
         for _ in 0..attributes_length {
             Attribute::from_reader(reader)?;
         }
-        Ok(Self { _max_stack: max_stack, _max_locals: max_locals, code })
+        Ok(Self { max_stack, max_locals, code, exception_table })
     }
+
+    /// Decodes the code of the named method into its instructions, each with its offset, without
+    /// running any of them. Decoding errors name the method, and when verifying, switches are checked
+    /// as [`Instruction::decode_switch`](crate::instruction::Instruction::decode_switch) checks them.
+    pub fn iter_instructions<'a>(&'a self, method_name: &'a str, verify: bool) -> Instructions<'a> {
+        Instructions::new(&self.code, method_name, verify)
+    }
+}
+
+/// The checked exceptions a method declares, as indexes of class constants.
+#[derive(Debug)]
+pub struct Exceptions {
+    pub exception_idxs: Vec<u16>,
 }
 
-fn read_u8<R: Read>(reader: &mut R) -> Result<u8, Error> {
+impl Exceptions {
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        Self::read(&mut CountingReader::new(reader))
+    }
+
+    /// Reads the contents of an Exceptions attribute, whose errors name the attribute with offsets
+    /// into it, as [`Code::read_within`]'s do.
+    pub fn read_within<R: Read>(reader: &mut R, within: &str) -> Result<Self, Error> {
+        Self::read(&mut CountingReader::within(reader, within))
+    }
+
+    fn read<R: Read>(reader: &mut CountingReader<R>) -> Result<Self, Error> {
+        let count = read_u16(reader)?;
+        let mut exception_idxs = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            exception_idxs.push(read_u16(reader)?);
+        }
+        Ok(Self { exception_idxs })
+    }
+}
+
+#[derive(Debug)]
+pub struct ExceptionHandler {
+    /// Start of the range of code covered by the handler, inclusive
+    pub start_pc: u16,
+    /// End of the range of code covered by the handler, exclusive
+    pub end_pc: u16,
+    pub handler_pc: u16,
+    pub catch_type: u16,
+}
+
+impl ExceptionHandler {
+    fn from_reader<R: Read>(reader: &mut CountingReader<R>) -> Result<Self, Error> {
+        let start_pc = read_u16(reader)?;
+        let end_pc = read_u16(reader)?;
+        let handler_pc = read_u16(reader)?;
+        let catch_type = read_u16(reader)?;
+        Ok(Self { start_pc, end_pc, handler_pc, catch_type })
+    }
+}
+
+/// Wraps a reader to track how far into the class file it is, so read errors can say where the file
+/// is malformed.
+struct CountingReader<R> {
+    inner: R,
+    offset: usize,
+    /// The attribute being read, whose start offsets are counted from rather than the class file's
+    within: Option<String>,
+}
+
+impl<R: Read> CountingReader<R> {
+    fn new(inner: R) -> Self {
+        CountingReader { inner, offset: 0, within: None }
+    }
+
+    /// A reader of an attribute's contents, whose errors give offsets into the attribute.
+    fn within(inner: R, within: &str) -> Self {
+        CountingReader { inner, offset: 0, within: Some(within.to_string()) }
+    }
+
+    /// Where a read starting at `offset` failed, for its error.
+    fn describe(&self, offset: usize) -> String {
+        match &self.within {
+            Some(within) => format!("at offset {:#x} into {}", offset, within),
+            None => format!("at offset {:#x}", offset),
+        }
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.offset += count;
+        Ok(count)
+    }
+}
+
+fn read_u8<R: Read>(reader: &mut CountingReader<R>) -> Result<u8, Error> {
+    let offset = reader.offset;
     let mut bytes = [0; 1];
-    reader.read_exact(&mut bytes)?;
+    reader.read_exact(&mut bytes).with_context(|| format!("failed to read u8 {}", reader.describe(offset)))?;
     Ok(bytes[0])
 }
 
-fn read_u32<R: Read>(reader: &mut R) -> Result<u32, Error> {
+fn read_u32<R: Read>(reader: &mut CountingReader<R>) -> Result<u32, Error> {
+    let offset = reader.offset;
     let mut bytes = [0; 4];
-    reader.read_exact(&mut bytes)?;
+    reader.read_exact(&mut bytes).with_context(|| format!("failed to read u32 {}", reader.describe(offset)))?;
     Ok(u32::from_be_bytes(bytes))
 }
 
-fn read_u16<R: Read>(reader: &mut R) -> Result<u16, Error> {
+fn read_u16<R: Read>(reader: &mut CountingReader<R>) -> Result<u16, Error> {
+    let offset = reader.offset;
     let mut bytes = [0; 2];
-    reader.read_exact(&mut bytes)?;
+    reader.read_exact(&mut bytes).with_context(|| format!("failed to read u16 {}", reader.describe(offset)))?;
     Ok(u16::from_be_bytes(bytes))
 }
 
-fn read_length<R: Read>(reader: &mut R, length: usize) -> Result<Vec<u8>, Error> {
+fn read_u64<R: Read>(reader: &mut CountingReader<R>) -> Result<u64, Error> {
+    let offset = reader.offset;
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes).with_context(|| format!("failed to read u64 {}", reader.describe(offset)))?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+fn read_length<R: Read>(reader: &mut CountingReader<R>, length: usize) -> Result<Vec<u8>, Error> {
+    let offset = reader.offset;
     let mut bytes = vec![0; length];
-    reader.read_exact(&mut bytes)?;
+    reader.read_exact(&mut bytes).with_context(|| format!("failed to read {} bytes {}", length, reader.describe(offset)))?;
     Ok(bytes)
 }
 
@@ -212,81 +888,253 @@ mod read_length_tests {
 
     #[test]
     fn test_read_u8_ok() {
-        let result = read_u8(&mut reader());
+        let result = read_u8(&mut CountingReader::new(reader()));
 
         assert_eq!(result.unwrap(), 0x10);
     }
 
     #[test]
     fn test_read_u8_err() {
-        let result = read_u8(&mut empty());
+        let result = read_u8(&mut CountingReader::new(empty()));
 
         assert!(result.is_err());
     }
 
     #[test]
     fn test_read_u16_ok() {
-        let result = read_u16(&mut reader());
+        let result = read_u16(&mut CountingReader::new(reader()));
 
         assert_eq!(result.unwrap(), 0x1020);
     }
 
     #[test]
     fn test_read_u16_err() {
-        let result = read_u16(&mut empty());
+        let result = read_u16(&mut CountingReader::new(empty()));
 
         assert!(result.is_err());
     }
 
     #[test]
     fn test_read_u32_ok() {
-        let result = read_u32(&mut reader());
+        let result = read_u32(&mut CountingReader::new(reader()));
 
         assert_eq!(result.unwrap(), 0x10203040);
     }
 
     #[test]
     fn test_read_u32_err() {
-        let result = read_u32(&mut empty());
+        let result = read_u32(&mut CountingReader::new(empty()));
 
         assert!(result.is_err());
     }
 
     #[test]
     fn test_read_length_ok() {
-        let result = read_length(&mut reader(), 3);
+        let result = read_length(&mut CountingReader::new(reader()), 3);
 
         assert_eq!(result.unwrap(), vec![0x10, 0x20, 0x30]);
     }
 
     #[test]
     fn test_read_length_err() {
-        let result = read_length(&mut empty(), 3);
+        let result = read_length(&mut CountingReader::new(empty()), 3);
 
         assert!(result.is_err());
     }
 }
 
-fn read_const<R: Read>(reader: &mut R) -> Result<Const, Error> {
+fn read_const<R: Read>(reader: &mut CountingReader<R>) -> Result<Const, Error> {
     let tag = read_u8(reader)?;
     match tag {
         1 => {
             let length = read_u16(reader)?;
+            let offset = reader.offset;
             let bytes = read_length(reader, length as usize)?;
-            Ok(Const::Utf8(Utf8 { bytes: String::from_utf8(bytes)? }))
+            let string = decode_modified_utf8(&bytes).with_context(|| format!("invalid utf8 constant at offset {:#x}", offset))?;
+            Ok(Const::Utf8(Utf8 { bytes: string }))
         }
+        3 => Ok(Const::Integer(read_u32(reader)? as i32)),
+        4 => Ok(Const::Float(f32::from_bits(read_u32(reader)?))),
+        5 => Ok(Const::Long(read_u64(reader)? as i64)),
+        6 => Ok(Const::Double(f64::from_bits(read_u64(reader)?))),
         7 => {
             let name_idx = read_u16(reader)?;
             Ok(Const::Class(Class { name_idx }))
         }
-        10 | 12 => {
+        8 => {
+            let string_idx = read_u16(reader)?;
+            Ok(Const::String(StringConst { string_idx }))
+        }
+        9 => {
+            let class_idx = read_u16(reader)?;
+            let name_and_type_idx = read_u16(reader)?;
+            Ok(Const::Fieldref(Fieldref { class_idx, name_and_type_idx }))
+        }
+        11 => {
             read_u32(reader)?;
             Ok(Const::Unimplemented)
         }
+        10 => {
+            let class_idx = read_u16(reader)?;
+            let name_and_type_idx = read_u16(reader)?;
+            Ok(Const::Methodref(Methodref { class_idx, name_and_type_idx }))
+        }
+        12 => {
+            let name_idx = read_u16(reader)?;
+            let descriptor_idx = read_u16(reader)?;
+            Ok(Const::NameAndType(NameAndType { name_idx, descriptor_idx }))
+        }
+        15 => {
+            let reference_kind = read_u8(reader)?;
+            let reference_idx = read_u16(reader)?;
+            Ok(Const::MethodHandle(MethodHandle { reference_kind, reference_idx }))
+        }
+        16 => {
+            let descriptor_idx = read_u16(reader)?;
+            Ok(Const::MethodType(MethodType { descriptor_idx }))
+        }
+        18 => {
+            let bootstrap_method_attr_idx = read_u16(reader)?;
+            let name_and_type_idx = read_u16(reader)?;
+            Ok(Const::InvokeDynamic(InvokeDynamic { bootstrap_method_attr_idx, name_and_type_idx }))
+        }
         _ => Err(anyhow!("Unimplemented tag {}", tag))
     }
 }
 
+/// Decodes the modified UTF-8 of a Utf8 constant, which differs from UTF-8 in encoding the null
+/// character in two bytes and supplementary characters as a surrogate pair of three bytes each.
+/// Every sequence must end within the bytes given. A surrogate that isn't part of a pair, which
+/// Rust strings can't hold, decodes as U+FFFD.
+fn decode_modified_utf8(bytes: &[u8]) -> Result<String, Error> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let first = bytes[idx] as u16;
+        let length = match first {
+            0x01..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            _ => Err(anyhow!("invalid modified utf8 byte {:#04x} at {}", first, idx))?,
+        };
+        let sequence = bytes.get(idx..idx + length)
+            .ok_or_else(|| anyhow!("modified utf8 sequence at {} runs past the end of the {} bytes", idx, bytes.len()))?;
+        if sequence[1..].iter().any(|byte| byte & 0xC0 != 0x80) {
+            Err(anyhow!("invalid modified utf8 continuation byte in the sequence at {}", idx))?
+        }
+        units.push(match length {
+            1 => first,
+            2 => (first & 0x1F) << 6 | (sequence[1] as u16 & 0x3F),
+            _ => (first & 0x0F) << 12 | (sequence[1] as u16 & 0x3F) << 6 | (sequence[2] as u16 & 0x3F),
+        });
+        idx += length;
+    }
+    Ok(char::decode_utf16(units).map(|unit| unit.unwrap_or(char::REPLACEMENT_CHARACTER)).collect())
+}
+
+#[cfg(test)]
+mod decode_modified_utf8_tests {
+    use super::*;
+
+    /// The modified UTF-8 of a supplementary character: each half of its surrogate pair in three bytes.
+    fn encode_supplementary(c: char) -> Vec<u8> {
+        let mut units = [0; 2];
+        c.encode_utf16(&mut units).iter()
+            .flat_map(|unit| [0xE0 | (unit >> 12) as u8, 0x80 | (unit >> 6 & 0x3F) as u8, 0x80 | (unit & 0x3F) as u8])
+            .collect()
+    }
+
+    #[test]
+    fn ascii() {
+        assert_eq!(decode_modified_utf8(b"hello").unwrap(), "hello");
+    }
+
+    #[test]
+    fn two_byte_null() {
+        assert_eq!(decode_modified_utf8(&[b'a', 0xC0, 0x80, b'b']).unwrap(), "a\0b");
+    }
+
+    #[test]
+    fn multibyte() {
+        assert_eq!(decode_modified_utf8("é€".as_bytes()).unwrap(), "é€");
+    }
+
+    #[test]
+    fn surrogate_pair() {
+        assert_eq!(decode_modified_utf8(&encode_supplementary('😀')).unwrap(), "😀");
+    }
+
+    #[test]
+    fn unpaired_surrogate() {
+        assert_eq!(decode_modified_utf8(&[0xED, 0xA0, 0xBD, b'a']).unwrap(), "\u{FFFD}a");
+    }
+
+    #[test]
+    fn at_length_limit() {
+        // 10922 supplementary characters of 6 bytes each, and 3 bytes more, make the largest constant
+        let mut bytes = b"abc".to_vec();
+        for _ in 0..10922 {
+            bytes.extend(encode_supplementary('😀'));
+        }
+        assert_eq!(bytes.len(), u16::MAX as usize);
+
+        let string = decode_modified_utf8(&bytes).unwrap();
+
+        assert_eq!(string.chars().count(), 3 + 10922);
+        assert!(string.ends_with('😀'));
+    }
+
+    #[test]
+    fn sequence_past_end() {
+        let mut bytes = encode_supplementary('😀');
+        bytes.pop();
+
+        assert_eq!(decode_modified_utf8(&bytes).unwrap_err().to_string(),
+                   "modified utf8 sequence at 3 runs past the end of the 5 bytes");
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        assert!(decode_modified_utf8(&[0x00]).is_err());
+        assert!(decode_modified_utf8(&[0xF0, 0x9F, 0x98, 0x80]).is_err());
+        assert!(decode_modified_utf8(&[0xC3, b'a']).is_err());
+    }
+}
+
+#[cfg(test)]
+mod read_offset_tests {
+    use super::*;
+
+    #[test]
+    fn truncated_utf8_reports_offset() {
+        let bytes: Vec<u8> = vec![
+            vec![0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x34],
+            vec![0x00, 0x02],
+            vec![0x01, 0x00, 0x05],
+            "hel".bytes().collect(),
+        ].into_iter().flatten().collect();
+
+        let error = ClassFile::read_from(&mut bytes.as_slice()).unwrap_err();
+
+        assert_eq!(error.to_string(), "failed to read 5 bytes at offset 0xd");
+    }
+
+    #[test]
+    fn truncated_method_reports_offset() {
+        let bytes: Vec<u8> = vec![
+            vec![0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x34],
+            vec![0x00, 0x01],
+            vec![0x00, 0x21, 0x00, 0x00, 0x00, 0x00],
+            vec![0x00, 0x00, 0x00, 0x00],
+            vec![0x00, 0x01, 0x00, 0x09],
+        ].into_iter().flatten().collect();
+
+        let error = ClassFile::read_from(&mut bytes.as_slice()).unwrap_err();
+
+        assert_eq!(error.to_string(), "failed to read u16 at offset 0x18");
+    }
+}
+
 #[cfg(test)]
 mod read_const_tests {
     use super::*;
@@ -300,16 +1148,27 @@ mod read_const_tests {
             "hello world".bytes().collect()
         ].into_iter().flatten().collect();
 
-        let utf8_const = read_const(&mut Cursor::new(reader));
+        let utf8_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
 
         assert_eq!(utf8_const.unwrap(), Const::Utf8(Utf8 { bytes: "hello world".to_string() }));
     }
 
+    #[test]
+    fn read_utf8_straddling_length() {
+        // The length ends inside the two byte encoding of é
+        let reader = vec![0x01, 0x00, 0x02, b'a', 0xC3, 0xA9];
+
+        let error = read_const(&mut CountingReader::new(Cursor::new(reader))).unwrap_err();
+
+        assert_eq!(format!("{:#}", error),
+                   "invalid utf8 constant at offset 0x3: modified utf8 sequence at 1 runs past the end of the 2 bytes");
+    }
+
     #[test]
     fn read_utf8_err() {
         let reader = vec![0x01, 0x0, 0x2];
 
-        let utf8_const = read_const(&mut Cursor::new(reader));
+        let utf8_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
 
         assert!(utf8_const.is_err());
     }
@@ -318,7 +1177,7 @@ mod read_const_tests {
     fn read_class_ok() {
         let reader = vec![0x07, 0x23, 0x45];
 
-        let utf8_const = read_const(&mut Cursor::new(reader));
+        let utf8_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
 
         assert_eq!(utf8_const.unwrap(), Const::Class(Class { name_idx: 0x2345 }));
     }
@@ -327,8 +1186,228 @@ mod read_const_tests {
     fn read_class_err() {
         let reader = vec![0x07];
 
-        let utf8_const = read_const(&mut Cursor::new(reader));
+        let utf8_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
 
         assert!(utf8_const.is_err());
     }
+
+    #[test]
+    fn read_integer_ok() {
+        let reader = vec![0x03, 0xFF, 0xFF, 0xFF, 0xFE];
+
+        let integer_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
+
+        assert_eq!(integer_const.unwrap(), Const::Integer(-2));
+    }
+
+    #[test]
+    fn read_float_ok() {
+        let reader = vec![0x04, 0x3F, 0xC0, 0x00, 0x00];
+
+        let float_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
+
+        assert_eq!(float_const.unwrap(), Const::Float(1.5));
+    }
+
+    #[test]
+    fn read_long_ok() {
+        let reader = vec![0x05, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+        let long_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
+
+        assert_eq!(long_const.unwrap(), Const::Long(1 << 40));
+    }
+
+    #[test]
+    fn read_string_ok() {
+        let reader = vec![0x08, 0x00, 0x07];
+
+        let string_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
+
+        assert_eq!(string_const.unwrap(), Const::String(StringConst { string_idx: 7 }));
+    }
+
+    #[test]
+    fn read_fieldref_ok() {
+        let reader = vec![0x09, 0x00, 0x02, 0x00, 0x03];
+
+        let fieldref_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
+
+        assert_eq!(fieldref_const.unwrap(), Const::Fieldref(Fieldref { class_idx: 2, name_and_type_idx: 3 }));
+    }
+
+    #[test]
+    fn read_methodref_ok() {
+        let reader = vec![0x0A, 0x00, 0x02, 0x00, 0x03];
+
+        let methodref_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
+
+        assert_eq!(methodref_const.unwrap(), Const::Methodref(Methodref { class_idx: 2, name_and_type_idx: 3 }));
+    }
+
+    #[test]
+    fn read_name_and_type_ok() {
+        let reader = vec![0x0C, 0x00, 0x04, 0x00, 0x05];
+
+        let name_and_type_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
+
+        assert_eq!(name_and_type_const.unwrap(), Const::NameAndType(NameAndType { name_idx: 4, descriptor_idx: 5 }));
+    }
+
+    #[test]
+    fn read_method_handle_ok() {
+        let reader = vec![0x0F, 0x06, 0x00, 0x09];
+
+        let method_handle_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
+
+        assert_eq!(method_handle_const.unwrap(), Const::MethodHandle(MethodHandle { reference_kind: 6, reference_idx: 9 }));
+    }
+
+    #[test]
+    fn read_method_type_ok() {
+        let reader = vec![0x10, 0x00, 0x04];
+
+        let method_type_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
+
+        assert_eq!(method_type_const.unwrap(), Const::MethodType(MethodType { descriptor_idx: 4 }));
+    }
+
+    #[test]
+    fn read_invoke_dynamic_ok() {
+        let reader = vec![0x12, 0x00, 0x00, 0x00, 0x05];
+
+        let invoke_dynamic_const = read_const(&mut CountingReader::new(Cursor::new(reader)));
+
+        assert_eq!(invoke_dynamic_const.unwrap(),
+                   Const::InvokeDynamic(InvokeDynamic { bootstrap_method_attr_idx: 0, name_and_type_idx: 5 }));
+    }
+}
+
+#[cfg(test)]
+mod const_pool_tests {
+    use super::*;
+
+    #[test]
+    fn iter_skips_long_gap() {
+        let reader: Vec<u8> = vec![
+            vec![0x00, 0x05],
+            vec![0x01, 0x00, 0x02],
+            "hi".bytes().collect(),
+            vec![0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2A],
+            vec![0x07, 0x00, 0x01],
+        ].into_iter().flatten().collect();
+
+        let pool = ConstPool::from_reader(&mut CountingReader::new(reader.as_slice())).unwrap();
+
+        let indexes: Vec<u16> = pool.iter().map(|(idx, _)| idx).collect();
+        assert_eq!(indexes, vec![1, 2, 4]);
+        for (idx, const_item) in pool.iter() {
+            assert_eq!(pool.get_const(idx).unwrap(), const_item);
+        }
+    }
+
+    #[test]
+    fn long_takes_two_entries() {
+        let reader: Vec<u8> = vec![
+            vec![0x00, 0x04],
+            vec![0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2A],
+            vec![0x01, 0x00, 0x02],
+            "hi".bytes().collect(),
+        ].into_iter().flatten().collect();
+
+        let pool = ConstPool::from_reader(&mut CountingReader::new(reader.as_slice())).unwrap();
+
+        assert_eq!(pool.get_const(1).unwrap(), &Const::Long(42));
+        assert_eq!(pool.get_utf8(3).unwrap(), &Utf8 { bytes: "hi".to_string() });
+    }
+
+    #[test]
+    fn padding_after_double() {
+        let reader: Vec<u8> = vec![
+            vec![0x00, 0x03],
+            vec![0x06, 0x3F, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        ].into_iter().flatten().collect();
+
+        let pool = ConstPool::from_reader(&mut CountingReader::new(reader.as_slice())).unwrap();
+
+        assert_eq!(pool.get_const(2).unwrap_err().to_string(),
+            "const pool index 2 refers to the second slot of a long/double constant");
+        assert_eq!(pool.get_utf8(2).unwrap_err().to_string(),
+            "const pool index 2 refers to the second slot of a long/double constant");
+    }
+
+    fn pool() -> ConstPool {
+        let reader: Vec<u8> = vec![
+            vec![0x00, 0x03],
+            vec![0x01, 0x00, 0x05],
+            "hello".bytes().collect(),
+            vec![0x07, 0x00, 0x01],
+        ].into_iter().flatten().collect();
+
+        ConstPool::from_reader(&mut CountingReader::new(reader.as_slice())).unwrap()
+    }
+
+    #[test]
+    fn from_reader_ok() {
+        let pool = pool();
+
+        assert_eq!(pool.get_utf8(1).unwrap(), &Utf8 { bytes: "hello".to_string() });
+        assert_eq!(pool.get_class(2).unwrap(), &Class { name_idx: 1 });
+    }
+
+    #[test]
+    fn from_reader_zero_count() {
+        let reader = [0x00, 0x00];
+
+        let pool = ConstPool::from_reader(&mut CountingReader::new(reader.as_slice()));
+
+        assert!(pool.unwrap_err().to_string().contains("ClassFormatError"));
+    }
+
+    #[test]
+    fn get_const_zero() {
+        let pool = pool();
+
+        assert!(pool.get_const(0).is_err());
+    }
+
+    #[test]
+    fn get_const_past_end() {
+        let pool = pool();
+
+        assert!(pool.get_const(3).is_err());
+    }
+
+    #[test]
+    fn resolve_class_name() {
+        let reader: Vec<u8> = vec![
+            vec![0x00, 0x05],
+            vec![0x01, 0x00, 0x10],
+            "java/lang/String".bytes().collect(),
+            vec![0x07, 0x00, 0x01],
+            vec![0x01, 0x00, 0x02],
+            "[I".bytes().collect(),
+            vec![0x07, 0x00, 0x03],
+        ].into_iter().flatten().collect();
+        let pool = ConstPool::from_reader(&mut CountingReader::new(reader.as_slice())).unwrap();
+
+        assert_eq!(pool.resolve_class_name(2).unwrap(), "java/lang/String");
+        assert_eq!(pool.resolve_class_name(4).unwrap(), "[I");
+        assert!(pool.resolve_class_name(1).unwrap_err().to_string().starts_with("expected class"));
+    }
+}
+
+#[cfg(test)]
+mod class_file_name_tests {
+    use super::*;
+
+    #[test]
+    fn class_extension_in_any_case() {
+        assert!(is_class_file_name("com/example/App.class"));
+        assert!(is_class_file_name("APP.CLASS"));
+        assert!(is_class_file_name("App.Class"));
+        assert!(!is_class_file_name("App.java"));
+        assert!(!is_class_file_name("class"));
+        assert!(!is_class_file_name("Ä.clas"));
+    }
 }
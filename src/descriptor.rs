@@ -0,0 +1,260 @@
+//! Parsing of field and method descriptors, the class file's encoding of types such as
+//! `[Ljava/lang/String;` and method signatures such as `(IJ)V`.
+//!
+//! Both [`FieldType`] and [`MethodDescriptor`] display in descriptor syntax, or with the alternate flag
+//! (`{:#}`) as Java source would write them: `java.lang.String[]`, `void (int, long)`.
+
+use crate::value::Value;
+use anyhow::{anyhow, Error};
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+    /// An instance of the class with the given internal name
+    Object(String),
+    Array(Box<FieldType>),
+}
+
+impl FieldType {
+    pub fn parse(descriptor: &str) -> Result<Self, Error> {
+        let mut chars = descriptor.chars().peekable();
+        let field_type = Self::parse_from(&mut chars)?;
+        if chars.next().is_some() {
+            Err(anyhow!("trailing characters in field descriptor {}", descriptor))?
+        }
+        Ok(field_type)
+    }
+
+    /// The value a field of this type holds before it's first assigned.
+    pub fn default_value(&self) -> Value {
+        match self {
+            FieldType::Byte | FieldType::Char | FieldType::Int | FieldType::Short | FieldType::Boolean => Value::Int(0),
+            FieldType::Long => Value::Long(0),
+            FieldType::Float => Value::Float(0.0),
+            FieldType::Double => Value::Double(0.0),
+            FieldType::Object(_) | FieldType::Array(_) => Value::Null,
+        }
+    }
+
+    fn parse_from(chars: &mut Peekable<Chars>) -> Result<Self, Error> {
+        match chars.next() {
+            Some('B') => Ok(FieldType::Byte),
+            Some('C') => Ok(FieldType::Char),
+            Some('D') => Ok(FieldType::Double),
+            Some('F') => Ok(FieldType::Float),
+            Some('I') => Ok(FieldType::Int),
+            Some('J') => Ok(FieldType::Long),
+            Some('S') => Ok(FieldType::Short),
+            Some('Z') => Ok(FieldType::Boolean),
+            Some('L') => {
+                let mut class_name = String::new();
+                loop {
+                    match chars.next() {
+                        Some(';') if !class_name.is_empty() => return Ok(FieldType::Object(class_name)),
+                        Some(';') | None => Err(anyhow!("invalid class name in descriptor"))?,
+                        Some(char) => class_name.push(char),
+                    }
+                }
+            }
+            Some('[') => Ok(FieldType::Array(Box::new(Self::parse_from(chars)?))),
+            Some(char) => Err(anyhow!("unexpected character {} in descriptor", char)),
+            None => Err(anyhow!("unexpected end of descriptor")),
+        }
+    }
+}
+
+impl Display for FieldType {
+    /// Formats the type in descriptor syntax, e.g. `[Ljava/lang/String;`, or with the alternate flag
+    /// as a Java type, e.g. `java.lang.String[]`
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return match self {
+                FieldType::Byte => write!(f, "byte"),
+                FieldType::Char => write!(f, "char"),
+                FieldType::Double => write!(f, "double"),
+                FieldType::Float => write!(f, "float"),
+                FieldType::Int => write!(f, "int"),
+                FieldType::Long => write!(f, "long"),
+                FieldType::Short => write!(f, "short"),
+                FieldType::Boolean => write!(f, "boolean"),
+                FieldType::Object(class_name) => write!(f, "{}", class_name.replace('/', ".")),
+                FieldType::Array(component) => write!(f, "{:#}[]", component),
+            };
+        }
+        match self {
+            FieldType::Byte => write!(f, "B"),
+            FieldType::Char => write!(f, "C"),
+            FieldType::Double => write!(f, "D"),
+            FieldType::Float => write!(f, "F"),
+            FieldType::Int => write!(f, "I"),
+            FieldType::Long => write!(f, "J"),
+            FieldType::Short => write!(f, "S"),
+            FieldType::Boolean => write!(f, "Z"),
+            FieldType::Object(class_name) => write!(f, "L{};", class_name),
+            FieldType::Array(component) => write!(f, "[{}", component),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodDescriptor {
+    pub parameters: Vec<FieldType>,
+    /// `None` for a `void` method
+    pub return_type: Option<FieldType>,
+}
+
+impl MethodDescriptor {
+    pub fn parse(descriptor: &str) -> Result<Self, Error> {
+        let mut chars = descriptor.chars().peekable();
+        if chars.next() != Some('(') {
+            Err(anyhow!("method descriptor {} does not start with (", descriptor))?
+        }
+
+        let mut parameters = vec![];
+        while chars.next_if_eq(&')').is_none() {
+            parameters.push(FieldType::parse_from(&mut chars)?);
+        }
+
+        let return_type = if chars.next_if_eq(&'V').is_some() {
+            None
+        } else {
+            Some(FieldType::parse_from(&mut chars)?)
+        };
+
+        if chars.next().is_some() {
+            Err(anyhow!("trailing characters in method descriptor {}", descriptor))?
+        }
+        Ok(MethodDescriptor { parameters, return_type })
+    }
+}
+
+impl Display for MethodDescriptor {
+    /// Formats the descriptor in its class file syntax, e.g. `(I[Ljava/lang/String;)V`, or with the
+    /// alternate flag as Java would declare it, e.g. `void (int, java.lang.String[])`
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            match &self.return_type {
+                Some(return_type) => write!(f, "{:#} (", return_type)?,
+                None => write!(f, "void (")?,
+            }
+            for (idx, parameter) in self.parameters.iter().enumerate() {
+                if idx > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{:#}", parameter)?;
+            }
+            return write!(f, ")");
+        }
+        write!(f, "(")?;
+        for parameter in &self.parameters {
+            write!(f, "{}", parameter)?;
+        }
+        match &self.return_type {
+            Some(return_type) => write!(f, "){}", return_type),
+            None => write!(f, ")V"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod descriptor_tests {
+    use super::*;
+
+    #[test]
+    fn parse_parameter_types() {
+        let descriptor = MethodDescriptor::parse("(BCDFIJSZLjava/lang/String;[[J)V").unwrap();
+
+        assert_eq!(descriptor.parameters, vec![
+            FieldType::Byte,
+            FieldType::Char,
+            FieldType::Double,
+            FieldType::Float,
+            FieldType::Int,
+            FieldType::Long,
+            FieldType::Short,
+            FieldType::Boolean,
+            FieldType::Object("java/lang/String".to_string()),
+            FieldType::Array(Box::new(FieldType::Array(Box::new(FieldType::Long)))),
+        ]);
+    }
+
+    #[test]
+    fn parse_invalid_parameter_types() {
+        assert!(MethodDescriptor::parse("(L;)V").is_err());
+        assert!(MethodDescriptor::parse("(Ljava/lang/String)V").is_err());
+        assert!(MethodDescriptor::parse("([)V").is_err());
+    }
+
+    #[test]
+    fn parse_field_descriptor() {
+        assert_eq!(FieldType::parse("[Ljava/lang/String;").unwrap(),
+            FieldType::Array(Box::new(FieldType::Object("java/lang/String".to_string()))));
+        assert!(FieldType::parse("II").is_err());
+        assert!(FieldType::parse("V").is_err());
+    }
+
+    #[test]
+    fn default_values() {
+        assert_eq!(FieldType::Boolean.default_value(), Value::Int(0));
+        assert_eq!(FieldType::Long.default_value(), Value::Long(0));
+        assert_eq!(FieldType::Object("java/lang/String".to_string()).default_value(), Value::Null);
+    }
+
+    #[test]
+    fn parse_method_descriptor() {
+        let descriptor = MethodDescriptor::parse("(IJ[Ljava/lang/String;)Ljava/lang/Object;").unwrap();
+
+        assert_eq!(descriptor, MethodDescriptor {
+            parameters: vec![
+                FieldType::Int,
+                FieldType::Long,
+                FieldType::Array(Box::new(FieldType::Object("java/lang/String".to_string()))),
+            ],
+            return_type: Some(FieldType::Object("java/lang/Object".to_string())),
+        });
+    }
+
+    #[test]
+    fn parse_void_method_descriptor() {
+        let descriptor = MethodDescriptor::parse("()V").unwrap();
+
+        assert_eq!(descriptor, MethodDescriptor { parameters: vec![], return_type: None });
+    }
+
+    #[test]
+    fn parse_invalid_method_descriptors() {
+        assert!(MethodDescriptor::parse("").is_err());
+        assert!(MethodDescriptor::parse("V").is_err());
+        assert!(MethodDescriptor::parse("(I").is_err());
+        assert!(MethodDescriptor::parse("(V)V").is_err());
+        assert!(MethodDescriptor::parse("()").is_err());
+        assert!(MethodDescriptor::parse("()VV").is_err());
+        assert!(MethodDescriptor::parse("(Q)V").is_err());
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let descriptor = "([[IJLjava/lang/String;)[Z";
+
+        assert_eq!(MethodDescriptor::parse(descriptor).unwrap().to_string(), descriptor);
+    }
+
+    #[test]
+    fn display_java_types() {
+        let descriptor = MethodDescriptor::parse("([[IJLjava/lang/String;)[Ljava/util/Map$Entry;").unwrap();
+
+        assert_eq!(format!("{:#}", descriptor), "java.util.Map$Entry[] (int[][], long, java.lang.String)");
+        assert_eq!(format!("{:#}", MethodDescriptor::parse("()V").unwrap()), "void ()");
+        assert_eq!(format!("{:#}", FieldType::parse("Z").unwrap()), "boolean");
+    }
+}
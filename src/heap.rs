@@ -0,0 +1,333 @@
+use crate::runtime::RuntimeClass;
+use crate::value::Value;
+use anyhow::{anyhow, Error};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// A reference to an object on the heap, identified by its allocation order: the first object
+/// allocated has id 1, the next 2, and so on, with 0 reserved for null. Ids never depend on memory
+/// addresses, so identity hash codes and trace output are the same on every run of a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Reference(usize);
+
+impl Reference {
+    /// The allocation order id, which also serves as the object's identity hash code.
+    pub fn id(self) -> usize {
+        self.0
+    }
+}
+
+/// The approximate size of an object's header, counted towards the heap's size along with its fields
+/// or elements.
+const HEADER_SIZE: usize = 16;
+
+pub struct Heap {
+    objects: Vec<HeapObject>,
+    /// The approximate number of bytes allocated so far
+    size: usize,
+    /// The most bytes that may be allocated, if capped
+    pub max_size: Option<usize>,
+    /// The bytes the heap is to start out with, as `-Xms` sets, which is reported but not reserved up
+    /// front as objects are allocated one by one
+    pub initial_size: Option<usize>,
+}
+
+impl Heap {
+    pub fn new() -> Self {
+        Heap { objects: vec![], size: 0, max_size: None, initial_size: None }
+    }
+
+    pub fn allocate(&mut self, object: HeapObject) -> Result<Reference, Error> {
+        self.reserve(object.size())?;
+        Ok(self.push(object))
+    }
+
+    /// Allocates an array with every element set to its default, checking the heap has room for it
+    /// before creating its elements.
+    pub fn allocate_array(&mut self, type_name: String, length: usize) -> Result<Reference, Error> {
+        self.reserve(Array::size(&type_name, length))?;
+        Ok(self.push(HeapObject::Array(Array::new(type_name, length))))
+    }
+
+    fn reserve(&mut self, size: usize) -> Result<(), Error> {
+        let size = self.size.saturating_add(size);
+        if self.max_size.is_some_and(|max_size| size > max_size) {
+            Err(anyhow!("java.lang.OutOfMemoryError: Java heap space"))?
+        }
+        self.size = size;
+        Ok(())
+    }
+
+    fn push(&mut self, object: HeapObject) -> Reference {
+        self.objects.push(object);
+        Reference(self.objects.len())
+    }
+
+    pub fn get(&self, reference: Reference) -> Result<&HeapObject, Error> {
+        reference.0.checked_sub(1)
+            .and_then(|idx| self.objects.get(idx))
+            .ok_or_else(|| anyhow!("dangling reference {:?}", reference))
+    }
+
+    pub fn get_mut(&mut self, reference: Reference) -> Result<&mut HeapObject, Error> {
+        reference.0.checked_sub(1)
+            .and_then(|idx| self.objects.get_mut(idx))
+            .ok_or_else(|| anyhow!("dangling reference {:?}", reference))
+    }
+}
+
+#[derive(Debug)]
+pub enum HeapObject {
+    Object(Object),
+    Array(Array),
+}
+
+impl HeapObject {
+    /// The internal name of the object's runtime type, an array descriptor such as `[I` for arrays.
+    pub fn type_name(&self) -> &str {
+        match self {
+            HeapObject::Object(object) => &object.class.this_class,
+            HeapObject::Array(array) => array.type_name(),
+        }
+    }
+
+    /// The approximate number of bytes the object occupies.
+    fn size(&self) -> usize {
+        match self {
+            HeapObject::Object(object) => HEADER_SIZE + object.fields.len() * 8,
+            HeapObject::Array(array) => Array::size(array.type_name(), array.len()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Object {
+    pub class: Rc<RuntimeClass>,
+    pub fields: HashMap<String, Value>,
+}
+
+/// An array's elements, stored at the width of the element type. Values are widened to ints when
+/// loaded onto the operand stack and narrowed back when stored, as the JVM's array instructions do.
+#[derive(Debug, Clone)]
+pub enum Array {
+    /// Booleans are stored as bytes holding 0 or 1
+    Boolean(Vec<u8>),
+    Byte(Vec<i8>),
+    Char(Vec<u16>),
+    Short(Vec<i16>),
+    Int(Vec<i32>),
+    Reference { type_name: String, elements: Vec<Value> },
+}
+
+impl Array {
+    /// Creates an array of the given array type descriptor, with every element set to its default.
+    pub fn new(type_name: String, length: usize) -> Self {
+        match type_name.as_str() {
+            "[Z" => Array::Boolean(vec![0; length]),
+            "[B" => Array::Byte(vec![0; length]),
+            "[C" => Array::Char(vec![0; length]),
+            "[S" => Array::Short(vec![0; length]),
+            "[I" => Array::Int(vec![0; length]),
+            _ => Array::Reference { type_name, elements: vec![Value::Null; length] },
+        }
+    }
+
+    /// The approximate number of bytes an array of the given type and length occupies.
+    fn size(type_name: &str, length: usize) -> usize {
+        let element_size = match type_name {
+            "[Z" | "[B" => 1,
+            "[C" | "[S" => 2,
+            "[I" => 4,
+            _ => 8,
+        };
+        length.saturating_mul(element_size).saturating_add(HEADER_SIZE)
+    }
+
+    pub fn type_name(&self) -> &str {
+        match self {
+            Array::Boolean(_) => "[Z",
+            Array::Byte(_) => "[B",
+            Array::Char(_) => "[C",
+            Array::Short(_) => "[S",
+            Array::Int(_) => "[I",
+            Array::Reference { type_name, .. } => type_name,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Array::Boolean(elements) => elements.len(),
+            Array::Byte(elements) => elements.len(),
+            Array::Char(elements) => elements.len(),
+            Array::Short(elements) => elements.len(),
+            Array::Int(elements) => elements.len(),
+            Array::Reference { elements, .. } => elements.len(),
+        }
+    }
+
+    /// Loads an element, zero-extending chars and sign-extending bytes and shorts to an int.
+    pub fn load(&self, index: usize) -> Result<Value, Error> {
+        let value = match self {
+            Array::Boolean(elements) => elements.get(index).map(|value| Value::Int(*value as i32)),
+            Array::Byte(elements) => elements.get(index).map(|value| Value::Int(*value as i32)),
+            Array::Char(elements) => elements.get(index).map(|value| Value::Int(*value as i32)),
+            Array::Short(elements) => elements.get(index).map(|value| Value::Int(*value as i32)),
+            Array::Int(elements) => elements.get(index).map(|value| Value::Int(*value)),
+            Array::Reference { elements, .. } => elements.get(index).copied(),
+        };
+        value.ok_or_else(|| anyhow!("array index {} out of bounds for length {}", index, self.len()))
+    }
+
+    /// Stores an element, truncating ints to the width of the element type. Only the lowest bit is
+    /// kept when storing to a boolean array.
+    pub fn store(&mut self, index: usize, value: Value) -> Result<(), Error> {
+        let length = self.len();
+        let out_of_bounds = || anyhow!("array index {} out of bounds for length {}", index, length);
+        match self {
+            Array::Boolean(elements) => *elements.get_mut(index).ok_or_else(out_of_bounds)? = value.as_int()? as u8 & 1,
+            Array::Byte(elements) => *elements.get_mut(index).ok_or_else(out_of_bounds)? = value.as_int()? as i8,
+            Array::Char(elements) => *elements.get_mut(index).ok_or_else(out_of_bounds)? = value.as_int()? as u16,
+            Array::Short(elements) => *elements.get_mut(index).ok_or_else(out_of_bounds)? = value.as_int()? as i16,
+            Array::Int(elements) => *elements.get_mut(index).ok_or_else(out_of_bounds)? = value.as_int()?,
+            Array::Reference { elements, .. } => {
+                value.as_reference()?;
+                *elements.get_mut(index).ok_or_else(out_of_bounds)? = value;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod reference_tests {
+    use super::*;
+
+    fn allocate_arrays() -> Vec<Reference> {
+        let mut heap = Heap::new();
+        (0..3).map(|_| heap.allocate_array("[I".to_string(), 1).unwrap()).collect()
+    }
+
+    #[test]
+    fn ids_start_at_one() {
+        assert_eq!(allocate_arrays(), vec![Reference(1), Reference(2), Reference(3)]);
+    }
+
+    #[test]
+    fn ids_are_reproducible() {
+        assert_eq!(allocate_arrays(), allocate_arrays());
+    }
+
+    #[test]
+    fn null_id_is_dangling() {
+        let heap = Heap::new();
+
+        assert!(heap.get(Reference(0)).is_err());
+    }
+}
+
+#[cfg(test)]
+mod heap_size_tests {
+    use super::*;
+
+    #[test]
+    fn allocate_within_cap() {
+        let mut heap = Heap::new();
+        heap.max_size = Some(2 * (HEADER_SIZE + 40));
+
+        heap.allocate_array("[I".to_string(), 10).unwrap();
+        heap.allocate_array("[I".to_string(), 10).unwrap();
+    }
+
+    #[test]
+    fn allocate_past_cap() {
+        let mut heap = Heap::new();
+        heap.max_size = Some(1024);
+
+        heap.allocate_array("[B".to_string(), 1000).unwrap();
+        let error = heap.allocate_array("[B".to_string(), 1000).unwrap_err();
+
+        assert_eq!(error.to_string(), "java.lang.OutOfMemoryError: Java heap space");
+    }
+
+    #[test]
+    fn huge_array_is_checked_before_creation() {
+        let mut heap = Heap::new();
+        heap.max_size = Some(1024);
+
+        assert!(heap.allocate_array("[Ljava/lang/Object;".to_string(), usize::MAX).is_err());
+    }
+}
+
+#[cfg(test)]
+mod array_tests {
+    use super::*;
+
+    #[test]
+    fn char_zero_extends() {
+        let mut array = Array::new("[C".to_string(), 1);
+
+        array.store(0, Value::Int(0xFFFF)).unwrap();
+
+        assert_eq!(array.load(0).unwrap(), Value::Int(0xFFFF));
+    }
+
+    #[test]
+    fn char_truncates() {
+        let mut array = Array::new("[C".to_string(), 1);
+
+        array.store(0, Value::Int(0x12345)).unwrap();
+
+        assert_eq!(array.load(0).unwrap(), Value::Int(0x2345));
+    }
+
+    #[test]
+    fn byte_truncates_and_sign_extends() {
+        let mut array = Array::new("[B".to_string(), 2);
+
+        array.store(0, Value::Int(300)).unwrap();
+        array.store(1, Value::Int(200)).unwrap();
+
+        assert_eq!(array.load(0).unwrap(), Value::Int(44));
+        assert_eq!(array.load(1).unwrap(), Value::Int(-56));
+    }
+
+    #[test]
+    fn short_truncates_and_sign_extends() {
+        let mut array = Array::new("[S".to_string(), 1);
+
+        array.store(0, Value::Int(0x18000)).unwrap();
+
+        assert_eq!(array.load(0).unwrap(), Value::Int(-0x8000));
+    }
+
+    #[test]
+    fn boolean_keeps_lowest_bit() {
+        let mut array = Array::new("[Z".to_string(), 2);
+
+        array.store(0, Value::Int(1)).unwrap();
+        array.store(1, Value::Int(2)).unwrap();
+
+        assert_eq!(array.load(0).unwrap(), Value::Int(1));
+        assert_eq!(array.load(1).unwrap(), Value::Int(0));
+    }
+
+    #[test]
+    fn boolean_and_char_type_names() {
+        assert_eq!(Array::new("[Z".to_string(), 0).type_name(), "[Z");
+        assert_eq!(Array::new("[C".to_string(), 0).type_name(), "[C");
+    }
+
+    #[test]
+    fn reference_defaults_to_null() {
+        let array = Array::new("[Ljava/lang/String;".to_string(), 1);
+
+        assert_eq!(array.load(0).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn store_out_of_bounds() {
+        let mut array = Array::new("[I".to_string(), 1);
+
+        assert!(array.store(1, Value::Int(0)).is_err());
+    }
+}
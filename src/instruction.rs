@@ -0,0 +1,415 @@
+//! Decoding a method's code into instructions: one at a time, as cached on the method for those
+//! whose operands are too costly to read from the bytes each time they run, or all of them in turn
+//! with [`Instructions`], as the checks a verifying runtime makes of a method's code do.
+
+use crate::class_file::Code;
+use crate::opcodes::opcode_name;
+use anyhow::{anyhow, Error};
+
+/// A decoded instruction. Jump targets are absolute pcs in the method's code, resolved from the
+/// offsets relative to the instruction that the bytecode holds.
+#[derive(Debug, PartialEq)]
+pub enum Instruction {
+    /// A `tableswitch`, with the target for each index from `low` in turn
+    TableSwitch { default: usize, low: i32, targets: Vec<usize> },
+    /// A `lookupswitch`, with its keys and their targets sorted by key
+    LookupSwitch { default: usize, pairs: Vec<(i32, usize)> },
+    /// A load of a local by a `wide` `iload`, `lload`, `fload`, `dload` or `aload`, the opcode given
+    Load { opcode: u8, index: u16 },
+    /// A store to a local by a `wide` `istore`, `lstore`, `fstore`, `dstore` or `astore`
+    Store { opcode: u8, index: u16 },
+    /// A `wide` `iinc`, whose constant is widened along with its index
+    Iinc { index: u16, constant: i16 },
+    /// A conditional branch, `goto` or `jsr`, in their two and four byte offset forms, with the
+    /// target or `None` for one before the start of the code
+    Branch { opcode: u8, target: Option<usize> },
+    /// Any other instruction, whose operands the interpreter reads as it runs it
+    Other { opcode: u8 },
+}
+
+impl Instruction {
+    /// Decodes the instruction at `pc` in a method's code.
+    pub fn decode(code: &[u8], pc: usize, method_name: &str, verify: bool) -> Result<Self, Error> {
+        let reader = Operands { code, pc };
+        match code.get(pc) {
+            Some(0xAA | 0xAB) => Self::decode_switch(code, pc, method_name, verify),
+            Some(0xC4) => Self::decode_wide(code, pc, method_name),
+            Some(&opcode @ (0x99..=0xA8 | 0xC6 | 0xC7)) =>
+                Ok(Instruction::Branch { opcode, target: reader.branch_target(reader.read_i16(1)? as isize) }),
+            Some(&opcode @ (0xC8 | 0xC9)) =>
+                Ok(Instruction::Branch { opcode, target: reader.branch_target(reader.read_i32(1)? as isize) }),
+            Some(&opcode) => Ok(Instruction::Other { opcode }),
+            None => Err(anyhow!("no instruction at pc {} in {}", pc, method_name)),
+        }
+    }
+
+    /// Decodes the instruction a `wide` at `pc` modifies, taking the local index, and iinc's
+    /// constant, as two bytes. Only loads, stores, `iinc` and `ret` may be widened.
+    fn decode_wide(code: &[u8], pc: usize, method_name: &str) -> Result<Self, Error> {
+        let reader = Operands { code, pc };
+        let opcode = reader.read_u8(1)?;
+        let index = reader.read_i16(2)? as u16;
+        match opcode {
+            0x15..=0x19 => Ok(Instruction::Load { opcode, index }),
+            0x36..=0x3A => Ok(Instruction::Store { opcode, index }),
+            0x84 => Ok(Instruction::Iinc { index, constant: reader.read_i16(4)? }),
+            0xA9 => Err(anyhow!("wide ret unsupported at pc {} in {}", pc, method_name)),
+            _ => Err(anyhow!("java.lang.VerifyError: wide applied to {} at pc {} in {}",
+                opcode_name(opcode), pc, method_name)),
+        }
+    }
+
+    /// The length in bytes of the instruction as it appears in the code, any `wide` included, for
+    /// the `wide` instructions, whose length doesn't depend on their alignment or opcode alone.
+    pub fn length(&self) -> Option<usize> {
+        match self {
+            Instruction::Load { .. } | Instruction::Store { .. } => Some(4),
+            Instruction::Iinc { .. } => Some(6),
+            _ => None,
+        }
+    }
+
+    /// The pcs the instruction may jump to, `None` for a branch before the start of the code. A
+    /// branch's fall through to the next instruction isn't included.
+    pub fn jump_targets(&self) -> Vec<Option<usize>> {
+        match self {
+            Instruction::TableSwitch { default, targets, .. } =>
+                [*default].iter().chain(targets).map(|target| Some(*target)).collect(),
+            Instruction::LookupSwitch { default, pairs } =>
+                [*default].into_iter().chain(pairs.iter().map(|(_, target)| *target)).map(Some).collect(),
+            Instruction::Branch { target, .. } => vec![*target],
+            _ => vec![],
+        }
+    }
+
+    /// Decodes the tableswitch or lookupswitch at `pc` in a method's code. When verifying, a table
+    /// whose high bound is below its low bound or a lookup with keys out of order is a VerifyError.
+    /// Otherwise such a table has no cases, and a lookup's keys are sorted with the first of any
+    /// duplicates kept, matching what searching them in turn would find.
+    pub fn decode_switch(code: &[u8], pc: usize, method_name: &str, verify: bool) -> Result<Self, Error> {
+        let reader = Operands { code, pc };
+        // The operands are padded to start at a multiple of four bytes from the start of the code
+        let operands = 4 - pc % 4;
+        let default = reader.target(operands)?;
+        match code.get(pc) {
+            Some(0xAA) => {
+                let low = reader.read_i32(operands + 4)?;
+                let high = reader.read_i32(operands + 8)?;
+                if verify && high < low {
+                    Err(anyhow!("java.lang.VerifyError: tableswitch at pc {} in {} has high {} below low {}",
+                        pc, method_name, high, low))?
+                }
+                let count = (high as i64 - low as i64 + 1).max(0) as usize;
+                let targets = (0..count)
+                    .map(|case| reader.target(operands + 12 + case * 4))
+                    .collect::<Result<_, _>>()?;
+                Ok(Instruction::TableSwitch { default, low, targets })
+            }
+            Some(0xAB) => {
+                let pair_count = reader.read_i32(operands + 4)?;
+                if pair_count < 0 {
+                    Err(anyhow!("lookupswitch at pc {} has negative pair count {}", pc, pair_count))?
+                }
+                let mut pairs: Vec<(i32, usize)> = Vec::with_capacity(pair_count as usize);
+                for pair in 0..pair_count as usize {
+                    let key = reader.read_i32(operands + 8 + pair * 8)?;
+                    if verify && pairs.last().is_some_and(|&(previous, _)| previous >= key) {
+                        Err(anyhow!("java.lang.VerifyError: lookupswitch at pc {} in {} has unsorted key {}",
+                            pc, method_name, key))?
+                    }
+                    pairs.push((key, reader.target(operands + 12 + pair * 8)?));
+                }
+                // A stable sort keeps duplicate keys in order for dedup to keep the first
+                pairs.sort_by_key(|&(key, _)| key);
+                pairs.dedup_by_key(|&mut (key, _)| key);
+                Ok(Instruction::LookupSwitch { default, pairs })
+            }
+            _ => Err(anyhow!("no switch at pc {} in {}", pc, method_name)),
+        }
+    }
+
+    /// The pc a switch jumps to for the given index or key, or `None` if the instruction isn't a switch.
+    pub fn switch_target(&self, key: i32) -> Option<usize> {
+        match self {
+            Instruction::TableSwitch { default, low, targets } => Some(usize::try_from(key as i64 - *low as i64).ok()
+                .and_then(|case| targets.get(case))
+                .copied()
+                .unwrap_or(*default)),
+            Instruction::LookupSwitch { default, pairs } => Some(pairs.binary_search_by_key(&key, |&(key, _)| key)
+                .map_or(*default, |pair| pairs[pair].1)),
+            _ => None,
+        }
+    }
+}
+
+/// The length in bytes of the instruction at `pc`, its opcode and operands together. Opcodes the
+/// specification doesn't assign have a length of one, leaving them for the interpreter to reject.
+pub fn instruction_length(code: &[u8], pc: usize) -> Result<usize, Error> {
+    let reader = Operands { code, pc };
+    let opcode = *code.get(pc).ok_or_else(|| anyhow!("no instruction at pc {}", pc))?;
+    Ok(match opcode {
+        0x10 | 0x12 | 0x15..=0x19 | 0x36..=0x3A | 0xA9 | 0xBC => 2,
+        0x11 | 0x13 | 0x14 | 0x84 | 0x99..=0xA8 | 0xB2..=0xB8 | 0xBB | 0xBD | 0xC0 | 0xC1 | 0xC6 | 0xC7 => 3,
+        0xC5 => 4,
+        0xB9 | 0xBA | 0xC8 | 0xC9 => 5,
+        // wide, which widens the local index of a load, store or ret, and the constant of an iinc too
+        0xC4 if reader.read_u8(1)? == 0x84 => 6,
+        0xC4 => 4,
+        0xAA => {
+            let operands = 4 - pc % 4;
+            let count = reader.read_i32(operands + 8)? as i64 - reader.read_i32(operands + 4)? as i64 + 1;
+            operands + 12 + count.max(0) as usize * 4
+        }
+        0xAB => {
+            let operands = 4 - pc % 4;
+            operands + 8 + reader.read_i32(operands + 4)?.max(0) as usize * 8
+        }
+        _ => 1,
+    })
+}
+
+/// Checks that every jump in a method's code, by a branch, goto or switch, lands on the start of an
+/// instruction within the code, failing with a VerifyError for the first that doesn't.
+pub fn verify_jumps(code: &Code, method_name: &str) -> Result<(), Error> {
+    let instructions = code.iter_instructions(method_name, true).collect::<Result<Vec<_>, _>>()?;
+    let code = &code.code;
+    let mut starts = vec![false; code.len()];
+    for (pc, _) in &instructions {
+        starts[*pc] = true;
+    }
+    for (pc, instruction) in &instructions {
+        for target in instruction.jump_targets() {
+            match target.filter(|target| *target < code.len()) {
+                Some(target) if !starts[target] => Err(anyhow!(
+                    "java.lang.VerifyError: jump at pc {} in {} to pc {}, which isn't the start of an instruction",
+                    pc, method_name, target))?,
+                Some(_) => {}
+                None => Err(anyhow!("java.lang.VerifyError: jump at pc {} in {} outside the {} bytes of its code",
+                    pc, method_name, code.len()))?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Decodes each instruction of a method's code in turn, giving its pc along with it. Decoding stops
+/// after the first failure, such as an instruction running past the end of the code.
+pub struct Instructions<'a> {
+    code: &'a [u8],
+    pc: usize,
+    method_name: &'a str,
+    verify: bool,
+}
+
+impl<'a> Instructions<'a> {
+    /// Decodes the code of the named method, checking the constraints on switches if verifying.
+    pub fn new(code: &'a [u8], method_name: &'a str, verify: bool) -> Self {
+        Instructions { code, pc: 0, method_name, verify }
+    }
+
+    fn decode_next(&self) -> Result<(Instruction, usize), Error> {
+        let length = instruction_length(self.code, self.pc)?;
+        if self.pc + length > self.code.len() {
+            Err(anyhow!("java.lang.VerifyError: instruction at pc {} in {} runs past the end of the code",
+                self.pc, self.method_name))?
+        }
+        Ok((Instruction::decode(self.code, self.pc, self.method_name, self.verify)?, length))
+    }
+}
+
+impl Iterator for Instructions<'_> {
+    type Item = Result<(usize, Instruction), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pc >= self.code.len() {
+            return None;
+        }
+        let pc = self.pc;
+        match self.decode_next() {
+            Ok((instruction, length)) => {
+                self.pc += length;
+                Some(Ok((pc, instruction)))
+            }
+            Err(error) => {
+                self.pc = self.code.len();
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// Reads the operands of the instruction at `pc`, at offsets from it.
+struct Operands<'a> {
+    code: &'a [u8],
+    pc: usize,
+}
+
+impl Operands<'_> {
+    fn read_u8(&self, offset: usize) -> Result<u8, Error> {
+        self.code.get(self.pc + offset).copied()
+            .ok_or_else(|| anyhow!("operand at pc {} out of bounds", self.pc + offset))
+    }
+
+    fn read_i16(&self, offset: usize) -> Result<i16, Error> {
+        Ok(i16::from_be_bytes([self.read_u8(offset)?, self.read_u8(offset + 1)?]))
+    }
+
+    fn read_i32(&self, offset: usize) -> Result<i32, Error> {
+        let start = self.pc + offset;
+        let bytes = self.code.get(start..start + 4)
+            .ok_or_else(|| anyhow!("operand at pc {} out of bounds", start))?;
+        Ok(i32::from_be_bytes(bytes.try_into()?))
+    }
+
+    /// The absolute pc a jump by `offset` from the instruction lands on, or `None` before the code.
+    fn branch_target(&self, offset: isize) -> Option<usize> {
+        self.pc.checked_add_signed(offset)
+    }
+
+    /// The absolute pc of the jump target whose offset from the instruction is at `offset`.
+    fn target(&self, offset: usize) -> Result<usize, Error> {
+        let jump = self.read_i32(offset)?;
+        self.pc.checked_add_signed(jump as isize).ok_or_else(|| anyhow!("switch to negative pc"))
+    }
+}
+
+#[cfg(test)]
+mod instruction_tests {
+    use super::*;
+
+    /// Checks the jumps of a method with the given bytecode.
+    fn verify(code: &[u8]) -> Result<(), Error> {
+        verify_jumps(&Code { max_stack: 4, max_locals: 4, code: code.to_vec(), exception_table: vec![] }, "test")
+    }
+
+    /// A lookupswitch at pc 1, after a nop, padded by two bytes, with the given default and pairs
+    /// of key and offset.
+    fn lookup_switch(default: i32, pairs: &[(i32, i32)]) -> Vec<u8> {
+        let mut code = vec![0x00, 0xAB, 0, 0];
+        code.extend(default.to_be_bytes());
+        code.extend((pairs.len() as i32).to_be_bytes());
+        for (key, offset) in pairs {
+            code.extend(key.to_be_bytes());
+            code.extend(offset.to_be_bytes());
+        }
+        code
+    }
+
+    #[test]
+    fn lookup_switch_targets() {
+        let code = lookup_switch(40, &[(-5, 20), (3, 30)]);
+
+        let switch = Instruction::decode_switch(&code, 1, "test", true).unwrap();
+
+        assert_eq!(switch, Instruction::LookupSwitch { default: 41, pairs: vec![(-5, 21), (3, 31)] });
+        assert_eq!(switch.switch_target(3), Some(31));
+        assert_eq!(switch.switch_target(-5), Some(21));
+        assert_eq!(switch.switch_target(0), Some(41));
+    }
+
+    #[test]
+    fn unsorted_lookup_switch_keeps_first_match() {
+        let code = lookup_switch(40, &[(3, 30), (-5, 20), (3, 10)]);
+
+        let switch = Instruction::decode_switch(&code, 1, "test", false).unwrap();
+
+        assert_eq!(switch.switch_target(3), Some(31));
+        assert_eq!(switch.switch_target(-5), Some(21));
+        assert_eq!(Instruction::decode_switch(&code, 1, "test", true).unwrap_err().to_string(),
+            "java.lang.VerifyError: lookupswitch at pc 1 in test has unsorted key -5");
+    }
+
+    #[test]
+    fn instruction_lengths() {
+        let lookup = lookup_switch(40, &[(-5, 20), (3, 30)]);
+        assert_eq!(instruction_length(&lookup, 1).unwrap(), 3 + 8 + 2 * 8);
+        // wide iload, wide iinc
+        assert_eq!(instruction_length(&[0xC4, 0x15, 0x01, 0x00], 0).unwrap(), 4);
+        assert_eq!(instruction_length(&[0xC4, 0x84, 0x01, 0x00, 0x00, 0x01], 0).unwrap(), 6);
+        assert_eq!(instruction_length(&[0xB9, 0x00, 0x01, 0x01, 0x00], 0).unwrap(), 5);
+    }
+
+    #[test]
+    fn switch_into_padding_rejected() {
+        // A default target of 2 lands in the lookupswitch's padding
+        let mut code = lookup_switch(1, &[]);
+        code.push(0xB1);
+
+        assert_eq!(verify(&code).unwrap_err().to_string(),
+            "java.lang.VerifyError: jump at pc 1 in test to pc 2, which isn't the start of an instruction");
+        let mut code = lookup_switch(11, &[]);
+        code.push(0xB1);
+        assert!(verify(&code).is_ok());
+    }
+
+    #[test]
+    fn branch_targets_verified() {
+        // iload_0, ifeq +6, bipush 7, ireturn, iconst_0, ireturn
+        let code = [0x1A, 0x99, 0x00, 0x06, 0x10, 0x07, 0xAC, 0x03, 0xAC];
+        let instructions = Instructions::new(&code, "test", true).collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(instructions[1].1, Instruction::Branch { opcode: 0x99, target: Some(7) });
+        assert!(verify(&code).is_ok());
+        // ifeq +8, to just past the end
+        let code = [0x1A, 0x99, 0x00, 0x08, 0x10, 0x07, 0xAC, 0x03, 0xAC];
+        assert_eq!(verify(&code).unwrap_err().to_string(),
+            "java.lang.VerifyError: jump at pc 1 in test outside the 9 bytes of its code");
+    }
+
+    #[test]
+    fn branch_into_instruction_rejected() {
+        // iload_0, ifeq +4 into bipush's operand, bipush 7, ireturn
+        let code = [0x1A, 0x99, 0x00, 0x04, 0x10, 0x07, 0xAC];
+
+        assert_eq!(verify(&code).unwrap_err().to_string(),
+            "java.lang.VerifyError: jump at pc 1 in test to pc 5, which isn't the start of an instruction");
+    }
+
+    #[test]
+    fn wide_instructions() {
+        // wide aload 300, wide iinc 300 by -2, wide return
+        let code = [0xC4, 0x19, 0x01, 0x2C, 0xC4, 0x84, 0x01, 0x2C, 0xFF, 0xFE, 0xC4, 0xB1, 0x00, 0x00];
+
+        assert_eq!(Instruction::decode(&code, 0, "test", false).unwrap(), Instruction::Load { opcode: 0x19, index: 300 });
+        assert_eq!(Instruction::decode(&code, 4, "test", false).unwrap(), Instruction::Iinc { index: 300, constant: -2 });
+        assert_eq!(Instruction::decode(&code, 10, "test", false).unwrap_err().to_string(),
+            "java.lang.VerifyError: wide applied to return at pc 10 in test");
+    }
+
+    #[test]
+    fn iterate_table_switch() {
+        // iload_0, tableswitch of 1 and 2 padded by two bytes, iconst_1, ireturn, iconst_2, ireturn
+        let mut code = vec![0x1A, 0xAA, 0x00, 0x00];
+        for operand in [24, 1, 2, 20, 22_i32] {
+            code.extend(operand.to_be_bytes());
+        }
+        code.extend([0x04, 0xAC, 0x05, 0xAC, 0x03, 0xAC]);
+
+        let instructions = Instructions::new(&code, "test", true).collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(instructions, vec![
+            (0, Instruction::Other { opcode: 0x1A }),
+            (1, Instruction::TableSwitch { default: 25, low: 1, targets: vec![21, 23] }),
+            (24, Instruction::Other { opcode: 0x04 }),
+            (25, Instruction::Other { opcode: 0xAC }),
+            (26, Instruction::Other { opcode: 0x05 }),
+            (27, Instruction::Other { opcode: 0xAC }),
+            (28, Instruction::Other { opcode: 0x03 }),
+            (29, Instruction::Other { opcode: 0xAC }),
+        ]);
+    }
+
+    #[test]
+    fn table_switch_targets() {
+        let mut code = vec![0xAA, 0, 0, 0];
+        for operand in [100, i32::MAX - 1, i32::MAX, 10, 20] {
+            code.extend(operand.to_be_bytes());
+        }
+
+        let switch = Instruction::decode_switch(&code, 0, "test", true).unwrap();
+
+        assert_eq!(switch.switch_target(i32::MAX - 1), Some(10));
+        assert_eq!(switch.switch_target(i32::MAX), Some(20));
+        assert_eq!(switch.switch_target(i32::MIN), Some(100));
+    }
+}
@@ -0,0 +1,2344 @@
+use crate::class_file::{Const, ACC_SUPER};
+use crate::descriptor::{FieldType, MethodDescriptor};
+use crate::heap::{HeapObject, Object, Reference};
+use crate::instruction::Instruction;
+use crate::natives::find_native;
+use crate::opcodes::opcode_name;
+use crate::runtime::{JavaException, Runtime, RuntimeClass, RuntimeField, RuntimeMethod};
+use crate::string_concat::{string_concat, STRING_CONCAT_FACTORY};
+use crate::value::Value;
+use anyhow::{anyhow, Error};
+use log::{debug, trace};
+use std::cmp::Ordering;
+use std::io::Write;
+use std::rc::Rc;
+use std::sync::atomic;
+
+pub struct Thread {
+    frames: Vec<Frame>,
+}
+
+struct Frame {
+    class: Rc<RuntimeClass>,
+    method: Rc<RuntimeMethod>,
+    pc: usize,
+    /// The local variables, where a long or double takes two slots as the bytecode indexes them,
+    /// the second holding a placeholder null
+    locals: Vec<Value>,
+    /// The operand stack, with one entry per value whatever its category. Only the instructions that
+    /// move stack entries without looking at their type, such as dup2 and pop2, count a long or
+    /// double as two slots.
+    stack: Vec<Value>,
+    /// The monitors this frame has entered and not yet exited, which are released however the frame
+    /// completes
+    monitors: Vec<Reference>,
+}
+
+impl Frame {
+    /// Creates a frame for calling a method with the given arguments, one per parameter (and the
+    /// receiver first for instance methods). Long and double arguments take two local variable slots,
+    /// moving every later argument up one.
+    fn new(class: Rc<RuntimeClass>, method: Rc<RuntimeMethod>, args: Vec<Value>) -> Self {
+        let mut locals = Vec::with_capacity(method.max_locals());
+        for arg in args {
+            locals.push(arg);
+            if arg.slot_count() == 2 {
+                locals.push(Value::Null);
+            }
+        }
+        locals.resize(locals.len().max(method.max_locals()), Value::Null);
+        let stack = Vec::with_capacity(method.max_stack());
+        Frame {
+            class,
+            method,
+            pc: 0,
+            locals,
+            stack,
+            monitors: vec![],
+        }
+    }
+
+    /// Enters the monitor a synchronized method holds while it runs: its receiver's, or its class
+    /// object's for a static method.
+    fn enter_synchronized(&mut self, runtime: &mut Runtime) -> Result<(), Error> {
+        if !self.method.is_synchronized() {
+            return Ok(());
+        }
+        let object = if self.method.is_static() {
+            runtime.class_object(&self.class.this_class)?
+        } else {
+            self.load(0)?.as_reference()?.ok_or_else(|| anyhow!("synchronized method called on null"))?
+        };
+        self.enter_monitor(runtime, object);
+        Ok(())
+    }
+
+    fn enter_monitor(&mut self, runtime: &mut Runtime, object: Reference) {
+        runtime.enter_monitor(object);
+        self.monitors.push(object);
+    }
+
+    /// Exits a monitor, returning false if it wasn't held. It may have been entered by a caller, in
+    /// which case it isn't this frame's to release.
+    fn exit_monitor(&mut self, runtime: &mut Runtime, object: Reference) -> bool {
+        if let Some(idx) = self.monitors.iter().rposition(|entered| *entered == object) {
+            self.monitors.remove(idx);
+        }
+        runtime.exit_monitor(object)
+    }
+
+    /// Resumes this frame after the method called by its current invoke instruction returns.
+    fn resume(&mut self, value: Option<Value>) -> Result<(), Error> {
+        self.pc += match self.read_u8(0)? {
+            0xB9 | 0xBA => 5, // invokeinterface, invokedynamic
+            _ => 3,
+        };
+        self.stack.extend(value);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<Value, Error> {
+        self.stack.pop().ok_or_else(|| anyhow!("pop from empty operand stack"))
+    }
+
+    /// Pops a category 1 value, one that isn't a long or double, for the instructions that move stack
+    /// entries without looking at their type. Debug builds check the category, catching a long or
+    /// double that the bytecode treats as one half of a value.
+    fn pop_category1(&mut self) -> Result<Value, Error> {
+        let value = self.pop()?;
+        debug_assert_eq!(value.slot_count(), 1, "expected a category 1 value on the operand stack, found {:?}", value);
+        Ok(value)
+    }
+
+    /// Copies the top value, which must be category 1, below the two category 1 values under it, or
+    /// below the one long or double under it.
+    fn dup_x2(&mut self) -> Result<(), Error> {
+        let value = self.pop_category1()?;
+        let under = self.pop()?;
+        if under.slot_count() == 2 {
+            self.stack.extend([value, under, value]);
+        } else {
+            let bottom = self.pop_category1()?;
+            self.stack.extend([value, bottom, under, value]);
+        }
+        Ok(())
+    }
+
+    /// Pops two slots' worth of the operand stack, giving either the one long or double on top or
+    /// the two category 1 values on top, in stack order.
+    fn pop_two_slots(&mut self) -> Result<Vec<Value>, Error> {
+        let top = self.pop()?;
+        if top.slot_count() == 2 {
+            return Ok(vec![top]);
+        }
+        let under = self.pop_category1()?;
+        Ok(vec![under, top])
+    }
+
+    /// Copies the top two slots, one long or double or two category 1 values, below the category 1
+    /// value under them.
+    fn dup2_x1(&mut self) -> Result<(), Error> {
+        let top = self.pop_two_slots()?;
+        let under = self.pop_category1()?;
+        self.stack.extend(&top);
+        self.stack.push(under);
+        self.stack.extend(top);
+        Ok(())
+    }
+
+    /// Copies the top two slots below the two slots under them, each one long or double or two
+    /// category 1 values.
+    fn dup2_x2(&mut self) -> Result<(), Error> {
+        let top = self.pop_two_slots()?;
+        let under = self.pop_two_slots()?;
+        self.stack.extend(&top);
+        self.stack.extend(under);
+        self.stack.extend(top);
+        Ok(())
+    }
+
+    fn load(&self, idx: usize) -> Result<Value, Error> {
+        self.locals.get(idx).copied().ok_or_else(|| anyhow!("local variable index {} out of bounds", idx))
+    }
+
+    /// Stores a value in the local variable at `idx`, along with the next local for longs and
+    /// doubles, which occupy two slots.
+    fn store(&mut self, idx: usize, value: Value) -> Result<(), Error> {
+        let slot_count = value.slot_count();
+        let locals = self.locals.get_mut(idx..idx + slot_count).ok_or_else(|| anyhow!("local variable index {} out of bounds", idx))?;
+        locals[0] = value;
+        if let Some(second) = locals.get_mut(1) {
+            // The second slot can't be loaded on its own
+            *second = Value::Null;
+        }
+        Ok(())
+    }
+
+    fn read_u8(&self, offset: usize) -> Result<u8, Error> {
+        self.method.code.code.get(self.pc + offset).copied().ok_or_else(|| anyhow!("operand at pc {} out of bounds", self.pc + offset))
+    }
+
+    fn read_u16(&self, offset: usize) -> Result<u16, Error> {
+        Ok(u16::from_be_bytes([self.read_u8(offset)?, self.read_u8(offset + 1)?]))
+    }
+}
+
+pub fn create_thread(class: Rc<RuntimeClass>, method: Rc<RuntimeMethod>, args: Vec<Value>) -> Thread {
+    Thread {
+        frames: vec![Frame::new(class, method, args)],
+    }
+}
+
+/// Pushes a frame for calling a method, entering its monitor if it's synchronized.
+fn push_frame(runtime: &mut Runtime, thread: &mut Thread, class: Rc<RuntimeClass>, method: Rc<RuntimeMethod>,
+              args: Vec<Value>) -> Result<(), Error> {
+    let mut frame = Frame::new(class, method, args);
+    frame.enter_synchronized(runtime)?;
+    thread.frames.push(frame);
+    runtime.frames_pushed += 1;
+    Ok(())
+}
+
+/// Pops the current frame, releasing the monitors it still holds, whether it's returning or being
+/// unwound by an exception. Javac releases them itself with handlers around synchronized blocks, but
+/// synchronized methods and other compilers' bytecode rely on this.
+fn pop_frame(runtime: &mut Runtime, thread: &mut Thread) {
+    if let Some(frame) = thread.frames.pop() {
+        for object in frame.monitors {
+            runtime.exit_monitor(object);
+        }
+    }
+}
+
+/// Runs the thread until its outermost frame returns, producing that frame's return value.
+pub fn run_thread(runtime: &mut Runtime, thread: &mut Thread) -> Result<Option<Value>, Error> {
+    loop {
+        match execute(runtime, thread) {
+            Err(error) if error.is::<JavaException>() => {
+                let exception = error.downcast_ref::<JavaException>().unwrap().0;
+                if !unwind(runtime, thread, exception)? {
+                    Err(anyhow!("Exception in thread \"main\" {}", runtime.describe_exception(exception)?))?
+                }
+            }
+            result => {
+                // Cancelling unwinds every frame, of this thread and of those it was run from, such as
+                // the one whose instruction triggered the <clinit> this thread runs
+                if result.is_err() && runtime.cancelled.load(atomic::Ordering::Relaxed) {
+                    while !thread.frames.is_empty() {
+                        pop_frame(runtime, thread);
+                    }
+                }
+                return result;
+            }
+        }
+    }
+}
+
+/// Calls a static method from outside the interpreter, initializing its class first as invokestatic
+/// would, and runs it to completion on its own thread.
+pub fn invoke_static(runtime: &mut Runtime, class_name: &str, name: &str, descriptor: &str, args: Vec<Value>)
+                     -> Result<Option<Value>, Error> {
+    let (class, method) = runtime.resolve_method(class_name, name, descriptor)?;
+    if !method.is_static() {
+        Err(anyhow!("java.lang.IncompatibleClassChangeError: Expected static method '{} {}.{}'",
+            method.descriptor, class.this_class.replace('/', "."), method.name))?
+    }
+    if args.len() != method.descriptor.parameters.len() {
+        Err(anyhow!("{}.{}{} takes {} arguments but was given {}", class.this_class.replace('/', "."),
+            method.name, method.descriptor, method.descriptor.parameters.len(), args.len()))?
+    }
+    initialize_class(runtime, &class)?;
+
+    if method.is_native() {
+        return call_native(runtime, &class, &method, args);
+    }
+    if method.is_trivial() {
+        return Ok(None);
+    }
+    let mut thread = Thread { frames: vec![] };
+    push_frame(runtime, &mut thread, class, method, args)?;
+    run_thread(runtime, &mut thread)
+}
+
+/// Calls a native method, implemented either by a host class or by the runtime itself.
+fn call_native(runtime: &mut Runtime, class: &RuntimeClass, method: &RuntimeMethod, args: Vec<Value>)
+               -> Result<Option<Value>, Error> {
+    let descriptor = method.descriptor.to_string();
+    trace!("calling native {}.{}{}", class.this_class.replace('/', "."), method.name, descriptor);
+    if let Some(host_method) = runtime.host_method(&class.this_class, &method.name, &descriptor) {
+        return host_method(runtime, args);
+    }
+    let native = find_native(&class.this_class, &method.name, &descriptor).ok_or_else(|| anyhow!(
+        "java.lang.UnsatisfiedLinkError: {}.{}{}", class.this_class.replace('/', "."), method.name, descriptor))?;
+    native(runtime, args)
+}
+
+/// Pops frames until one has a handler for the exception, then resumes that frame at the handler with
+/// only the exception on its operand stack. Returns false if no frame handles the exception.
+fn unwind(runtime: &mut Runtime, thread: &mut Thread, exception: Reference) -> Result<bool, Error> {
+    while let Some(frame) = thread.frames.last_mut() {
+        if let Some(handler_pc) = find_handler(runtime, frame, exception)? {
+            frame.stack.clear();
+            frame.stack.push(Value::Reference(exception));
+            frame.pc = handler_pc as usize;
+            return Ok(true);
+        }
+        pop_frame(runtime, thread);
+    }
+    Ok(false)
+}
+
+/// Finds the first handler of the frame's exception table covering its pc that catches the exception.
+fn find_handler(runtime: &Runtime, frame: &Frame, exception: Reference) -> Result<Option<u16>, Error> {
+    let type_name = runtime.heap.get(exception)?.type_name();
+    for handler in &frame.method.code.exception_table {
+        if !(handler.start_pc as usize..handler.end_pc as usize).contains(&frame.pc) {
+            continue;
+        }
+        // A catch type of 0 catches everything, as compiled for finally blocks
+        if handler.catch_type == 0 {
+            return Ok(Some(handler.handler_pc));
+        }
+        let catch_type = frame.class.const_pool.resolve_class_name(handler.catch_type)?;
+        if runtime.is_assignable(type_name, catch_type)? {
+            return Ok(Some(handler.handler_pc));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether `execute` implements an opcode, as reported by `--version`. Kept in step with its
+/// dispatch, which the tests check by running every opcode.
+pub(crate) fn is_implemented(opcode: u8) -> bool {
+    matches!(opcode, 0x01..=0x2E | 0x32..=0x4F | 0x53..=0x5E | 0x60 | 0x64 | 0x68 | 0x6C | 0x70 | 0x84
+        | 0x89 | 0x8A | 0x8C | 0x8F | 0x91..=0x93 | 0x95..=0xA7 | 0xAA..=0xB8 | 0xBA..=0xC4 | 0xC6 | 0xC7)
+}
+
+/// How many instructions run between checks of the runtime's cancellation flag.
+const CANCELLATION_CHECK_INTERVAL: u32 = 1024;
+
+/// Runs instructions until the outermost frame returns. Callers' pcs stay on their invoke instruction
+/// until the callee returns, so exception handlers covering the invoke are found while unwinding.
+fn execute(runtime: &mut Runtime, thread: &mut Thread) -> Result<Option<Value>, Error> {
+    loop {
+        runtime.unchecked_instructions += 1;
+        if runtime.unchecked_instructions >= CANCELLATION_CHECK_INTERVAL {
+            runtime.unchecked_instructions = 0;
+            if runtime.cancelled.load(atomic::Ordering::Relaxed) {
+                Err(anyhow!("java.lang.InterruptedException: execution was cancelled"))?
+            }
+        }
+        // Fetch the current frame afresh for every instruction, as invokes and returns change it
+        let Some(frame) = thread.frames.last_mut() else {
+            return Ok(None);
+        };
+        if runtime.verify {
+            frame.method.verify_jumps()?;
+        }
+        let pc = frame.pc;
+        let instr = *frame.method.code.code.get(pc).ok_or_else(|| anyhow!("pc {} past the end of the {} bytes of {}.{}{}",
+            pc, frame.method.code_len(), frame.class.this_class, frame.method.name, frame.method.descriptor))?;
+        if let Some(counts) = &mut runtime.opcode_counts {
+            counts[instr as usize] += 1;
+        }
+        let location = runtime.trace.then(|| {
+            format!("{}.{}{}", frame.class.this_class, frame.method.name, frame.method.descriptor)
+        });
+        match instr {
+            0x01 => { // aconst_null
+                frame.stack.push(Value::Null);
+                frame.pc += 1;
+            }
+            0x02..=0x08 => { // iconst_<i>
+                frame.stack.push(Value::Int(instr as i32 - 0x03));
+                frame.pc += 1;
+            }
+            0x09 | 0x0A => { // lconst_<l>
+                frame.stack.push(Value::Long(instr as i64 - 0x09));
+                frame.pc += 1;
+            }
+            0x0B..=0x0D => { // fconst_<f>
+                frame.stack.push(Value::Float((instr - 0x0B) as f32));
+                frame.pc += 1;
+            }
+            0x0E | 0x0F => { // dconst_<d>
+                frame.stack.push(Value::Double((instr - 0x0E) as f64));
+                frame.pc += 1;
+            }
+            0x10 => { // bipush
+                frame.stack.push(Value::Int(frame.read_u8(1)? as i8 as i32));
+                frame.pc += 2;
+            }
+            0x11 => { // sipush
+                frame.stack.push(Value::Int(frame.read_u16(1)? as i16 as i32));
+                frame.pc += 3;
+            }
+            0x12 | 0x13 => { // ldc, ldc_w
+                let (idx, length) = if instr == 0x12 { (frame.read_u8(1)? as u16, 2) } else { (frame.read_u16(1)?, 3) };
+                let const_pool = &frame.class.const_pool;
+                let value = match const_pool.get_const(idx)? {
+                    Const::Integer(value) => Value::Int(*value),
+                    Const::Float(value) => Value::Float(*value),
+                    Const::String(string) => Value::Reference(runtime.intern(&const_pool.get_utf8(string.string_idx)?.bytes)?),
+                    Const::Class(class) => Value::Reference(runtime.class_object(&const_pool.get_utf8(class.name_idx)?.bytes)?),
+                    const_item => Err(anyhow!("ldc of unsupported constant {:?}", const_item))?,
+                };
+                frame.stack.push(value);
+                frame.pc += length;
+            }
+            0x14 => { // ldc2_w
+                let value = match frame.class.const_pool.get_const(frame.read_u16(1)?)? {
+                    Const::Long(value) => Value::Long(*value),
+                    Const::Double(value) => Value::Double(*value),
+                    const_item => Err(anyhow!("ldc2_w of unsupported constant {:?}", const_item))?,
+                };
+                frame.stack.push(value);
+                frame.pc += 3;
+            }
+            0x15..=0x19 => { // iload, lload, fload, dload, aload
+                load_local(frame, instr, frame.read_u8(1)? as usize)?;
+                frame.pc += 2;
+            }
+            0x1A..=0x2D => { // iload_<n>, lload_<n>, fload_<n>, dload_<n>, aload_<n>
+                load_local(frame, 0x15 + (instr - 0x1A) / 4, ((instr - 0x1A) % 4) as usize)?;
+                frame.pc += 1;
+            }
+            0x2E | 0x32..=0x35 => { // iaload, aaload, baload, caload, saload
+                array_load(runtime, frame)?;
+                frame.pc += 1;
+            }
+            0x36..=0x3A => { // istore, lstore, fstore, dstore, astore
+                store_local(frame, instr, frame.read_u8(1)? as usize)?;
+                frame.pc += 2;
+            }
+            0x3B..=0x4E => { // istore_<n>, lstore_<n>, fstore_<n>, dstore_<n>, astore_<n>
+                store_local(frame, 0x36 + (instr - 0x3B) / 4, ((instr - 0x3B) % 4) as usize)?;
+                frame.pc += 1;
+            }
+            0x4F | 0x53..=0x56 => { // iastore, aastore, bastore, castore, sastore
+                array_store(runtime, frame)?;
+                frame.pc += 1;
+            }
+            0x57 => { // pop
+                frame.pop_category1()?;
+                frame.pc += 1;
+            }
+            0x58 => { // pop2
+                frame.pop_two_slots()?;
+                frame.pc += 1;
+            }
+            0x59 => { // dup
+                let value = frame.pop_category1()?;
+                frame.stack.extend([value, value]);
+                frame.pc += 1;
+            }
+            0x5A => { // dup_x1
+                let value = frame.pop_category1()?;
+                let under = frame.pop_category1()?;
+                frame.stack.extend([value, under, value]);
+                frame.pc += 1;
+            }
+            0x5B => { // dup_x2
+                frame.dup_x2()?;
+                frame.pc += 1;
+            }
+            0x5C => { // dup2
+                let top = frame.pop_two_slots()?;
+                frame.stack.extend(&top);
+                frame.stack.extend(top);
+                frame.pc += 1;
+            }
+            0x5D => { // dup2_x1
+                frame.dup2_x1()?;
+                frame.pc += 1;
+            }
+            0x5E => { // dup2_x2
+                frame.dup2_x2()?;
+                frame.pc += 1;
+            }
+            0x60 | 0x64 | 0x68 => { // iadd, isub, imul
+                let right = frame.pop()?.as_int()?;
+                let left = frame.pop()?.as_int()?;
+                let result = match instr {
+                    0x60 => left.wrapping_add(right),
+                    0x64 => left.wrapping_sub(right),
+                    _ => left.wrapping_mul(right),
+                };
+                frame.stack.push(Value::Int(result));
+                frame.pc += 1;
+            }
+            0x6C | 0x70 => { // idiv, irem
+                let right = frame.pop()?.as_int()?;
+                let left = frame.pop()?.as_int()?;
+                if right == 0 {
+                    Err(runtime.throw("java/lang/ArithmeticException", "/ by zero"))?
+                }
+                // Dividing the minimum int by -1 overflows back to the minimum, with no remainder
+                let result = if instr == 0x6C { left.wrapping_div(right) } else { left.wrapping_rem(right) };
+                frame.stack.push(Value::Int(result));
+                frame.pc += 1;
+            }
+            0x84 => { // iinc
+                increment_local(frame, frame.read_u8(1)? as usize, frame.read_u8(2)? as i8 as i32)?;
+                frame.pc += 3;
+            }
+            // Rust's casts round to nearest, ties to even, from long to floating point, and truncate
+            // towards zero from floating point to long, saturating and taking NaN to 0, as Java's do
+            0x89 => { // l2f
+                let value = frame.pop()?.as_long()?;
+                frame.stack.push(Value::Float(value as f32));
+                frame.pc += 1;
+            }
+            0x8A => { // l2d
+                let value = frame.pop()?.as_long()?;
+                frame.stack.push(Value::Double(value as f64));
+                frame.pc += 1;
+            }
+            0x8C => { // f2l
+                let value = frame.pop()?.as_float()?;
+                frame.stack.push(Value::Long(value as i64));
+                frame.pc += 1;
+            }
+            0x8F => { // d2l
+                let value = frame.pop()?.as_double()?;
+                frame.stack.push(Value::Long(value as i64));
+                frame.pc += 1;
+            }
+            0x91 => { // i2b
+                let value = frame.pop()?.as_int()?;
+                frame.stack.push(Value::Int(value as i8 as i32));
+                frame.pc += 1;
+            }
+            0x92 => { // i2c
+                let value = frame.pop()?.as_int()?;
+                frame.stack.push(Value::Int(value as u16 as i32));
+                frame.pc += 1;
+            }
+            0x93 => { // i2s
+                let value = frame.pop()?.as_int()?;
+                frame.stack.push(Value::Int(value as i16 as i32));
+                frame.pc += 1;
+            }
+            0x95 | 0x96 => { // fcmpl, fcmpg
+                let right = frame.pop()?.as_float()?;
+                let left = frame.pop()?.as_float()?;
+                frame.stack.push(Value::Int(floating_compare(left.partial_cmp(&right), instr == 0x96)));
+                frame.pc += 1;
+            }
+            0x97 | 0x98 => { // dcmpl, dcmpg
+                let right = frame.pop()?.as_double()?;
+                let left = frame.pop()?.as_double()?;
+                frame.stack.push(Value::Int(floating_compare(left.partial_cmp(&right), instr == 0x98)));
+                frame.pc += 1;
+            }
+            0x99..=0x9E => { // ifeq, ifne, iflt, ifge, ifgt, ifle
+                let value = frame.pop()?.as_int()?;
+                branch(frame, compare(instr - 0x99, value, 0))?;
+            }
+            0x9F..=0xA4 => { // if_icmpeq, if_icmpne, if_icmplt, if_icmpge, if_icmpgt, if_icmple
+                let right = frame.pop()?.as_int()?;
+                let left = frame.pop()?.as_int()?;
+                branch(frame, compare(instr - 0x9F, left, right))?;
+            }
+            0xA5 | 0xA6 => { // if_acmpeq, if_acmpne
+                let right = frame.pop()?.as_reference()?;
+                let left = frame.pop()?.as_reference()?;
+                branch(frame, (left == right) == (instr == 0xA5))?;
+            }
+            0xA7 => { // goto
+                branch(frame, true)?;
+            }
+            0xC4 => { // wide
+                let instruction = frame.method.instruction_at(pc, runtime.verify)?;
+                match *instruction {
+                    Instruction::Load { opcode, index } => load_local(frame, opcode, index as usize)?,
+                    Instruction::Store { opcode, index } => store_local(frame, opcode, index as usize)?,
+                    Instruction::Iinc { index, constant } => increment_local(frame, index as usize, constant as i32)?,
+                    _ => Err(anyhow!("wide decoded as {:?}", instruction))?,
+                }
+                frame.pc += instruction.length().unwrap_or(1);
+            }
+            0xAA | 0xAB => { // tableswitch, lookupswitch
+                let key = frame.pop()?.as_int()?;
+                switch(runtime, frame, key)?;
+            }
+            0xAC..=0xB0 => { // ireturn, lreturn, freturn, dreturn, areturn
+                let value = frame.pop()?;
+                pop_frame(runtime, thread);
+                let Some(caller) = thread.frames.last_mut() else {
+                    trace(runtime, location, pc, instr, Some(&value));
+                    return Ok(Some(value));
+                };
+                caller.resume(Some(value))?;
+                trace(runtime, location, pc, instr, Some(&value));
+                continue;
+            }
+            0xB1 => { // return
+                pop_frame(runtime, thread);
+                let Some(caller) = thread.frames.last_mut() else {
+                    trace(runtime, location, pc, instr, None);
+                    return Ok(None);
+                };
+                caller.resume(None)?;
+                trace(runtime, location, pc, instr, caller.stack.last());
+                continue;
+            }
+            0xB2 => { // getstatic
+                let (class, name, field_type) = resolve_fieldref(runtime, frame)?;
+                let owner = static_field_owner(runtime, class, &name)?;
+                let value = match owner.find_field(&name) {
+                    // Compile-time constants are read without initializing their class
+                    Some(field) if field.is_constant() => match runtime.get_static(&owner.this_class, &name) {
+                        Some(value) => value,
+                        None => constant_value(runtime, &owner, field)?,
+                    },
+                    _ => {
+                        initialize_class(runtime, &owner)?;
+                        runtime.get_static(&owner.this_class, &name).unwrap_or(field_type.default_value())
+                    }
+                };
+                frame.stack.push(value);
+                frame.pc += 3;
+            }
+            0xB3 => { // putstatic
+                let (class, name, _) = resolve_fieldref(runtime, frame)?;
+                let owner = static_field_owner(runtime, class, &name)?;
+                initialize_class(runtime, &owner)?;
+                let value = frame.pop()?;
+                runtime.put_static(&owner.this_class, &name, value);
+                frame.pc += 3;
+            }
+            0xB4 => { // getfield
+                let (class, name, field_type) = resolve_fieldref(runtime, frame)?;
+                let Some(reference) = frame.pop()?.as_reference()? else {
+                    let message = format!("Cannot read field \"{}\" because the object is null", name);
+                    Err(runtime.throw("java/lang/NullPointerException", &message))?
+                };
+                if runtime.is_uninitialized(reference) {
+                    Err(anyhow!("java.lang.VerifyError: Reading field {}.{} of an uninitialized object in {}.{}{}",
+                        class.this_class.replace('/', "."), name, frame.class.this_class.replace('/', "."),
+                        frame.method.name, frame.method.descriptor))?
+                }
+                let HeapObject::Object(object) = runtime.heap.get(reference)? else {
+                    Err(anyhow!("getfield of array {:?}", reference))?
+                };
+                let key = runtime.field_key(&object.class, &class.this_class, &name)?;
+                frame.stack.push(object.fields.get(&key).copied().unwrap_or(field_type.default_value()));
+                frame.pc += 3;
+            }
+            0xB5 => { // putfield
+                let (class, name, _) = resolve_fieldref(runtime, frame)?;
+                let value = frame.pop()?;
+                // Constructors may assign fields before calling their superclass constructor
+                let Some(reference) = frame.pop()?.as_reference()? else {
+                    let message = format!("Cannot assign field \"{}\" because the object is null", name);
+                    Err(runtime.throw("java/lang/NullPointerException", &message))?
+                };
+                let HeapObject::Object(object) = runtime.heap.get(reference)? else {
+                    Err(anyhow!("putfield of array {:?}", reference))?
+                };
+                let key = runtime.field_key(&object.class, &class.this_class, &name)?;
+                if let HeapObject::Object(object) = runtime.heap.get_mut(reference)? {
+                    object.fields.insert(key, value);
+                }
+                frame.pc += 3;
+            }
+            0xB6 if invokes_array_clone(frame) => { // invokevirtual
+                let Some(receiver) = frame.pop()?.as_reference()? else {
+                    Err(runtime.throw("java/lang/NullPointerException", "Cannot invoke \"Object.clone()\" because the array is null"))?
+                };
+                let HeapObject::Array(array) = runtime.heap.get(receiver)? else {
+                    Err(anyhow!("array clone of non-array {:?}", receiver))?
+                };
+                let copy = runtime.heap.allocate(HeapObject::Array(array.clone()))?;
+                frame.stack.push(Value::Reference(copy));
+                frame.pc += 3;
+            }
+            0xB6..=0xB8 => { // invokevirtual, invokespecial, invokestatic
+                let (class, method) = resolve_methodref(runtime, frame)?;
+                // Each argument is a single operand stack entry, whatever its slot count
+                let receiver_count = (instr != 0xB8) as usize;
+                let arg_count = method.descriptor.parameters.len() + receiver_count;
+                if frame.stack.len() < arg_count {
+                    Err(anyhow!("pop from empty operand stack"))?
+                }
+                let args = frame.stack.split_off(frame.stack.len() - arg_count);
+
+                let (class, method) = match instr {
+                    0xB8 => {
+                        initialize_class(runtime, &class)?;
+                        (class, method)
+                    }
+                    _ => {
+                        let Some(receiver) = args[0].as_reference()? else {
+                            let message = format!("Cannot invoke \"{}.{}{}\" because the receiver is null",
+                                class.this_class.replace('/', "."), method.name, method.descriptor);
+                            Err(runtime.throw("java/lang/NullPointerException", &message))?
+                        };
+                        if runtime.is_uninitialized(receiver) {
+                            if instr != 0xB7 || method.name != "<init>" {
+                                Err(anyhow!("java.lang.VerifyError: Calling {}.{}{} on an uninitialized object in {}.{}{}",
+                                    class.this_class.replace('/', "."), method.name, method.descriptor,
+                                    frame.class.this_class.replace('/', "."), frame.method.name, frame.method.descriptor))?
+                            }
+                            runtime.mark_initialized(receiver);
+                        }
+                        if instr == 0xB6 {
+                            select_method(runtime, receiver, &method)?
+                        } else {
+                            select_special_method(runtime, frame, class, method)?
+                        }
+                    }
+                };
+
+                if method.is_native() {
+                    frame.stack.extend(call_native(runtime, &class, &method, args)?);
+                    frame.pc += 3;
+                } else if method.is_trivial() {
+                    // Nothing to run, so no frame to set up for it
+                    frame.pc += 3;
+                } else {
+                    trace(runtime, location, pc, instr, frame.stack.last());
+                    push_frame(runtime, thread, class, method, args)?;
+                    continue;
+                }
+            }
+            0xBA => { // invokedynamic
+                let reference = invoke_dynamic(runtime, frame)?;
+                frame.stack.push(Value::Reference(reference));
+                frame.pc += 5;
+            }
+            0xBB => { // new
+                let class = runtime.get_class(frame.class.const_pool.resolve_class_name(frame.read_u16(1)?)?)?.clone();
+                if class.is_interface() || class.is_abstract() {
+                    Err(anyhow!("java.lang.InstantiationError: {}", class.this_class.replace('/', ".")))?
+                }
+                initialize_class(runtime, &class)?;
+                let fields = runtime.instance_fields(&class)?;
+                let reference = runtime.heap.allocate(HeapObject::Object(Object { class, fields }))?;
+                runtime.mark_uninitialized(reference);
+                frame.stack.push(Value::Reference(reference));
+                frame.pc += 3;
+            }
+            0xBC => { // newarray
+                let type_name = match frame.read_u8(1)? {
+                    4 => "[Z",
+                    5 => "[C",
+                    8 => "[B",
+                    9 => "[S",
+                    10 => "[I",
+                    atype => Err(anyhow!("unsupported newarray type {}", atype))?,
+                };
+                let reference = new_array(runtime, frame, type_name.to_string())?;
+                frame.stack.push(Value::Reference(reference));
+                frame.pc += 2;
+            }
+            0xBD => { // anewarray
+                let component = frame.class.const_pool.resolve_class_name(frame.read_u16(1)?)?;
+                let type_name = if component.starts_with('[') {
+                    format!("[{}", component)
+                } else {
+                    format!("[L{};", component)
+                };
+                let reference = new_array(runtime, frame, type_name)?;
+                frame.stack.push(Value::Reference(reference));
+                frame.pc += 3;
+            }
+            0xBE => { // arraylength
+                let Some(reference) = frame.pop()?.as_reference()? else {
+                    Err(runtime.throw("java/lang/NullPointerException", "Cannot read the array length of null"))?
+                };
+                let length = match runtime.heap.get(reference)? {
+                    HeapObject::Array(array) => array.len(),
+                    HeapObject::Object(_) => Err(anyhow!("arraylength of non-array {:?}", reference))?,
+                };
+                frame.stack.push(Value::Int(length as i32));
+                frame.pc += 1;
+            }
+            0xBF => { // athrow
+                let Some(exception) = frame.pop()?.as_reference()? else {
+                    Err(runtime.throw("java/lang/NullPointerException", "Cannot throw exception because it is null"))?
+                };
+                Err(JavaException(exception))?
+            }
+            0xC0 => { // checkcast
+                let value = frame.pop()?;
+                if let Some(reference) = value.as_reference()? {
+                    let target = frame.class.const_pool.resolve_class_name(frame.read_u16(1)?)?;
+                    let type_name = runtime.heap.get(reference)?.type_name();
+                    if !runtime.is_assignable(type_name, target)? {
+                        let message = format!("class {} cannot be cast to class {}",
+                            type_name.replace('/', "."), target.replace('/', "."));
+                        Err(runtime.throw("java/lang/ClassCastException", &message))?
+                    }
+                }
+                frame.stack.push(value);
+                frame.pc += 3;
+            }
+            0xC2 => { // monitorenter
+                let Some(object) = frame.pop()?.as_reference()? else {
+                    Err(runtime.throw("java/lang/NullPointerException", "Cannot enter synchronized block because the object is null"))?
+                };
+                frame.enter_monitor(runtime, object);
+                frame.pc += 1;
+            }
+            0xC3 => { // monitorexit
+                let Some(object) = frame.pop()?.as_reference()? else {
+                    Err(runtime.throw("java/lang/NullPointerException", "Cannot exit synchronized block because the object is null"))?
+                };
+                if !frame.exit_monitor(runtime, object) {
+                    Err(runtime.throw("java/lang/IllegalMonitorStateException", "current thread is not owner"))?
+                }
+                frame.pc += 1;
+            }
+            0xC6 | 0xC7 => { // ifnull, ifnonnull
+                let value = frame.pop()?.as_reference()?;
+                branch(frame, value.is_none() == (instr == 0xC6))?;
+            }
+            0xC1 => { // instanceof
+                let result = match frame.pop()?.as_reference()? {
+                    Some(reference) => {
+                        let target = frame.class.const_pool.resolve_class_name(frame.read_u16(1)?)?;
+                        let type_name = runtime.heap.get(reference)?.type_name();
+                        runtime.is_assignable(type_name, target)?
+                    }
+                    None => false,
+                };
+                frame.stack.push(Value::Int(result as i32));
+                frame.pc += 3;
+            }
+            _ => {
+                let at = format!("{}.{}{}+{}", frame.class.this_class.replace('/', "."), frame.method.name,
+                    frame.method.descriptor, pc);
+                match opcode_name(instr) {
+                    "unknown" => Err(anyhow!("unknown opcode {:#04x} at {}", instr, at))?,
+                    name => Err(anyhow!("{} ({:#04x}) unsupported at {}", name, instr, at))?,
+                }
+            }
+        }
+        trace(runtime, location, pc, instr, frame.stack.last());
+    }
+}
+
+/// Jumps by the branch offset operand of the frame's current instruction if `condition` holds, or
+/// moves on to the next instruction otherwise.
+fn branch(frame: &mut Frame, condition: bool) -> Result<(), Error> {
+    if condition {
+        let offset = frame.read_u16(1)? as i16 as isize;
+        frame.pc = frame.pc.checked_add_signed(offset).ok_or_else(|| anyhow!("branch to negative pc"))?;
+    } else {
+        frame.pc += 3;
+    }
+    Ok(())
+}
+
+/// Jumps to the case of the tableswitch or lookupswitch at the frame's pc for `key`, or to its
+/// default if it has none.
+fn switch(runtime: &Runtime, frame: &mut Frame, key: i32) -> Result<(), Error> {
+    frame.pc = frame.method.instruction_at(frame.pc, runtime.verify)?.switch_target(key)
+        .ok_or_else(|| anyhow!("no switch at pc {} in {}", frame.pc, frame.method.name))?;
+    Ok(())
+}
+
+/// Pushes the local at `idx` for a load opcode, `iload` to `aload`, whose narrower forms share its
+/// type. Longs, floats and doubles are checked to be as the opcode expects.
+fn load_local(frame: &mut Frame, opcode: u8, idx: usize) -> Result<(), Error> {
+    let value = frame.load(idx)?;
+    let value = match opcode {
+        0x16 => Value::Long(value.as_long()?),
+        0x17 => Value::Float(value.as_float()?),
+        0x18 => Value::Double(value.as_double()?),
+        _ => value,
+    };
+    frame.stack.push(value);
+    Ok(())
+}
+
+/// Pops a value into the local at `idx` for a store opcode, `istore` to `astore`.
+fn store_local(frame: &mut Frame, opcode: u8, idx: usize) -> Result<(), Error> {
+    let value = frame.pop()?;
+    let value = match opcode {
+        0x37 => Value::Long(value.as_long()?),
+        0x38 => Value::Float(value.as_float()?),
+        0x39 => Value::Double(value.as_double()?),
+        _ => value,
+    };
+    frame.store(idx, value)
+}
+
+/// Adds a constant to the int local at `idx`, wrapping on overflow.
+fn increment_local(frame: &mut Frame, idx: usize, constant: i32) -> Result<(), Error> {
+    let value = frame.load(idx)?.as_int()?;
+    frame.store(idx, Value::Int(value.wrapping_add(constant)))
+}
+
+/// The result of fcmp<op> or dcmp<op> for the ordering of its operands: -1, 0 or 1, or when either
+/// is NaN, 1 for the g variants and -1 for the l variants, so that the branch after it isn't taken
+/// whichever way it tests.
+fn floating_compare(ordering: Option<Ordering>, nan_greater: bool) -> i32 {
+    match ordering {
+        Some(ordering) => ordering as i32,
+        None if nan_greater => 1,
+        None => -1,
+    }
+}
+
+/// Compares ints as the conditional branches do, with `condition` the branch's offset from the first
+/// of its family: equal, not equal, less than, greater or equal, greater than, less or equal.
+fn compare(condition: u8, left: i32, right: i32) -> bool {
+    match condition {
+        0 => left == right,
+        1 => left != right,
+        2 => left < right,
+        3 => left >= right,
+        4 => left > right,
+        _ => left <= right,
+    }
+}
+
+/// Initializes a class before its first instance is created, static field accessed or static method
+/// called: its superclass first, then its static initializer, which runs to completion on its own
+/// thread. A class is marked initialized before its initializer runs, so the initializer may use it.
+pub(crate) fn initialize_class(runtime: &mut Runtime, class: &Rc<RuntimeClass>) -> Result<(), Error> {
+    if !runtime.begin_initialization(&class.this_class) {
+        return Ok(());
+    }
+    debug!("initializing class {}", class.this_class.replace('/', "."));
+    if let Some(super_class) = &class.super_class {
+        let super_class = runtime.get_class(super_class)?.clone();
+        initialize_class(runtime, &super_class)?;
+    }
+    // Constant fields are set from their ConstantValue attributes, as <clinit> doesn't set them
+    for field in class.fields.iter().filter(|field| field.is_constant()) {
+        let value = constant_value(runtime, class, field)?;
+        runtime.put_static(&class.this_class, &field.name, value);
+    }
+    if let Some(clinit) = class.find_method("<clinit>", "()V") {
+        let mut thread = create_thread(class.clone(), clinit.clone(), vec![]);
+        run_thread(runtime, &mut thread)?;
+    }
+    Ok(())
+}
+
+/// The class declaring a static field a reference names through `class`, which initializing and
+/// storing the field go by, so that a subclass shares its superclasses' statics. Fields no class
+/// declares stay with the class named.
+fn static_field_owner(runtime: &Runtime, class: Rc<RuntimeClass>, name: &str) -> Result<Rc<RuntimeClass>, Error> {
+    Ok(runtime.resolve_field(&class.this_class, name)?.map(|(owner, _)| owner).unwrap_or(class))
+}
+
+/// The value a constant field's ConstantValue attribute gives it, with strings interned as ldc
+/// interns them.
+fn constant_value(runtime: &mut Runtime, class: &RuntimeClass, field: &RuntimeField) -> Result<Value, Error> {
+    let Some(idx) = field.constant_value_idx else {
+        Err(anyhow!("field {}.{} has no ConstantValue", class.this_class.replace('/', "."), field.name))?
+    };
+    Ok(match class.const_pool.get_const(idx)? {
+        Const::Integer(value) => Value::Int(*value),
+        Const::Float(value) => Value::Float(*value),
+        Const::Long(value) => Value::Long(*value),
+        Const::Double(value) => Value::Double(*value),
+        Const::String(string) => Value::Reference(runtime.intern(&class.const_pool.get_utf8(string.string_idx)?.bytes)?),
+        const_item => Err(anyhow!("java.lang.ClassFormatError: ConstantValue of field {}.{} is {:?}",
+            class.this_class.replace('/', "."), field.name, const_item))?,
+    })
+}
+
+/// Resolves the field named by the field reference operand of the frame's current instruction, giving
+/// the class named by the reference, the field's name and its type.
+fn resolve_fieldref(runtime: &Runtime, frame: &Frame) -> Result<(Rc<RuntimeClass>, String, FieldType), Error> {
+    let const_pool = &frame.class.const_pool;
+    let fieldref = const_pool.get_fieldref(frame.read_u16(1)?)?;
+    let class = runtime.get_class(const_pool.resolve_class_name(fieldref.class_idx)?)?.clone();
+    let name_and_type = const_pool.get_name_and_type(fieldref.name_and_type_idx)?;
+    let name = const_pool.get_utf8(name_and_type.name_idx)?.bytes.clone();
+    let field_type = FieldType::parse(&const_pool.get_utf8(name_and_type.descriptor_idx)?.bytes)?;
+    if runtime.strict_access {
+        check_field_access(runtime, &frame.class, &class, &name)?;
+    }
+    Ok((class, name, field_type))
+}
+
+/// Checks that a class may access a field by the field's access flags, as `--strict-access` does:
+/// private fields only from the class declaring them, package private fields only from its package,
+/// and protected fields from there or subclasses. Nestmates, which share private members since
+/// Java 11, aren't recognised.
+fn check_field_access(runtime: &Runtime, accessor: &RuntimeClass, class: &RuntimeClass, name: &str) -> Result<(), Error> {
+    let Some((owner, field)) = runtime.resolve_field(&class.this_class, name)? else {
+        return Ok(());
+    };
+    let allowed = field.is_public()
+        || accessor.this_class == owner.this_class
+        || (!field.is_private() && accessor.package() == owner.package())
+        || (field.is_protected() && runtime.is_subclass(&accessor.this_class, &owner.this_class)?);
+    if !allowed {
+        let kind = if field.is_private() { "private " } else if field.is_protected() { "protected " } else { "" };
+        Err(anyhow!("java.lang.IllegalAccessError: class {} tried to access {}field {}.{}",
+            accessor.this_class.replace('/', "."), kind, owner.this_class.replace('/', "."), name))?
+    }
+    Ok(())
+}
+
+/// Resolves the method named by the method reference operand of the frame's current instruction.
+fn resolve_methodref(runtime: &Runtime, frame: &Frame) -> Result<(Rc<RuntimeClass>, Rc<RuntimeMethod>), Error> {
+    let const_pool = &frame.class.const_pool;
+    let methodref = const_pool.get_methodref(frame.read_u16(1)?)?;
+    let class_name = const_pool.resolve_class_name(methodref.class_idx)?;
+    let name_and_type = const_pool.get_name_and_type(methodref.name_and_type_idx)?;
+    let name = &const_pool.get_utf8(name_and_type.name_idx)?.bytes;
+    let descriptor = &const_pool.get_utf8(name_and_type.descriptor_idx)?.bytes;
+    // Arrays have Object's methods
+    let class_name = if class_name.starts_with('[') { "java/lang/Object" } else { class_name };
+    runtime.resolve_method(class_name, name, descriptor)
+}
+
+/// Runs the invokedynamic call site that is the frame's current instruction, popping its arguments.
+/// Only string concatenation's bootstrap methods are supported, giving the concatenated String.
+fn invoke_dynamic(runtime: &mut Runtime, frame: &mut Frame) -> Result<Reference, Error> {
+    let class = frame.class.clone();
+    let const_pool = &class.const_pool;
+    let invoke_dynamic = const_pool.get_invoke_dynamic(frame.read_u16(1)?)?;
+    let name_and_type = const_pool.get_name_and_type(invoke_dynamic.name_and_type_idx)?;
+    let descriptor = MethodDescriptor::parse(&const_pool.get_utf8(name_and_type.descriptor_idx)?.bytes)?;
+
+    let bootstrap_method = class.bootstrap_methods.get(invoke_dynamic.bootstrap_method_attr_idx as usize)
+        .ok_or_else(|| anyhow!("bootstrap method index {} out of bounds", invoke_dynamic.bootstrap_method_attr_idx))?;
+    let method_handle = const_pool.get_method_handle(bootstrap_method.method_ref_idx)?;
+    let methodref = const_pool.get_methodref(method_handle.reference_idx)?;
+    let bootstrap_class = const_pool.resolve_class_name(methodref.class_idx)?;
+    let bootstrap_name = &const_pool.get_utf8(const_pool.get_name_and_type(methodref.name_and_type_idx)?.name_idx)?.bytes;
+    if bootstrap_class != STRING_CONCAT_FACTORY {
+        Err(anyhow!("java.lang.BootstrapMethodError: unsupported bootstrap method {}.{}",
+            bootstrap_class.replace('/', "."), bootstrap_name))?
+    }
+
+    let arg_count = descriptor.parameters.len();
+    if frame.stack.len() < arg_count {
+        Err(anyhow!("pop from empty operand stack"))?
+    }
+    let args = frame.stack.split_off(frame.stack.len() - arg_count);
+    string_concat(runtime, const_pool, bootstrap_name, bootstrap_method, &descriptor, args)
+}
+
+/// Selects the method invokespecial calls: a constructor or private method exactly as resolved, but a
+/// superclass method, when the calling class has `ACC_SUPER` set, from the calling class's direct
+/// superclass, which may override the resolved one.
+fn select_special_method(runtime: &Runtime, frame: &Frame, class: Rc<RuntimeClass>, method: Rc<RuntimeMethod>)
+                         -> Result<(Rc<RuntimeClass>, Rc<RuntimeMethod>), Error> {
+    let caller = &frame.class;
+    let Some(super_class) = caller.super_class.as_deref() else {
+        return Ok((class, method));
+    };
+    if method.name == "<init>" || caller.access_flags & ACC_SUPER == 0 || class.is_interface()
+        || class.this_class == caller.this_class || !runtime.is_assignable(super_class, &class.this_class)? {
+        return Ok((class, method));
+    }
+    runtime.resolve_method(super_class, &method.name, &method.descriptor.to_string())
+}
+
+/// Whether the frame's current invokevirtual calls `clone` on an array type. Arrays implement it as a
+/// public method making a shallow copy, rather than through Object's protected one.
+fn invokes_array_clone(frame: &Frame) -> bool {
+    let const_pool = &frame.class.const_pool;
+    let clone = || -> Result<bool, Error> {
+        let methodref = const_pool.get_methodref(frame.read_u16(1)?)?;
+        let class_name = const_pool.resolve_class_name(methodref.class_idx)?;
+        let name_and_type = const_pool.get_name_and_type(methodref.name_and_type_idx)?;
+        Ok(class_name.starts_with('[') && const_pool.get_utf8(name_and_type.name_idx)?.bytes == "clone"
+            && const_pool.get_utf8(name_and_type.descriptor_idx)?.bytes == "()Ljava/lang/Object;")
+    };
+    // Malformed references are reported by the general invoke instead
+    clone().unwrap_or(false)
+}
+
+/// Selects the implementation of a resolved method for the receiver's class, which may override it.
+fn select_method(runtime: &Runtime, receiver: Reference, method: &RuntimeMethod) -> Result<(Rc<RuntimeClass>, Rc<RuntimeMethod>), Error> {
+    let class_name = match runtime.heap.get(receiver)? {
+        HeapObject::Object(object) => object.class.this_class.as_str(),
+        HeapObject::Array(_) => "java/lang/Object",
+    };
+    runtime.resolve_method(class_name, &method.name, &method.descriptor.to_string())
+}
+
+/// Logs an executed instruction to the diagnostics sink along with the top of the operand stack it
+/// left behind, if tracing gave the instruction's method as its `location`. A failed write is
+/// ignored rather than failing the program.
+fn trace(runtime: &mut Runtime, location: Option<String>, pc: usize, instr: u8, top: Option<&Value>) {
+    if let Some(location) = location {
+        let _ = match top {
+            Some(top) => writeln!(runtime.stderr, "{} {}: {} {}", location, pc, opcode_name(instr), top.display(&runtime.heap)),
+            None => writeln!(runtime.stderr, "{} {}: {}", location, pc, opcode_name(instr)),
+        };
+    }
+}
+
+fn new_array(runtime: &mut Runtime, frame: &mut Frame, type_name: String) -> Result<Reference, Error> {
+    let count = frame.pop()?.as_int()?;
+    if count < 0 {
+        Err(runtime.throw("java/lang/NegativeArraySizeException", &count.to_string()))?
+    }
+    runtime.heap.allocate_array(type_name, count as usize)
+}
+
+/// Pops an array and index, pushing the element at that index.
+fn array_load(runtime: &mut Runtime, frame: &mut Frame) -> Result<(), Error> {
+    let index = frame.pop()?.as_int()?;
+    let array = frame.pop()?.as_reference()?;
+    let (array, index) = check_array_index(runtime, array, index, "load from")?;
+    let HeapObject::Array(array) = runtime.heap.get(array)? else {
+        Err(anyhow!("array load from non-array"))?
+    };
+    frame.stack.push(array.load(index)?);
+    Ok(())
+}
+
+/// Pops an array, index and value, storing the value at that index.
+fn array_store(runtime: &mut Runtime, frame: &mut Frame) -> Result<(), Error> {
+    let value = frame.pop()?;
+    let index = frame.pop()?.as_int()?;
+    let array = frame.pop()?.as_reference()?;
+    let (array, index) = check_array_index(runtime, array, index, "store to")?;
+    let HeapObject::Array(array) = runtime.heap.get_mut(array)? else {
+        Err(anyhow!("array store to non-array"))?
+    };
+    array.store(index, value)
+}
+
+/// Throws if the array is null or the index is outside of it. `action` describes the access for the
+/// exception message.
+fn check_array_index(runtime: &mut Runtime, array: Option<Reference>, index: i32, action: &str) -> Result<(Reference, usize), Error> {
+    let Some(array) = array else {
+        Err(runtime.throw("java/lang/NullPointerException", &format!("Cannot {} array because it is null", action)))?
+    };
+    let length = match runtime.heap.get(array)? {
+        HeapObject::Array(array) => array.len(),
+        HeapObject::Object(_) => Err(anyhow!("array access on non-array {:?}", array))?,
+    };
+    if index < 0 || index as usize >= length {
+        let message = format!("Index {} out of bounds for length {}", index, length);
+        Err(runtime.throw("java/lang/ArrayIndexOutOfBoundsException", &message))?
+    }
+    Ok((array, index as usize))
+}
+
+#[cfg(all(test, feature = "jar"))]
+pub mod test_invoke {
+    use super::*;
+    use crate::runtime::test_runtime::test_runtime;
+
+    /// Runs the method of a test jar class with the given name, passing no arguments.
+    pub fn invoke(runtime: &mut Runtime, class_name: &str, name: &str) -> Result<Option<Value>, Error> {
+        let class = runtime.get_class(class_name)?.clone();
+        let method = class.methods.iter().find(|method| method.name.eq(name)).unwrap().clone();
+        let mut thread = create_thread(class, method, vec![]);
+        run_thread(runtime, &mut thread)
+    }
+
+    /// A frame of some method, for testing operand stack manipulation on.
+    pub(super) fn frame() -> Frame {
+        let runtime = test_runtime();
+        let class = runtime.get_class("com/jkitch/robusta/test/Longs").unwrap().clone();
+        let method = class.methods[0].clone();
+        Frame::new(class, method, vec![])
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod implemented_opcode_tests {
+    use super::test_invoke::frame;
+    use super::{execute, is_implemented, Frame, Thread};
+    use crate::class_file::{Code, ACC_STATIC};
+    use crate::descriptor::MethodDescriptor;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::runtime::RuntimeMethod;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    #[test]
+    fn matches_dispatch() {
+        let mut runtime = test_runtime();
+        let class = frame().class;
+        for opcode in 0..=u8::MAX {
+            let method = Rc::new(RuntimeMethod {
+                access_flags: ACC_STATIC,
+                name: "test".to_string(),
+                descriptor: MethodDescriptor::parse("()V").unwrap(),
+                code: Code { max_stack: 4, max_locals: 4, code: vec![opcode, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1, 0xB1], exception_table: vec![] },
+                exceptions: vec![],
+                signature: None,
+                annotations: vec![],
+                decoded: RefCell::default(),
+                jumps_verified: Cell::default(),
+            });
+            let mut thread = Thread { frames: vec![Frame::new(class.clone(), method, vec![])] };
+
+            // Operands of returns make any instruction after it return, and any jump go before the code
+            let unsupported = execute(&mut runtime, &mut thread).err().is_some_and(|error| {
+                let message = error.to_string();
+                (message.contains(" unsupported at ") || message.starts_with("unknown opcode")) && message.ends_with("test()V+0")
+            });
+
+            assert_eq!(is_implemented(opcode), !unsupported, "opcode {:#04x}", opcode);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "jar", debug_assertions))]
+mod stack_category_tests {
+    use super::test_invoke::frame;
+    use crate::value::Value;
+
+    #[test]
+    fn pop_category1() {
+        let mut frame = frame();
+        frame.stack.push(Value::Int(1));
+
+        assert_eq!(frame.pop_category1().unwrap(), Value::Int(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a category 1 value")]
+    fn pop_category1_of_long() {
+        let mut frame = frame();
+        frame.stack.push(Value::Long(1));
+
+        let _ = frame.pop_category1();
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a category 1 value")]
+    fn pop_two_slots_splitting_long() {
+        let mut frame = frame();
+        frame.stack.extend([Value::Long(1), Value::Int(2)]);
+
+        let _ = frame.pop_two_slots();
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod array_instanceof_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/ArrayInstanceOf";
+
+    #[test]
+    fn string_array_instanceof_object_array() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "stringArrayIsObjectArray").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn int_array_instanceof_object_array() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "intArrayIsObjectArray").unwrap(), Some(Value::Int(0)));
+    }
+
+    #[test]
+    fn int_array_instanceof_long_array() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "intArrayIsLongArray").unwrap(), Some(Value::Int(0)));
+    }
+
+    #[test]
+    fn string_array_instanceof_object() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "stringArrayIsObject").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn nested_array_instanceof_object_array() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "nestedArrayIsObjectArray").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn checkcast_string_array_to_object_array() {
+        let mut runtime = test_runtime();
+
+        assert!(matches!(invoke(&mut runtime, CLASS, "castStringArray").unwrap(), Some(Value::Reference(_))));
+    }
+
+    #[test]
+    fn checkcast_int_array_to_object_array() {
+        let mut runtime = test_runtime();
+
+        let error = invoke(&mut runtime, CLASS, "castIntArray").unwrap_err();
+
+        assert!(error.to_string().contains("ClassCastException"));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod null_pointer_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/NullPointer";
+
+    #[test]
+    fn catch_null_array_length() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "catchArrayLength").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn uncaught_null_array_length() {
+        let mut runtime = test_runtime();
+
+        let error = invoke(&mut runtime, CLASS, "uncaughtArrayLength").unwrap_err();
+
+        assert_eq!(error.to_string(),
+            "Exception in thread \"main\" java.lang.NullPointerException: Cannot read the array length of null");
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod char_array_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/CharArrays";
+
+    #[test]
+    fn store_above_byte_range() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "storeAboveByteRange").unwrap(), Some(Value::Int(200)));
+    }
+
+    #[test]
+    fn store_euro_sign() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "storeEuroSign").unwrap(), Some(Value::Int('€' as i32)));
+    }
+
+    #[test]
+    fn load_out_of_bounds() {
+        let mut runtime = test_runtime();
+
+        let error = invoke(&mut runtime, CLASS, "loadOutOfBounds").unwrap_err();
+
+        assert!(error.to_string().contains("java.lang.ArrayIndexOutOfBoundsException: Index 1 out of bounds for length 1"));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod narrow_array_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/NarrowArrays";
+
+    #[test]
+    fn byte_truncates() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "storeOverflowingByte").unwrap(), Some(Value::Int(44)));
+    }
+
+    #[test]
+    fn byte_sign_extends() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "storeNegativeByte").unwrap(), Some(Value::Int(-100)));
+    }
+
+    #[test]
+    fn short_sign_extends() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "storeNegativeShort").unwrap(), Some(Value::Int(-12345)));
+    }
+
+    #[test]
+    fn char_zero_extends() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "storeNegativeChar").unwrap(), Some(Value::Int(0xFFFF)));
+    }
+
+    #[test]
+    fn boolean_round_trip() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "storeBoolean").unwrap(), Some(Value::Int(1)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod call_tests {
+    use super::invoke_static;
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Calls";
+
+    #[test]
+    fn call_and_return() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "callAndReturn").unwrap(), Some(Value::Int(5)));
+    }
+
+    #[test]
+    fn catch_exception_from_callee() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "catchFromCallee").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn construct_empty_objects() {
+        let mut runtime = test_runtime();
+
+        let constructed = invoke_static(&mut runtime, CLASS, "constructEmpty", "(I)I", vec![Value::Int(100)]).unwrap();
+
+        assert_eq!(constructed, Some(Value::Int(100)));
+    }
+
+    #[test]
+    fn empty_constructor_pushes_no_frame() {
+        let mut runtime = test_runtime();
+
+        invoke_static(&mut runtime, CLASS, "constructEmpty", "(I)I", vec![Value::Int(100)]).unwrap();
+
+        // One for constructEmpty and one for each Empty.<init>, but none for the Object.<init> they call
+        assert_eq!(runtime.frames_pushed, 101);
+    }
+
+    #[test]
+    fn construct_with_fields() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "constructPoint").unwrap(), Some(Value::Int(7)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod boxing_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Boxing";
+
+    #[test]
+    fn small_integers_are_cached() {
+        let mut runtime = test_runtime();
+
+        let first = invoke(&mut runtime, CLASS, "boxHundred").unwrap();
+        let second = invoke(&mut runtime, CLASS, "boxHundred").unwrap();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn large_integers_are_distinct() {
+        let mut runtime = test_runtime();
+
+        let first = invoke(&mut runtime, CLASS, "boxTwoHundred").unwrap();
+        let second = invoke(&mut runtime, CLASS, "boxTwoHundred").unwrap();
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn unbox_through_number() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "unbox").unwrap(), Some(Value::Int(200)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod class_literal_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+
+    const CLASS: &str = "com/jkitch/robusta/test/ClassLiterals";
+
+    #[test]
+    fn object_class_name() {
+        let mut runtime = test_runtime();
+
+        let name = invoke(&mut runtime, CLASS, "objectClassName").unwrap().unwrap().as_reference().unwrap().unwrap();
+
+        assert_eq!(runtime.read_string(name).unwrap(), "java.lang.Object");
+    }
+
+    #[test]
+    fn array_class_name() {
+        let mut runtime = test_runtime();
+
+        let name = invoke(&mut runtime, CLASS, "intArrayClassName").unwrap().unwrap().as_reference().unwrap().unwrap();
+
+        assert_eq!(runtime.read_string(name).unwrap(), "[I");
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod long_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Longs";
+
+    #[test]
+    fn return_long() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "storeAndLoad").unwrap(), Some(Value::Long(1 << 40)));
+    }
+
+    #[test]
+    fn int_after_long_local() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "intAfterLong").unwrap(), Some(Value::Int(7)));
+    }
+
+    #[test]
+    fn int_after_long_parameter() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "intAfterLongParameter").unwrap(), Some(Value::Int(3)));
+    }
+
+    #[test]
+    fn long_parameter() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "longParameter").unwrap(), Some(Value::Long(1 << 40)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod constant_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Constants";
+
+    #[test]
+    fn long_constants() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "longOne").unwrap(), Some(Value::Long(1)));
+        assert_eq!(invoke(&mut runtime, CLASS, "longZeroInLocal").unwrap(), Some(Value::Long(0)));
+    }
+
+    #[test]
+    fn float_constant() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "floatTwo").unwrap(), Some(Value::Float(2.0)));
+    }
+
+    #[test]
+    fn double_constant() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "doubleOne").unwrap(), Some(Value::Double(1.0)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod assertion_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Assertions";
+
+    #[test]
+    fn assertions_disabled_by_default() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "assertionsEnabled").unwrap(), Some(Value::Int(0)));
+    }
+
+    #[test]
+    fn assertions_enabled() {
+        let mut runtime = test_runtime();
+        runtime.assertions = true;
+
+        assert_eq!(invoke(&mut runtime, CLASS, "assertionsEnabled").unwrap(), Some(Value::Int(1)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod monitor_tests {
+    use super::test_invoke::{frame, invoke};
+    use super::{unwind, Thread};
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Monitors";
+
+    #[test]
+    fn notify_without_monitor() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "notifyOutsideSynchronized").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn notify_with_monitor() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "notifyInsideSynchronized").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn wait_returns_immediately() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "waitInsideSynchronized").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn notify_in_synchronized_methods() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "notifyInsideSynchronizedMethods").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn synchronized_method_releases_on_return() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "releasedAfterSynchronizedMethod").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn synchronized_method_releases_on_throw() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "releasedAfterThrowFromSynchronizedMethod").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn synchronized_block_releases_on_throw() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "releasedAfterThrowFromSynchronizedBlock").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn unwinding_releases_frame_monitors() {
+        let mut runtime = test_runtime();
+        let lock = runtime.new_string("lock").unwrap();
+        let exception = runtime.new_string("exception").unwrap();
+        let mut frame = frame();
+        // Entered twice, as by a block nested in another on the same object, with neither exited
+        frame.enter_monitor(&mut runtime, lock);
+        frame.enter_monitor(&mut runtime, lock);
+        let mut thread = Thread { frames: vec![frame] };
+
+        assert!(!unwind(&mut runtime, &mut thread, exception).unwrap());
+        assert!(!runtime.holds_monitor(lock));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod cancellation_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use std::sync::atomic::Ordering;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Cancellation";
+    const CANCELLED: &str = "java.lang.InterruptedException: execution was cancelled";
+
+    #[test]
+    fn cancel_loop_catching_exceptions() {
+        let mut runtime = test_runtime();
+        runtime.cancelled.store(true, Ordering::Relaxed);
+
+        assert_eq!(invoke(&mut runtime, CLASS, "throwAndCatchForever").unwrap_err().to_string(), CANCELLED);
+    }
+
+    #[test]
+    fn cancel_in_static_initializer() {
+        let mut runtime = test_runtime();
+        let lock = runtime.class_object(CLASS).unwrap();
+        runtime.cancelled.store(true, Ordering::Relaxed);
+
+        assert_eq!(invoke(&mut runtime, CLASS, "initializeSpinning").unwrap_err().to_string(), CANCELLED);
+        // Held by the synchronized method whose invokestatic ran the initializer
+        assert!(!runtime.holds_monitor(lock));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod switch_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Switches";
+
+    #[test]
+    fn table_switch() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "tableMatch").unwrap(), Some(Value::Int(30)));
+        assert_eq!(invoke(&mut runtime, CLASS, "tableDefault").unwrap(), Some(Value::Int(-1)));
+    }
+
+    #[test]
+    fn lookup_switch() {
+        let mut runtime = test_runtime();
+        runtime.verify = true;
+
+        assert_eq!(invoke(&mut runtime, CLASS, "lookupMatch").unwrap(), Some(Value::Int(1)));
+        assert_eq!(invoke(&mut runtime, CLASS, "lookupDefault").unwrap(), Some(Value::Int(-1)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod enum_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Enums";
+
+    #[test]
+    fn ordinals() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "redOrdinal").unwrap(), Some(Value::Int(0)));
+        assert_eq!(invoke(&mut runtime, CLASS, "greenOrdinal").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn values() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "valueCount").unwrap(), Some(Value::Int(2)));
+    }
+
+    #[test]
+    fn value_of() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "valueOfName").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn value_of_missing_constant() {
+        let mut runtime = test_runtime();
+
+        let error = invoke(&mut runtime, CLASS, "valueOfMissing").unwrap_err();
+
+        assert_eq!(error.to_string(), "Exception in thread \"main\" java.lang.IllegalArgumentException: \
+            No enum constant com.jkitch.robusta.test.Enums$Color.BLUE");
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod string_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Strings";
+
+    #[test]
+    fn literals_shared() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "literalsShared").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn built_string_interned() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "builtDistinct").unwrap(), Some(Value::Int(1)));
+        assert_eq!(invoke(&mut runtime, CLASS, "internedBuiltShared").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn length_and_char_at() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "literalLength").unwrap(), Some(Value::Int(7)));
+        assert_eq!(invoke(&mut runtime, CLASS, "literalCharAt").unwrap(), Some(Value::Int('b' as i32)));
+    }
+
+    #[test]
+    fn char_at_out_of_range() {
+        let mut runtime = test_runtime();
+
+        let message = invoke(&mut runtime, CLASS, "charAtOutOfRange").unwrap().unwrap().as_reference().unwrap().unwrap();
+
+        assert_eq!(runtime.read_string(message).unwrap(), "Index 7 out of bounds for length 7");
+    }
+
+    #[test]
+    fn equals_compares_chars() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "equalToBuilt").unwrap(), Some(Value::Int(1)));
+        assert_eq!(invoke(&mut runtime, CLASS, "equalToOtherValues").unwrap(), Some(Value::Int(0)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod loop_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    #[test]
+    fn counted_loop() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, "com/jkitch/robusta/test/Loops", "sumOfSquaresToTen").unwrap(), Some(Value::Int(285)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod null_check_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/NullChecks";
+
+    #[test]
+    fn require_non_null_passes_through() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "passThrough").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn require_non_null_throws() {
+        let mut runtime = test_runtime();
+
+        let error = invoke(&mut runtime, CLASS, "requireNull").unwrap_err();
+
+        assert_eq!(error.to_string(), "Exception in thread \"main\" java.lang.NullPointerException");
+    }
+
+    #[test]
+    fn require_non_null_throws_with_message() {
+        let mut runtime = test_runtime();
+
+        let error = invoke(&mut runtime, CLASS, "requireNullWithMessage").unwrap_err();
+
+        assert_eq!(error.to_string(), "Exception in thread \"main\" java.lang.NullPointerException: value is null");
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod finally_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Finally";
+
+    #[test]
+    fn finally_runs_on_return() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "finallyRunsOnReturn").unwrap(), Some(Value::Int(11)));
+    }
+
+    #[test]
+    fn finally_runs_on_throw() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "finallyRunsOnThrow").unwrap(), Some(Value::Int(11)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod array_clone_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/ArrayClones";
+
+    #[test]
+    fn clone_is_distinct() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "cloneIsDistinct").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn mutate_clone() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "mutateClone").unwrap(), Some(Value::Int(110)));
+    }
+
+    #[test]
+    fn clone_is_shallow() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "cloneIsShallow").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn clone_null() {
+        let mut runtime = test_runtime();
+
+        let error = invoke(&mut runtime, CLASS, "cloneNull").unwrap_err();
+
+        assert_eq!(error.to_string(), "Exception in thread \"main\" java.lang.NullPointerException: \
+                                       Cannot invoke \"Object.clone()\" because the array is null");
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod interface_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Interfaces";
+
+    #[test]
+    fn direct_instanceof() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "directInstanceOf").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn inherited_instanceof() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "inheritedInstanceOf").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn super_interface_instanceof() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "superInterfaceInstanceOf").unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn not_instanceof() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "notInstanceOf").unwrap(), Some(Value::Int(0)));
+    }
+
+    #[test]
+    fn cast_inherited() {
+        let mut runtime = test_runtime();
+
+        assert!(matches!(invoke(&mut runtime, CLASS, "castInherited").unwrap(), Some(Value::Reference(_))));
+    }
+
+    #[test]
+    fn cast_not_implemented() {
+        let mut runtime = test_runtime();
+
+        let error = invoke(&mut runtime, CLASS, "castNotImplemented").unwrap_err();
+
+        assert!(error.to_string().contains("java.lang.ClassCastException"));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod dup_tests {
+    use super::test_invoke::{frame, invoke};
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/StackOps";
+
+    #[test]
+    fn dup_x1_of_constructed_object() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "fieldAssignment").unwrap(), Some(Value::Int(7)));
+    }
+
+    #[test]
+    fn dup_x2_of_constructed_object() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "arrayAssignment").unwrap(), Some(Value::Int(9)));
+    }
+
+    #[test]
+    fn dup_x2_of_int() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "intArrayAssignment").unwrap(), Some(Value::Int(55)));
+    }
+
+    #[test]
+    fn dup_x2_under_long() {
+        let mut frame = frame();
+        frame.stack.extend([Value::Int(1), Value::Long(2), Value::Int(3)]);
+
+        frame.dup_x2().unwrap();
+
+        assert_eq!(frame.stack, vec![Value::Int(1), Value::Int(3), Value::Long(2), Value::Int(3)]);
+    }
+
+    #[test]
+    fn pop2_of_long() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "discardLong").unwrap(), Some(Value::Int(3)));
+    }
+
+    #[test]
+    fn dup2_of_long() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "chainedLongLocals").unwrap(), Some(Value::Long(1 << 40)));
+    }
+
+    #[test]
+    fn dup2_of_ints() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "incrementInArray").unwrap(), Some(Value::Int(5)));
+    }
+
+    #[test]
+    fn dup2_x1_of_long() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "longFieldAssignment").unwrap(), Some(Value::Long(1 << 40)));
+    }
+
+    #[test]
+    fn pop_two_slots() {
+        let mut frame = frame();
+        frame.stack.extend([Value::Int(1), Value::Long(2), Value::Int(3), Value::Int(4)]);
+
+        assert_eq!(frame.pop_two_slots().unwrap(), vec![Value::Int(3), Value::Int(4)]);
+        assert_eq!(frame.pop_two_slots().unwrap(), vec![Value::Long(2)]);
+        assert_eq!(frame.stack, vec![Value::Int(1)]);
+    }
+
+    #[test]
+    fn dup2_x1() {
+        let mut frame = frame();
+        frame.stack.extend([Value::Int(1), Value::Double(2.5)]);
+
+        frame.dup2_x1().unwrap();
+
+        assert_eq!(frame.stack, vec![Value::Double(2.5), Value::Int(1), Value::Double(2.5)]);
+    }
+
+    #[test]
+    fn dup2_x2() {
+        let mut frame = frame();
+        frame.stack.extend([Value::Long(1), Value::Int(2), Value::Int(3)]);
+
+        frame.dup2_x2().unwrap();
+
+        assert_eq!(frame.stack, vec![Value::Int(2), Value::Int(3), Value::Long(1), Value::Int(2), Value::Int(3)]);
+
+        frame.stack = vec![Value::Long(1), Value::Long(2)];
+
+        frame.dup2_x2().unwrap();
+
+        assert_eq!(frame.stack, vec![Value::Long(2), Value::Long(1), Value::Long(2)]);
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod array_string_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/ArrayStrings";
+
+    /// Runs a method of the test class that returns a String, giving its value.
+    fn invoke_string(name: &str) -> String {
+        let mut runtime = test_runtime();
+        let string = invoke(&mut runtime, CLASS, name).unwrap().unwrap().as_reference().unwrap().unwrap();
+        runtime.read_string(string).unwrap()
+    }
+
+    #[test]
+    fn int_array_to_string() {
+        assert_eq!(invoke_string("intArray"), "[1, 2, 3]");
+        assert_eq!(invoke_string("emptyArray"), "[]");
+        assert_eq!(invoke_string("nullArray"), "null");
+    }
+
+    #[test]
+    fn primitive_arrays_to_string() {
+        assert_eq!(invoke_string("charArray"), "[a, b]");
+        assert_eq!(invoke_string("booleanArray"), "[true, false]");
+    }
+
+    #[test]
+    fn object_array_to_string() {
+        assert_eq!(invoke_string("objectArray"), "[a, null, 42]");
+    }
+
+    #[test]
+    fn fill() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "fillInts").unwrap(), Some(Value::Int(21)));
+        assert_eq!(invoke_string("fillBytes"), "[-1, -1]");
+        assert_eq!(invoke_string("fillObjects"), "[x, x]");
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod float_comparison_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/FloatComparisons";
+
+    #[test]
+    fn nan_is_unequal_to_itself() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "nanEqualsItself").unwrap(), Some(Value::Int(0)));
+        assert_eq!(invoke(&mut runtime, CLASS, "nanNotEqualsItself").unwrap(), Some(Value::Int(1)));
+        assert_eq!(invoke(&mut runtime, CLASS, "doubleNanEqualsItself").unwrap(), Some(Value::Int(0)));
+    }
+
+    #[test]
+    fn nan_is_unordered() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "nanLessThan").unwrap(), Some(Value::Int(0)));
+        assert_eq!(invoke(&mut runtime, CLASS, "nanGreaterThan").unwrap(), Some(Value::Int(0)));
+        assert_eq!(invoke(&mut runtime, CLASS, "doubleNanGreaterOrEqual").unwrap(), Some(Value::Int(0)));
+    }
+
+    #[test]
+    fn ordered_comparisons() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "equalFloats").unwrap(), Some(Value::Int(1)));
+        assert_eq!(invoke(&mut runtime, CLASS, "lessFloat").unwrap(), Some(Value::Int(1)));
+        assert_eq!(invoke(&mut runtime, CLASS, "lessDouble").unwrap(), Some(Value::Int(1)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod division_tests {
+    use super::test_invoke::invoke;
+    use super::{unwind, Frame, Thread};
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::runtime::JavaException;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Division";
+
+    #[test]
+    fn rounds_toward_zero() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "negativeQuotient").unwrap(), Some(Value::Int(-3)));
+        assert_eq!(invoke(&mut runtime, CLASS, "negativeRemainder").unwrap(), Some(Value::Int(-1)));
+    }
+
+    #[test]
+    fn min_value_by_minus_one_overflows() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "minValueByMinusOne").unwrap(), Some(Value::Int(i32::MIN)));
+    }
+
+    #[test]
+    fn divide_by_zero_message() {
+        let mut runtime = test_runtime();
+
+        let message = invoke(&mut runtime, CLASS, "divideByZeroMessage").unwrap().unwrap().as_reference().unwrap().unwrap();
+
+        assert_eq!(runtime.read_string(message).unwrap(), "/ by zero");
+    }
+
+    #[test]
+    fn remainder_by_zero_caught_as_runtime_exception() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "remainderByZeroCaughtAsRuntimeException").unwrap(), Some(Value::Int(-1)));
+    }
+
+    #[test]
+    fn caught_mid_expression() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "caughtMidExpression").unwrap(), Some(Value::Int(101)));
+    }
+
+    #[test]
+    fn handler_sees_only_the_exception() {
+        let mut runtime = test_runtime();
+        let class = runtime.get_class(CLASS).unwrap().clone();
+        let method = class.find_method("caughtMidExpression", "()I").unwrap().clone();
+        let call = method.code.code.iter().position(|&instr| instr == 0xB8).unwrap();
+        let handler_pc = method.code.exception_table[0].handler_pc as usize;
+        let error = runtime.throw("java/lang/ArithmeticException", "/ by zero");
+        let exception = error.downcast_ref::<JavaException>().unwrap().0;
+        let mut frame = Frame::new(class, method, vec![Value::Int(100)]);
+        // The call's arguments are popped, leaving base and 2 for the addition and multiplication
+        frame.stack.extend([Value::Int(100), Value::Int(2)]);
+        frame.pc = call;
+        let mut thread = Thread { frames: vec![frame] };
+
+        assert!(unwind(&mut runtime, &mut thread, exception).unwrap());
+        assert_eq!(thread.frames[0].stack, vec![Value::Reference(exception)]);
+        assert_eq!(thread.frames[0].pc, handler_pc);
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod fluent_tests {
+    use super::test_invoke::invoke;
+    use super::{create_thread, run_thread};
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Fluent";
+
+    #[test]
+    fn returns_receiver() {
+        let mut runtime = test_runtime();
+        let builder = invoke(&mut runtime, CLASS, "create").unwrap().unwrap();
+        let (class, method) = runtime.resolve_method(CLASS, "add", "(I)Lcom/jkitch/robusta/test/Fluent;").unwrap();
+
+        let mut thread = create_thread(class, method, vec![builder, Value::Int(5)]);
+
+        assert_eq!(run_thread(&mut runtime, &mut thread).unwrap(), Some(builder));
+    }
+
+    #[test]
+    fn chained_calls_share_receiver() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "chainedTotal").unwrap(), Some(Value::Int(6)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod inheritance_tests {
+    use super::test_invoke::invoke;
+    use crate::heap::HeapObject;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+    use std::collections::HashMap;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Inheritance";
+
+    #[test]
+    fn storage_for_inherited_fields() {
+        let mut runtime = test_runtime();
+        let counter = invoke(&mut runtime, CLASS, "create").unwrap().unwrap().as_reference().unwrap().unwrap();
+
+        let HeapObject::Object(object) = runtime.heap.get(counter).unwrap() else { panic!("not an object") };
+
+        assert_eq!(object.fields, HashMap::from([("count".to_string(), Value::Int(0)), ("name".to_string(), Value::Null)]));
+    }
+
+    #[test]
+    fn inherited_field() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "inheritedField").unwrap(), Some(Value::Int(2)));
+    }
+
+    #[test]
+    fn hidden_field() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "hiddenField").unwrap(), Some(Value::Int(21)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod conversion_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Conversions";
+
+    #[test]
+    fn long_to_floating_rounds_to_nearest() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "longToFloat").unwrap(), Some(Value::Float(16777216.0)));
+        assert_eq!(invoke(&mut runtime, CLASS, "longToFloatTie").unwrap(), Some(Value::Float(16777220.0)));
+        assert_eq!(invoke(&mut runtime, CLASS, "longToDouble").unwrap(), Some(Value::Double(9007199254740992.0)));
+    }
+
+    #[test]
+    fn floating_to_long_saturates() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "hugeFloatToLong").unwrap(), Some(Value::Long(i64::MAX)));
+        assert_eq!(invoke(&mut runtime, CLASS, "hugeNegativeDoubleToLong").unwrap(), Some(Value::Long(i64::MIN)));
+    }
+
+    #[test]
+    fn floating_to_long_truncates() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "nanToLong").unwrap(), Some(Value::Long(0)));
+        assert_eq!(invoke(&mut runtime, CLASS, "fractionToLong").unwrap(), Some(Value::Long(-2)));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod static_tests {
+    use super::test_invoke::invoke;
+    use crate::runtime::test_runtime::test_runtime;
+    use crate::value::Value;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Statics";
+
+    #[test]
+    fn inherited_static_is_shared() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "inheritedStatic").unwrap(), Some(Value::Int(5)));
+    }
+
+    #[test]
+    fn inherited_static_read_through_subclass() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "readThroughSubclass").unwrap(), Some(Value::Int(7)));
+    }
+
+    #[test]
+    fn inherited_static_initializes_declaring_class() {
+        let mut runtime = test_runtime();
+
+        assert_eq!(invoke(&mut runtime, CLASS, "subclassInitializedByInheritedStatic").unwrap(), Some(Value::Int(0)));
+    }
+}
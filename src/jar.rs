@@ -0,0 +1,174 @@
+use crate::class_file::is_class_file_name;
+use anyhow::{Context, Error};
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+const MANIFEST_PATH: &str = "META-INF/MANIFEST.MF";
+
+/// The directory of a jmod that its class files are laid out under by package.
+const JMOD_CLASS_DIR: &str = "classes/";
+
+/// A module's descriptor, which is stored as a class file but isn't a class.
+const MODULE_INFO: &str = "module-info.class";
+
+/// A jar read from a file, or from any other seekable source such as a jar embedded in the binary.
+/// Also reads jmods, which are zips behind a short header with their class files under `classes/`.
+pub struct Jar<R = File> {
+    archive: ZipArchive<R>,
+    /// The directory class files are under, the root for a jar
+    class_dir: &'static str,
+}
+
+impl Jar {
+    /// Opens a jar file, failing with an error naming it if it can't be read or isn't a zip.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        File::open(path).map_err(Error::from)
+            .and_then(Self::from_reader)
+            .with_context(|| format!("failed to open jar {}", path.display()))
+    }
+
+    pub fn open_jmod(path: &Path) -> Result<Self, Error> {
+        File::open(path).map_err(Error::from)
+            .and_then(Self::jmod_from_reader)
+            .with_context(|| format!("failed to open jmod {}", path.display()))
+    }
+}
+
+impl<R: Read + Seek> Jar<R> {
+    pub fn from_reader(reader: R) -> Result<Self, Error> {
+        Ok(Jar { archive: ZipArchive::new(reader)?, class_dir: "" })
+    }
+
+    /// Reads a jmod. The zip reader finds the archive after the jmod's `JM` header itself, as it would
+    /// after any other data prepended to a zip.
+    pub fn jmod_from_reader(reader: R) -> Result<Self, Error> {
+        Ok(Jar { archive: ZipArchive::new(reader)?, class_dir: JMOD_CLASS_DIR })
+    }
+
+    /// The paths of the jar's class files, such as `com/example/App.class`, relative to the directory
+    /// they're under.
+    pub fn class_file_names(&self) -> Vec<String> {
+        self.archive.file_names()
+            .filter_map(|file| file.strip_prefix(self.class_dir))
+            .filter(|file| is_class_file_name(file) && *file != MODULE_INFO)
+            .map(|str| str.to_string())
+            .collect()
+    }
+
+    /// The bytes of one of the jar's class files, named as [`Jar::class_file_names`] gives it.
+    pub fn class_file(&mut self, file_name: &str) -> Result<impl Read + '_, Error> {
+        Ok(self.archive.by_name(&format!("{}{}", self.class_dir, file_name))?)
+    }
+
+    /// Reads the `Main-Class` attribute from the jar's manifest, if it has one.
+    pub fn main_class(&mut self) -> Result<Option<String>, Error> {
+        if self.archive.index_for_name(MANIFEST_PATH).is_none() {
+            return Ok(None);
+        }
+
+        let mut manifest = String::new();
+        self.archive.by_name(MANIFEST_PATH)?.read_to_string(&mut manifest)?;
+
+        Ok(manifest_attribute(&manifest, "Main-Class"))
+    }
+}
+
+fn manifest_attribute(manifest: &str, name: &str) -> Option<String> {
+    // Long values are wrapped onto continuation lines starting with a single space
+    let mut lines: Vec<String> = vec![];
+    for line in manifest.lines() {
+        match (line.strip_prefix(' '), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    lines.iter()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq(name))
+        .map(|(_, value)| value.trim().to_string())
+}
+
+#[cfg(test)]
+mod manifest_tests {
+    use super::*;
+
+    #[test]
+    fn main_class_present() {
+        let manifest = "Manifest-Version: 1.0\r\nMain-Class: com.example.App\r\n\r\n";
+
+        assert_eq!(manifest_attribute(manifest, "Main-Class"), Some("com.example.App".to_string()));
+    }
+
+    #[test]
+    fn main_class_continuation() {
+        let manifest = "Manifest-Version: 1.0\nMain-Class: com.example.some\n .really.LongApp\n";
+
+        assert_eq!(manifest_attribute(manifest, "Main-Class"), Some("com.example.some.really.LongApp".to_string()));
+    }
+
+    #[test]
+    fn main_class_missing() {
+        let manifest = "Manifest-Version: 1.0\nCreated-By: Maven JAR Plugin 3.4.1\n";
+
+        assert_eq!(manifest_attribute(manifest, "Main-Class"), None);
+    }
+}
+
+#[cfg(test)]
+mod jmod_tests {
+    use super::*;
+    use crate::class_file::ClassFile;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    /// A jmod holding the given files, written as the JDK's jmod tool writes them: a zip whose offsets
+    /// don't count the header before it.
+    fn jmod(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        for (name, contents) in files {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(contents).unwrap();
+        }
+        let mut jmod = b"JM\x01\x00".to_vec();
+        jmod.extend(zip.finish().unwrap().into_inner());
+        jmod
+    }
+
+    /// The jar's copy of Object, which is as good a class as any to put in a jmod.
+    fn object_class() -> Vec<u8> {
+        let mut jar = ZipArchive::new(File::open("data/robusta-java-runtime-0.1.0.jar").unwrap()).unwrap();
+        let mut bytes = vec![];
+        jar.by_name("java/lang/Object.class").unwrap().read_to_end(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn class_file_names() {
+        let object = object_class();
+        let jmod = jmod(&[
+            ("classes/module-info.class", b"not a class"),
+            ("classes/java/lang/Object.class", &object),
+            ("conf/security/java.policy", b""),
+            ("legal/LICENSE.class", b""),
+        ]);
+
+        let jar = Jar::jmod_from_reader(Cursor::new(jmod)).unwrap();
+
+        assert_eq!(jar.class_file_names(), vec!["java/lang/Object.class"]);
+    }
+
+    #[test]
+    fn read_class() {
+        let object = object_class();
+        let jmod = jmod(&[("classes/module-info.class", b"not a class"), ("classes/java/lang/Object.class", &object)]);
+
+        let mut jar = Jar::jmod_from_reader(Cursor::new(jmod)).unwrap();
+
+        let class_file = ClassFile::read_from(&mut jar.class_file("java/lang/Object.class").unwrap()).unwrap();
+        assert_eq!(class_file.super_class, 0);
+    }
+}
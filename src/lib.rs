@@ -1,138 +1,378 @@
-mod class_file;
+mod check;
+pub mod class_file;
+pub mod descriptor;
+mod heap;
+pub mod instruction;
+mod interpreter;
+#[cfg(feature = "jar")]
+mod jar;
+mod natives;
+mod opcodes;
+mod runtime;
+mod string_concat;
+mod value;
+mod vm;
 
-use crate::class_file::{ClassFile, Code};
+use crate::check::{check_class_path, link_classes, validate_class};
+use crate::class_file::is_class_file_name;
+use crate::interpreter::{create_thread, is_implemented, run_thread};
+#[cfg(feature = "jar")]
+use crate::jar::Jar;
+use crate::opcodes::opcode_name;
+use crate::runtime::{sorted_dir_entries, Runtime, MAX_SUPPORTED_VERSION};
 use anyhow::{anyhow, Error};
-use std::collections::HashMap;
-use std::env::{args, current_dir};
-use std::fs;
+#[cfg(feature = "jar")]
+use anyhow::Context;
+use log::info;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::env::{args, current_dir, split_paths, var_os};
 use std::fs::File;
-use std::io::Cursor;
+use std::io::{stdin, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use zip::ZipArchive;
+
+pub use crate::runtime::{RuntimeClass, RuntimeField, RuntimeMethod};
+pub use crate::value::Value;
+pub use crate::vm::{Host, HostClass, Vm};
+
+/// Robusta's own `java` classes, loaded before any others unless `--bootclasspath` replaces them.
+#[cfg(feature = "jar")]
+const BOOT_JAR: &[u8] = include_bytes!("../data/robusta-java-runtime-0.1.0.jar");
+
+const USAGE: &str = "Usage: robusta [options] <main class> [args...]
+           (to run a class)
+   or  robusta [options] -jar <jar file> [args...]
+           (to run the main class of a jar)
+   or  robusta [options] <class file>.class [args...]
+           (to run a single class file, or - for one read from stdin)";
+
+/// How the main class is given, by the first argument that isn't an option.
+enum MainClass {
+    Name(String),
+    #[cfg(feature = "jar")]
+    Jar(String),
+    /// A single class file, which names itself
+    File(String),
+    /// A single class file read from stdin
+    Stdin,
+}
 
 pub fn run() -> Result<(), Error> {
-    let mut classes = HashMap::new();
+    let mut runtime = Runtime::new();
 
-    let jar_dir = current_dir()?.join("data");
+    let mut args = args().skip(1);
+    let mut class_path = vec![];
+    let mut module_path = vec![];
+    let mut boot_class_path = None;
+    let mut home = None;
+    let mut main_class = None;
+    let mut verbose = false;
+    let mut list = false;
+    let mut check = false;
+    let mut validate_only = false;
+    let mut eager = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-cp" | "-classpath" | "--class-path" => {
+                let entries = args.next().ok_or(anyhow!("{} requires class path specification", arg))?;
+                class_path.extend(split_paths(&entries));
+            }
+            "-p" | "--module-path" => {
+                let entries = args.next().ok_or(anyhow!("{} requires module path specification", arg))?;
+                module_path.extend(split_paths(&entries));
+            }
+            "-bootclasspath" | "--bootclasspath" => {
+                let entries = args.next().ok_or(anyhow!("{} requires class path specification", arg))?;
+                boot_class_path = Some(split_paths(&entries).collect::<Vec<_>>());
+            }
+            "--home" => home = Some(PathBuf::from(args.next().ok_or(anyhow!("--home requires a directory"))?)),
+            "--trace" => runtime.trace = true,
+            "--count-opcodes" => runtime.opcode_counts = Some(Box::new([0; 256])),
+            "-v" | "--verbose" => verbose = true,
+            "--list-classes" => list = true,
+            "--check" => check = true,
+            "--validate-only" => validate_only = true,
+            "--version" | "-version" => {
+                print_version();
+                return Ok(());
+            }
+            "--eager" => eager = true,
+            "--lazy" => eager = false,
+            "-ea" | "-enableassertions" | "--assert" => runtime.assertions = true,
+            "-Xverify" | "-Xverify:all" => runtime.verify = true,
+            "-Xverify:none" => runtime.verify = false,
+            "--strict-access" => runtime.strict_access = true,
+            "--strict-class-path" => runtime.strict_class_path = true,
+            "--ignore-class-version" => runtime.max_version = None,
+            _ if arg.starts_with("-D") => {
+                // A property with no value is set to the empty string
+                let (key, value) = arg["-D".len()..].split_once('=').unwrap_or((&arg["-D".len()..], ""));
+                runtime.properties.insert(key.to_string(), value.to_string());
+            }
+            _ if arg.starts_with("-Xmx") => {
+                let max_size = parse_size(&arg["-Xmx".len()..]).ok_or(anyhow!("Invalid maximum heap size: {}", arg))?;
+                runtime.heap.max_size = Some(max_size);
+            }
+            _ if arg.starts_with("-Xms") => {
+                let initial_size = parse_size(&arg["-Xms".len()..]).ok_or(anyhow!("Invalid initial heap size: {}", arg))?;
+                runtime.heap.initial_size = Some(initial_size);
+            }
+            #[cfg(feature = "jar")]
+            "-jar" => {
+                main_class = Some(MainClass::Jar(args.next().ok_or(anyhow!("-jar requires a jar file"))?));
+                break;
+            }
+            #[cfg(not(feature = "jar"))]
+            "-jar" => Err(anyhow!("-jar requires robusta to be built with the jar feature"))?,
+            "-" => {
+                main_class = Some(MainClass::Stdin);
+                break;
+            }
+            _ if is_class_file_name(&arg) => {
+                main_class = Some(MainClass::File(arg));
+                break;
+            }
+            _ => {
+                main_class = Some(MainClass::Name(arg));
+                break;
+            }
+        }
+    }
+    // Everything after the main class is passed to main
+    let program_args = args.collect::<Vec<_>>();
+    if let (Some(initial_size), Some(max_size)) = (runtime.heap.initial_size, runtime.heap.max_size) {
+        if initial_size > max_size {
+            Err(anyhow!("Initial heap size set to a larger value than the maximum heap size"))?
+        }
+    }
+    if verbose {
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug")).init();
+    }
+    if let Some(initial_size) = runtime.heap.initial_size {
+        info!("heap starting at {} bytes", initial_size);
+    }
+
+    if check {
+        return check_class_path(&mut runtime, &class_path);
+    }
 
-    for path in fs::read_dir(jar_dir)? {
-        let path = path?.path();
-        let zip_reader = File::open(path)?;
-        let mut zip_archive = ZipArchive::new(zip_reader)?;
+    let home = home.or_else(|| var_os("ROBUSTA_HOME").map(PathBuf::from));
+    let boot_jar_loaded = cfg!(feature = "jar") && boot_class_path.is_none() && home.is_none();
+    let mut boot_entries = match &home {
+        Some(home) => home_class_path(home)?,
+        None => vec![],
+    };
+    match boot_class_path {
+        Some(entries) => boot_entries.extend(entries),
+        #[cfg(feature = "jar")]
+        None if home.is_none() => {
+            runtime.load_jar(&mut Jar::from_reader(Cursor::new(BOOT_JAR))?, "the built-in runtime classes")?;
+        }
+        None => {}
+    }
+    for entry in boot_entries {
+        load_class_path_entry(&mut runtime, &entry)?;
+    }
+    for entry in module_path {
+        load_module_path_entry(&mut runtime, &entry)?;
+    }
 
-        let class_files: Vec<String> = zip_archive.file_names()
-            .filter(|file| file.ends_with(".class"))
-            .map(|str| str.to_string())
-            .collect();
+    let jar_dir = current_dir()?.join("data");
+    let mut entries = vec![];
+    // Single class files can run without the runtime jars
+    if jar_dir.is_dir() {
+        // The runtime jar there is the one built in, so loading it again would only duplicate its classes
+        entries.extend(sorted_dir_entries(&jar_dir)?.into_iter().filter(|entry| !(boot_jar_loaded && is_boot_jar(entry))));
+    }
+    entries.extend(class_path);
+    if list {
+        return list_classes(&mut runtime, &entries);
+    }
+    for entry in entries {
+        load_class_path_entry(&mut runtime, &entry)?;
+    }
 
-        for file in class_files {
-            let mut file = zip_archive.by_name(&file)?;
-            let class_file = ClassFile::read_from(&mut file)?;
-            insert_class(&mut classes, class_file)?;
+    let main_class_name = match main_class.ok_or_else(|| anyhow!(USAGE))? {
+        MainClass::Name(name) => name,
+        #[cfg(feature = "jar")]
+        MainClass::Jar(jar_path) => {
+            let mut jar = Jar::open(Path::new(&jar_path))?;
+            runtime.load_jar(&mut jar, &jar_path)?;
+            jar.main_class()?.ok_or(anyhow!("no main manifest attribute, in {}", jar_path))?
         }
+        MainClass::File(path) => {
+            let class_file = runtime.read_class(&mut File::open(&path)?)?;
+            runtime.insert_class(class_file, &path)?.this_class.clone()
+        }
+        MainClass::Stdin => load_stdin_class(&mut runtime)?.this_class.clone(),
+    };
+    if validate_only {
+        return validate_class(&runtime, &main_class_name.replace('.', "/"));
+    }
+    if eager {
+        link_classes(&runtime, &main_class_name.replace('.', "/"))?;
     }
+    let main_class = runtime.get_class(&main_class_name.replace('.', "/"))?.clone();
 
-    let main_class_name = args().nth(1).ok_or(anyhow!("required main class"))?;
-    let main_class = classes.get(&main_class_name).ok_or(anyhow!("unknown class {}", main_class_name))?;
+    // A main that isn't public static can't be invoked without a receiver, so isn't a main method
+    let main_method = main_class.find_method("main", "([Ljava/lang/String;)V")
+        .filter(|method| method.is_public() && method.is_static())
+        .ok_or_else(|| {
+            anyhow!("Main method not found in class {}, please define the main method as:\n   \
+                     public static void main(String[] args)", main_class.this_class.replace('/', "."))
+        })?
+        .clone();
 
-    let main_method = main_class.methods.iter()
-        .find(|method| method.name.eq("main") && method.descriptor.eq("([Ljava/lang/String;)V"))
-        .ok_or(anyhow!("can't find main method"))?;
+    let main_args = runtime.new_string_array(&program_args)?;
 
-    let mut thread = create_thread(main_method);
+    let mut thread = create_thread(main_class, main_method, vec![Value::Reference(main_args)]);
 
-    run_thread(&mut thread)?;
+    let result = run_thread(&mut runtime, &mut thread);
+    if let Some(counts) = runtime.opcode_counts.take() {
+        print_opcode_counts(&mut runtime, &counts)?;
+    }
+    result?;
 
     Ok(())
 }
 
-#[derive(Debug)]
-struct RuntimeClass {
-    this_class: String,
-    methods: Vec<RuntimeMethod>,
+/// Prints what this build supports: the newest class file version it loads and how many of the
+/// specification's opcodes, up to `jsr_w`, it implements.
+fn print_version() {
+    let opcodes = (0..=0xC9).filter(|opcode| is_implemented(*opcode)).count();
+    println!("robusta {}", env!("CARGO_PKG_VERSION"));
+    println!("Class file versions up to {} (Java {})", MAX_SUPPORTED_VERSION, MAX_SUPPORTED_VERSION - 44);
+    println!("{} of {} opcodes implemented", opcodes, 0xC9 + 1);
 }
 
-// Need to think about how we name this
-#[derive(Debug)]
-struct RuntimeMethod {
-    name: String,
-    descriptor: String,
-    code: Code,
+/// Prints how many times each opcode ran, most frequent first, skipping those that never did.
+fn print_opcode_counts(runtime: &mut Runtime, counts: &[u64; 256]) -> Result<(), Error> {
+    let mut opcodes = (0..=u8::MAX).filter(|opcode| counts[*opcode as usize] > 0).collect::<Vec<_>>();
+    opcodes.sort_by_key(|opcode| Reverse(counts[*opcode as usize]));
+    for opcode in opcodes {
+        writeln!(runtime.stderr, "{:>12} {}", counts[opcode as usize], opcode_name(opcode))?;
+    }
+    Ok(())
 }
 
-fn insert_class(classes: &mut HashMap<String, Rc<RuntimeClass>>, class_file: ClassFile) -> Result<Rc<RuntimeClass>, Error> {
-    let this_class = class_file.const_pool.get_class(class_file.this_class)?;
-    let class_name = class_file.const_pool.get_utf8(this_class.name_idx)?;
-
-    let mut methods = Vec::with_capacity(class_file.methods.len());
-    for method in class_file.methods {
-        let name = class_file.const_pool.get_utf8(method.name_idx)?;
-        let descriptor = class_file.const_pool.get_utf8(method.descriptor_idx)?;
-
-        let code_attr = method.attributes.iter().find(|attr| {
-            class_file.const_pool.get_utf8(attr.name_idx).ok().map(|name_const| {
-                name_const.bytes.eq("Code")
-            }).unwrap_or_else(|| false)
-        });
-
-        let code = if let Some(code_attr) = code_attr {
-            let mut reader = Cursor::new(&code_attr.info);
-            Code::read_from(&mut reader)?
-        } else {
-            Code {
-                _max_stack: 0,
-                _max_locals: 0,
-                code: vec![],
-            }
-        };
+/// Parses a heap size as given to `-Xmx`, in bytes or with a `k`, `m` or `g` suffix.
+fn parse_size(size: &str) -> Option<usize> {
+    let (digits, unit) = match size.char_indices().last()? {
+        (idx, 'k' | 'K') => (&size[..idx], 1 << 10),
+        (idx, 'm' | 'M') => (&size[..idx], 1 << 20),
+        (idx, 'g' | 'G') => (&size[..idx], 1 << 30),
+        _ => (size, 1),
+    };
+    digits.parse::<usize>().ok()?.checked_mul(unit)
+}
 
-        methods.push(RuntimeMethod {
-            name: name.bytes.clone(),
-            descriptor: descriptor.bytes.clone(),
-            code,
-        });
+/// The boot class path entries of a Robusta home directory: its `lib` directory, as a directory of
+/// class files, followed by the jars in it in name order.
+fn home_class_path(home: &Path) -> Result<Vec<PathBuf>, Error> {
+    let lib = home.join("lib");
+    if !lib.is_dir() {
+        Err(anyhow!("Robusta home {} has no lib directory", home.display()))?
     }
+    let jars = sorted_dir_entries(&lib)?.into_iter()
+        .filter(|path| path.extension().is_some_and(|extension| extension.eq("jar")));
+    Ok([lib].into_iter().chain(jars).collect())
+}
 
-    let class = Rc::new(RuntimeClass {
-        this_class: class_name.bytes.clone(),
-        methods,
-    });
-
-    classes.insert(class.this_class.clone().replace("/", "."), class.clone());
+/// Whether a file holds exactly the runtime classes built into the binary.
+#[cfg(feature = "jar")]
+fn is_boot_jar(path: &Path) -> bool {
+    path.metadata().is_ok_and(|metadata| metadata.len() == BOOT_JAR.len() as u64)
+        && std::fs::read(path).is_ok_and(|bytes| bytes == BOOT_JAR)
+}
 
-    Ok(class)
+#[cfg(not(feature = "jar"))]
+fn is_boot_jar(_path: &Path) -> bool {
+    false
 }
 
-struct Thread {
-    frames: Vec<Frame>,
+/// Loads a single class file read from stdin.
+fn load_stdin_class(runtime: &mut Runtime) -> Result<Rc<RuntimeClass>, Error> {
+    let mut bytes = vec![];
+    stdin().read_to_end(&mut bytes)?;
+    let class_file = runtime.read_class(&mut Cursor::new(bytes))?;
+    runtime.insert_class(class_file, "stdin")
 }
 
-struct Frame {
-    pc: usize,
-    code: Vec<u8>,
+/// Loads a directory of class files or, with the `jar` feature, a jar or jmod, giving the classes
+/// loaded. Jars found without the feature are skipped so that the runtime directory can still be
+/// scanned for directories.
+pub(crate) fn load_class_path_entry(runtime: &mut Runtime, path: &Path) -> Result<Vec<Rc<RuntimeClass>>, Error> {
+    info!("loading class path entry {}", path.display());
+    if path == Path::new("-") {
+        return Ok(vec![load_stdin_class(runtime)?]);
+    }
+    if path.is_dir() {
+        return runtime.load_dir(path);
+    }
+
+    match path.extension() {
+        #[cfg(feature = "jar")]
+        Some(extension) if extension.eq("jar") => {
+            let mut jar = Jar::open(path)?;
+            runtime.load_jar(&mut jar, &path.display().to_string()).with_context(|| format!("failed to load jar {}", path.display()))
+        }
+        #[cfg(feature = "jar")]
+        Some(extension) if extension.eq("jmod") => {
+            let mut jmod = Jar::open_jmod(path)?;
+            runtime.load_jar(&mut jmod, &path.display().to_string()).with_context(|| format!("failed to load jmod {}", path.display()))
+        }
+        #[cfg(not(feature = "jar"))]
+        Some(extension) if extension.eq("jar") || extension.eq("jmod") => Ok(vec![]),
+        _ => Err(anyhow!("unsupported class path entry {}", path.display())),
+    }
 }
 
-fn create_thread(method: &RuntimeMethod) -> Thread {
-    Thread {
-        frames: vec![Frame {
-            pc: 0,
-            code: method.code.code.clone(),
-        }],
+/// Loads a module path entry: a jmod, a jar, an exploded module, which is a directory of class files
+/// with a `module-info.class`, or a directory of any of those, which are loaded in name order.
+fn load_module_path_entry(runtime: &mut Runtime, path: &Path) -> Result<Vec<Rc<RuntimeClass>>, Error> {
+    if !path.is_dir() || path.join("module-info.class").is_file() {
+        return load_class_path_entry(runtime, path);
+    }
+    let mut classes = vec![];
+    for module in sorted_dir_entries(path)? {
+        classes.extend(load_class_path_entry(runtime, &module)?);
     }
+    Ok(classes)
 }
 
-fn run_thread(thread: &mut Thread) -> Result<(), Error> {
-    while let Some(frame) = thread.frames.last_mut() {
-        while frame.pc < frame.code.len() {
-            let instr = frame.code[frame.pc];
-            match instr {
-                0xB1 => {
-                    thread.frames.pop();
-                    break;
-                }
-                _ => Err(anyhow!("unknown instruction {:#02x}", instr))?
-            }
+/// Prints the name of every class on the class path with the entry it's loaded from, sorted by name.
+/// A class in more than one entry is listed with the one it's loaded from, the last.
+fn list_classes(runtime: &mut Runtime, entries: &[PathBuf]) -> Result<(), Error> {
+    let mut sources = BTreeMap::new();
+    for entry in entries {
+        for class in load_class_path_entry(runtime, entry)? {
+            sources.insert(class.this_class.replace('/', "."), entry);
         }
     }
+    for (class_name, source) in sources {
+        println!("{} ({})", class_name, source.display());
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod parse_size_tests {
+    use super::*;
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("1024"), Some(1024));
+        assert_eq!(parse_size("64k"), Some(64 << 10));
+        assert_eq!(parse_size("16M"), Some(16 << 20));
+        assert_eq!(parse_size("2g"), Some(2 << 30));
+    }
+
+    #[test]
+    fn invalid_sizes() {
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("m"), None);
+        assert_eq!(parse_size("-1m"), None);
+        assert_eq!(parse_size("12x"), None);
+    }
+}
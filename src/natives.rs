@@ -0,0 +1,580 @@
+use crate::heap::{Array, HeapObject, Object, Reference};
+use crate::runtime::Runtime;
+use crate::value::Value;
+use anyhow::{anyhow, Error};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Implements a native method, taking its arguments (with the receiver first for instance methods)
+/// and producing its return value.
+pub type NativeMethod = fn(&mut Runtime, Vec<Value>) -> Result<Option<Value>, Error>;
+
+/// The boxed values `valueOf` returns shared instances of, as the JVM's caches do.
+const BOX_CACHE_RANGE: std::ops::RangeInclusive<i64> = -128..=127;
+
+/// Finds the implementation of a method declared `native` by a runtime class.
+pub fn find_native(class_name: &str, name: &str, descriptor: &str) -> Option<NativeMethod> {
+    match (class_name, name, descriptor) {
+        ("java/lang/Integer", "valueOf", "(I)Ljava/lang/Integer;") => Some(integer_value_of),
+        ("java/lang/Long", "valueOf", "(J)Ljava/lang/Long;") => Some(long_value_of),
+        ("java/lang/Float", "valueOf", "(F)Ljava/lang/Float;") => Some(float_value_of),
+        ("java/lang/Double", "valueOf", "(D)Ljava/lang/Double;") => Some(double_value_of),
+        ("java/lang/Integer", "bitCount", "(I)I") => Some(|_, args| int_bits(&args, |i| i.count_ones() as i32)),
+        ("java/lang/Integer", "highestOneBit", "(I)I") => Some(|_, args| int_bits(&args, |i| {
+            i & (i32::MIN as u32).checked_shr(i.leading_zeros()).unwrap_or(0) as i32
+        })),
+        ("java/lang/Integer", "lowestOneBit", "(I)I") => Some(|_, args| int_bits(&args, |i| i & i.wrapping_neg())),
+        ("java/lang/Integer", "numberOfLeadingZeros", "(I)I") => Some(|_, args| int_bits(&args, |i| i.leading_zeros() as i32)),
+        ("java/lang/Integer", "numberOfTrailingZeros", "(I)I") => Some(|_, args| int_bits(&args, |i| i.trailing_zeros() as i32)),
+        ("java/lang/Long", "bitCount", "(J)I") => Some(|_, args| long_bits(&args, |i| Value::Int(i.count_ones() as i32))),
+        ("java/lang/Long", "highestOneBit", "(J)J") => Some(|_, args| long_bits(&args, |i| {
+            Value::Long(i & (i64::MIN as u64).checked_shr(i.leading_zeros()).unwrap_or(0) as i64)
+        })),
+        ("java/lang/Long", "lowestOneBit", "(J)J") => Some(|_, args| long_bits(&args, |i| Value::Long(i & i.wrapping_neg()))),
+        ("java/lang/Long", "numberOfLeadingZeros", "(J)I") => Some(|_, args| long_bits(&args, |i| Value::Int(i.leading_zeros() as i32))),
+        ("java/lang/Long", "numberOfTrailingZeros", "(J)I") => Some(|_, args| long_bits(&args, |i| Value::Int(i.trailing_zeros() as i32))),
+        ("java/lang/Object", "clone", "()Ljava/lang/Object;") => Some(clone),
+        ("java/lang/Object", "notify" | "notifyAll" | "wait", "()V") => Some(check_monitor),
+        // With only one thread nothing could notify a waiter, so waiting returns at once, as a
+        // spurious wakeup may
+        ("java/lang/Object", "wait", "(J)V") => Some(check_monitor),
+        ("java/lang/String", "length", "()I") => Some(string_length),
+        ("java/lang/String", "charAt", "(I)C") => Some(string_char_at),
+        ("java/lang/String", "equals", "(Ljava/lang/Object;)Z") => Some(string_equals),
+        ("java/lang/String", "intern", "()Ljava/lang/String;") => Some(string_intern),
+        ("java/lang/Enum", "valueOf", "(Ljava/lang/Class;Ljava/lang/String;)Ljava/lang/Enum;") => Some(enum_value_of),
+        ("java/util/Objects", "requireNonNull", "(Ljava/lang/Object;)Ljava/lang/Object;") => Some(require_non_null),
+        ("java/util/Objects", "requireNonNull", "(Ljava/lang/Object;Ljava/lang/String;)Ljava/lang/Object;") => Some(require_non_null),
+        // Every overload, as the array itself knows its element type
+        ("java/util/Arrays", "toString", _) => Some(arrays_to_string),
+        ("java/util/Arrays", "fill", _) => Some(arrays_fill),
+        ("java/lang/Class", "getName", "()Ljava/lang/String;") => Some(class_get_name),
+        ("java/lang/Class", "desiredAssertionStatus", "()Z") => Some(|runtime, _| Ok(Some(Value::Int(runtime.assertions as i32)))),
+        ("java/lang/System", "standardStream", "(I)Ljava/io/PrintStream;") => Some(standard_stream),
+        ("java/lang/System", "getProperty", "(Ljava/lang/String;)Ljava/lang/String;") => Some(get_property),
+        ("java/lang/System", "getProperty", "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;") => Some(get_property),
+        ("java/io/PrintStream", "print", "(Z)V") => Some(|runtime, args| print(runtime, &args, format_boolean, false)),
+        ("java/io/PrintStream", "print", "(C)V") => Some(|runtime, args| print(runtime, &args, format_char, false)),
+        ("java/io/PrintStream", "print", "(I)V") => Some(|runtime, args| print(runtime, &args, format_int, false)),
+        ("java/io/PrintStream", "print", "(J)V") => Some(|runtime, args| print(runtime, &args, format_long, false)),
+        ("java/io/PrintStream", "print", "(Ljava/lang/String;)V") => Some(|runtime, args| print(runtime, &args, format_string, false)),
+        ("java/io/PrintStream", "println", "()V") => Some(|runtime, args| print(runtime, &args, format_nothing, true)),
+        ("java/io/PrintStream", "println", "(Z)V") => Some(|runtime, args| print(runtime, &args, format_boolean, true)),
+        ("java/io/PrintStream", "println", "(C)V") => Some(|runtime, args| print(runtime, &args, format_char, true)),
+        ("java/io/PrintStream", "println", "(I)V") => Some(|runtime, args| print(runtime, &args, format_int, true)),
+        ("java/io/PrintStream", "println", "(J)V") => Some(|runtime, args| print(runtime, &args, format_long, true)),
+        ("java/io/PrintStream", "println", "(Ljava/lang/String;)V") => Some(|runtime, args| print(runtime, &args, format_string, true)),
+        ("java/lang/Integer" | "java/lang/Long" | "java/lang/Float" | "java/lang/Double", _, _) => match (name, descriptor) {
+            ("intValue", "()I") => Some(int_value),
+            ("longValue", "()J") => Some(long_value),
+            ("floatValue", "()F") => Some(float_value),
+            ("doubleValue", "()D") => Some(double_value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn integer_value_of(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let value = arg(&args, 0)?.as_int()?;
+    box_cached(runtime, "java/lang/Integer", value as i64, Value::Int(value))
+}
+
+fn long_value_of(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let value = arg(&args, 0)?.as_long()?;
+    box_cached(runtime, "java/lang/Long", value, Value::Long(value))
+}
+
+fn float_value_of(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let value = Value::Float(arg(&args, 0)?.as_float()?);
+    Ok(Some(Value::Reference(new_box(runtime, "java/lang/Float", value)?)))
+}
+
+fn double_value_of(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let value = Value::Double(arg(&args, 0)?.as_double()?);
+    Ok(Some(Value::Reference(new_box(runtime, "java/lang/Double", value)?)))
+}
+
+/// Applies a bit manipulation to an int argument. Rust's intrinsics give the results Java specifies
+/// for zero, such as 32 leading zeros.
+fn int_bits(args: &[Value], op: fn(i32) -> i32) -> Result<Option<Value>, Error> {
+    Ok(Some(Value::Int(op(arg(args, 0)?.as_int()?))))
+}
+
+/// Applies a bit manipulation to a long argument, giving an int count or a long.
+fn long_bits(args: &[Value], op: fn(i64) -> Value) -> Result<Option<Value>, Error> {
+    Ok(Some(op(arg(args, 0)?.as_long()?)))
+}
+
+fn int_value(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let value = match unbox(runtime, arg(&args, 0)?)? {
+        Value::Int(value) => value,
+        Value::Long(value) => value as i32,
+        Value::Float(value) => value as i32,
+        Value::Double(value) => value as i32,
+        value => Err(anyhow!("cannot convert {:?} to int", value))?,
+    };
+    Ok(Some(Value::Int(value)))
+}
+
+fn long_value(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let value = match unbox(runtime, arg(&args, 0)?)? {
+        Value::Int(value) => value as i64,
+        Value::Long(value) => value,
+        Value::Float(value) => value as i64,
+        Value::Double(value) => value as i64,
+        value => Err(anyhow!("cannot convert {:?} to long", value))?,
+    };
+    Ok(Some(Value::Long(value)))
+}
+
+fn float_value(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let value = match unbox(runtime, arg(&args, 0)?)? {
+        Value::Int(value) => value as f32,
+        Value::Long(value) => value as f32,
+        Value::Float(value) => value,
+        Value::Double(value) => value as f32,
+        value => Err(anyhow!("cannot convert {:?} to float", value))?,
+    };
+    Ok(Some(Value::Float(value)))
+}
+
+fn double_value(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let value = match unbox(runtime, arg(&args, 0)?)? {
+        Value::Int(value) => value as f64,
+        Value::Long(value) => value as f64,
+        Value::Float(value) => value as f64,
+        Value::Double(value) => value,
+        value => Err(anyhow!("cannot convert {:?} to double", value))?,
+    };
+    Ok(Some(Value::Double(value)))
+}
+
+/// Copies an array, sharing the referents of a reference array's elements. No class implements
+/// `Cloneable`, so cloning any other object throws.
+fn clone(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let receiver = arg(&args, 0)?.as_reference()?.ok_or(anyhow!("clone of null object"))?;
+    let copy = match runtime.heap.get(receiver)? {
+        HeapObject::Array(array) => HeapObject::Array(array.clone()),
+        HeapObject::Object(object) => {
+            let class_name = object.class.this_class.replace('/', ".");
+            Err(runtime.throw("java/lang/CloneNotSupportedException", &class_name))?
+        }
+    };
+    Ok(Some(Value::Reference(runtime.heap.allocate(copy)?)))
+}
+
+fn string_length(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let string = arg(&args, 0)?.as_reference()?.ok_or(anyhow!("length of null string"))?;
+    Ok(Some(Value::Int(string_chars(runtime, string)?.len() as i32)))
+}
+
+fn string_char_at(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let string = arg(&args, 0)?.as_reference()?.ok_or(anyhow!("charAt of null string"))?;
+    let index = arg(&args, 1)?.as_int()?;
+    let chars = string_chars(runtime, string)?;
+    match usize::try_from(index).ok().and_then(|index| chars.get(index)) {
+        Some(char) => Ok(Some(Value::Int(*char as i32))),
+        None => {
+            let message = format!("Index {} out of bounds for length {}", index, chars.len());
+            Err(runtime.throw("java/lang/StringIndexOutOfBoundsException", &message))
+        }
+    }
+}
+
+/// Whether a string has the same chars as another object, which is only ever true of another string.
+fn string_equals(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let string = arg(&args, 0)?.as_reference()?.ok_or(anyhow!("equals of null string"))?;
+    let equal = match arg(&args, 1)?.as_reference()? {
+        Some(other) if other == string => true,
+        Some(other) if matches!(runtime.heap.get(other)?,
+            HeapObject::Object(object) if object.class.this_class == "java/lang/String") =>
+            string_chars(runtime, string)? == string_chars(runtime, other)?,
+        _ => false,
+    };
+    Ok(Some(Value::Int(equal as i32)))
+}
+
+/// The chars of a string, which unlike its value as a Rust string may hold unpaired surrogates.
+fn string_chars(runtime: &Runtime, string: Reference) -> Result<&[u16], Error> {
+    let HeapObject::Object(object) = runtime.heap.get(string)? else {
+        Err(anyhow!("string {:?} is not an object", string))?
+    };
+    let chars = object.fields.get("value").copied().unwrap_or(Value::Null).as_reference()?
+        .ok_or_else(|| anyhow!("string {:?} has no value", string))?;
+    let HeapObject::Array(Array::Char(chars)) = runtime.heap.get(chars)? else {
+        Err(anyhow!("string {:?} value is not a char array", string))?
+    };
+    Ok(chars)
+}
+
+fn string_intern(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let string = arg(&args, 0)?.as_reference()?.ok_or(anyhow!("intern of null string"))?;
+    let value = runtime.read_string(string)?;
+    Ok(Some(Value::Reference(runtime.intern(&value)?)))
+}
+
+/// Finds the constant of an enum class with the given name among those its static initializer stored
+/// in the synthetic `$VALUES` array.
+fn enum_value_of(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let class_object = arg(&args, 0)?.as_reference()?.ok_or(anyhow!("valueOf of null class"))?;
+    let HeapObject::Object(class_object) = runtime.heap.get(class_object)? else {
+        Err(anyhow!("class {:?} is not an object", class_object))?
+    };
+    let class_name = class_object.fields.get("name").copied().unwrap_or(Value::Null).as_reference()?
+        .ok_or(anyhow!("class {:?} has no name", class_object))?;
+    let class_name = runtime.read_string(class_name)?;
+    let Some(name) = arg(&args, 1)?.as_reference()? else {
+        Err(runtime.throw("java/lang/NullPointerException", "Name is null"))?
+    };
+    let name = runtime.read_string(name)?;
+
+    if !runtime.get_class(&class_name.replace('.', "/"))?.is_enum() {
+        Err(runtime.throw("java/lang/IllegalArgumentException", &format!("{} is not an enum class", class_name)))?
+    }
+    let values = runtime.get_static(&class_name.replace('.', "/"), "$VALUES").unwrap_or(Value::Null).as_reference()?
+        .ok_or(anyhow!("enum class {} has no $VALUES", class_name))?;
+    let HeapObject::Array(values) = runtime.heap.get(values)? else {
+        Err(anyhow!("$VALUES of {} is not an array", class_name))?
+    };
+    for idx in 0..values.len() {
+        let Some(value) = values.load(idx)?.as_reference()? else { continue };
+        let HeapObject::Object(constant) = runtime.heap.get(value)? else { continue };
+        let constant_name = constant.fields.get("name").copied().unwrap_or(Value::Null).as_reference()?;
+        if constant_name.map(|constant_name| runtime.read_string(constant_name)).transpose()?.as_deref() == Some(&name) {
+            return Ok(Some(Value::Reference(value)));
+        }
+    }
+    Err(runtime.throw("java/lang/IllegalArgumentException", &format!("No enum constant {}.{}", class_name, name)))?
+}
+
+/// Returns the object unless it's null, when it throws a NullPointerException with the message given
+/// as the second argument, if there is one.
+fn require_non_null(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let object = arg(&args, 0)?;
+    if object.as_reference()?.is_some() {
+        return Ok(Some(object));
+    }
+    match args.get(1).copied().unwrap_or(Value::Null).as_reference()? {
+        Some(message) => {
+            let message = runtime.read_string(message)?;
+            Err(runtime.throw("java/lang/NullPointerException", &message))
+        }
+        None => Err(runtime.throw_without_message("java/lang/NullPointerException")),
+    }
+}
+
+/// Formats an array's elements as `[1, 2, 3]`, or gives `"null"` for a null array.
+fn arrays_to_string(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let Some(array) = arg(&args, 0)?.as_reference()? else {
+        return Ok(Some(Value::Reference(runtime.new_string("null")?)));
+    };
+    let HeapObject::Array(array) = runtime.heap.get(array)? else {
+        Err(anyhow!("Arrays.toString of non-array {:?}", array))?
+    };
+    let elements = match array {
+        Array::Boolean(elements) => elements.iter().map(|value| (*value != 0).to_string()).collect(),
+        Array::Byte(elements) => elements.iter().map(i8::to_string).collect(),
+        Array::Char(elements) => elements.iter().map(|value| String::from_utf16_lossy(&[*value])).collect(),
+        Array::Short(elements) => elements.iter().map(i16::to_string).collect(),
+        Array::Int(elements) => elements.iter().map(i32::to_string).collect(),
+        Array::Reference { elements, .. } => elements.iter()
+            .map(|element| element_string(runtime, *element))
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    let string = format!("[{}]", elements.join(", "));
+    Ok(Some(Value::Reference(runtime.new_string(&string)?)))
+}
+
+/// Converts an element of an Object[] to a string as `String.valueOf` would for the classes whose
+/// `toString` the runtime knows: strings and boxes. Any other object is shown as `Object.toString`
+/// shows it, by class name and identity hash code.
+fn element_string(runtime: &Runtime, element: Value) -> Result<String, Error> {
+    let Some(reference) = element.as_reference()? else {
+        return Ok("null".to_string());
+    };
+    match runtime.heap.get(reference)?.type_name() {
+        "java/lang/String" => runtime.read_string(reference),
+        "java/lang/Integer" | "java/lang/Long" | "java/lang/Float" | "java/lang/Double" => {
+            Ok(unbox(runtime, element)?.display(&runtime.heap).to_string())
+        }
+        _ => Ok(element.display(&runtime.heap).to_string()),
+    }
+}
+
+/// Stores a value in every element of an array, narrowed to the element type as its store
+/// instruction would.
+fn arrays_fill(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let Some(array) = arg(&args, 0)?.as_reference()? else {
+        Err(runtime.throw("java/lang/NullPointerException", "Cannot read the array length because \"a\" is null"))?
+    };
+    let value = arg(&args, 1)?;
+    let HeapObject::Array(array) = runtime.heap.get_mut(array)? else {
+        Err(anyhow!("Arrays.fill of non-array {:?}", array))?
+    };
+    for idx in 0..array.len() {
+        array.store(idx, value)?;
+    }
+    Ok(None)
+}
+
+/// Throws unless the receiver's monitor is held, as required to notify or wait on it.
+fn check_monitor(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let object = arg(&args, 0)?.as_reference()?.ok_or(anyhow!("monitor of null object"))?;
+    if !runtime.holds_monitor(object) {
+        Err(runtime.throw("java/lang/IllegalMonitorStateException", "current thread is not owner"))?
+    }
+    Ok(None)
+}
+
+fn class_get_name(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let class_object = arg(&args, 0)?.as_reference()?.ok_or(anyhow!("getName of null class"))?;
+    let HeapObject::Object(object) = runtime.heap.get(class_object)? else {
+        Err(anyhow!("class {:?} is not an object", class_object))?
+    };
+    Ok(Some(object.fields.get("name").copied().unwrap_or(Value::Null)))
+}
+
+/// Looks up a system property, giving the default argument, if there is one, or else null when it's unset.
+fn get_property(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let Some(key) = arg(&args, 0)?.as_reference()? else {
+        Err(runtime.throw("java/lang/NullPointerException", "key can't be null"))?
+    };
+    let key = runtime.read_string(key)?;
+    match runtime.properties.get(&key).cloned() {
+        Some(value) => Ok(Some(Value::Reference(runtime.new_string(&value)?))),
+        None => Ok(Some(args.get(1).copied().unwrap_or(Value::Null))),
+    }
+}
+
+/// Creates a PrintStream for a file descriptor, setting its field directly as its constructor is
+/// package private.
+fn standard_stream(runtime: &mut Runtime, args: Vec<Value>) -> Result<Option<Value>, Error> {
+    let fd = arg(&args, 0)?;
+    let class = runtime.get_class("java/io/PrintStream")?.clone();
+    let stream = runtime.heap.allocate(HeapObject::Object(Object {
+        class,
+        fields: HashMap::from([("fd".to_string(), Value::Int(fd.as_int()?))]),
+    }))?;
+    Ok(Some(Value::Reference(stream)))
+}
+
+/// Formats the printed argument of a PrintStream print method as Java's `String.valueOf` would.
+type PrintFormat = fn(&Runtime, Option<Value>) -> Result<String, Error>;
+
+/// Writes the print method's argument to the standard stream the receiving PrintStream targets.
+fn print(runtime: &mut Runtime, args: &[Value], format: PrintFormat, newline: bool) -> Result<Option<Value>, Error> {
+    let receiver = arg(args, 0)?.as_reference()?.ok_or(anyhow!("print to null stream"))?;
+    let HeapObject::Object(stream) = runtime.heap.get(receiver)? else {
+        Err(anyhow!("stream {:?} is not an object", receiver))?
+    };
+    let fd = stream.fields.get("fd").copied().unwrap_or(Value::Int(1)).as_int()?;
+    let text = print_text(runtime, args, format, newline)?;
+    let sink = if fd == 2 { &mut runtime.stderr } else { &mut runtime.stdout };
+    sink.write_all(text.as_bytes())?;
+    sink.flush()?;
+    Ok(None)
+}
+
+fn print_text(runtime: &Runtime, args: &[Value], format: PrintFormat, newline: bool) -> Result<String, Error> {
+    let mut text = format(runtime, args.get(1).copied())?;
+    if newline {
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+fn format_nothing(_: &Runtime, _: Option<Value>) -> Result<String, Error> {
+    Ok(String::new())
+}
+
+fn format_boolean(_: &Runtime, value: Option<Value>) -> Result<String, Error> {
+    let value = value.ok_or(anyhow!("missing print argument"))?.as_int()?;
+    Ok(if value != 0 { "true" } else { "false" }.to_string())
+}
+
+fn format_char(_: &Runtime, value: Option<Value>) -> Result<String, Error> {
+    let value = value.ok_or(anyhow!("missing print argument"))?.as_int()?;
+    Ok(String::from_utf16_lossy(&[value as u16]))
+}
+
+fn format_int(_: &Runtime, value: Option<Value>) -> Result<String, Error> {
+    Ok(value.ok_or(anyhow!("missing print argument"))?.as_int()?.to_string())
+}
+
+fn format_long(_: &Runtime, value: Option<Value>) -> Result<String, Error> {
+    Ok(value.ok_or(anyhow!("missing print argument"))?.as_long()?.to_string())
+}
+
+fn format_string(runtime: &Runtime, value: Option<Value>) -> Result<String, Error> {
+    match value.ok_or(anyhow!("missing print argument"))?.as_reference()? {
+        Some(string) => runtime.read_string(string),
+        None => Ok("null".to_string()),
+    }
+}
+
+fn arg(args: &[Value], idx: usize) -> Result<Value, Error> {
+    args.get(idx).copied().ok_or(anyhow!("missing native argument {}", idx))
+}
+
+/// Boxes a value, sharing one instance per value within the cache range.
+fn box_cached(runtime: &mut Runtime, class_name: &'static str, key: i64, value: Value) -> Result<Option<Value>, Error> {
+    if !BOX_CACHE_RANGE.contains(&key) {
+        return Ok(Some(Value::Reference(new_box(runtime, class_name, value)?)));
+    }
+    if let Some(reference) = runtime.box_cache.get(&(class_name, key)) {
+        return Ok(Some(Value::Reference(*reference)));
+    }
+    let reference = new_box(runtime, class_name, value)?;
+    runtime.box_cache.insert((class_name, key), reference);
+    Ok(Some(Value::Reference(reference)))
+}
+
+fn new_box(runtime: &mut Runtime, class_name: &str, value: Value) -> Result<Reference, Error> {
+    let class = runtime.get_class(class_name)?.clone();
+    runtime.heap.allocate(HeapObject::Object(Object {
+        class,
+        fields: HashMap::from([("value".to_string(), value)]),
+    }))
+}
+
+fn unbox(runtime: &Runtime, receiver: Value) -> Result<Value, Error> {
+    let receiver = receiver.as_reference()?.ok_or(anyhow!("native called on null receiver"))?;
+    let HeapObject::Object(object) = runtime.heap.get(receiver)? else {
+        Err(anyhow!("box {:?} is not an object", receiver))?
+    };
+    object.fields.get("value").copied().ok_or(anyhow!("box {:?} has no value", receiver))
+}
+
+#[cfg(test)]
+mod bit_tests {
+    use super::*;
+
+    fn call(class_name: &str, name: &str, descriptor: &str, arg: Value) -> Value {
+        find_native(class_name, name, descriptor).unwrap()(&mut Runtime::new(), vec![arg]).unwrap().unwrap()
+    }
+
+    #[test]
+    fn bit_count() {
+        assert_eq!(call("java/lang/Integer", "bitCount", "(I)I", Value::Int(0)), Value::Int(0));
+        assert_eq!(call("java/lang/Integer", "bitCount", "(I)I", Value::Int(0b1011)), Value::Int(3));
+        assert_eq!(call("java/lang/Integer", "bitCount", "(I)I", Value::Int(-1)), Value::Int(32));
+        assert_eq!(call("java/lang/Long", "bitCount", "(J)I", Value::Long(-1)), Value::Int(64));
+    }
+
+    #[test]
+    fn number_of_leading_zeros() {
+        assert_eq!(call("java/lang/Integer", "numberOfLeadingZeros", "(I)I", Value::Int(0)), Value::Int(32));
+        assert_eq!(call("java/lang/Integer", "numberOfLeadingZeros", "(I)I", Value::Int(1)), Value::Int(31));
+        assert_eq!(call("java/lang/Integer", "numberOfLeadingZeros", "(I)I", Value::Int(-1)), Value::Int(0));
+        assert_eq!(call("java/lang/Long", "numberOfLeadingZeros", "(J)I", Value::Long(0)), Value::Int(64));
+        assert_eq!(call("java/lang/Long", "numberOfTrailingZeros", "(J)I", Value::Long(1 << 40)), Value::Int(40));
+    }
+
+    #[test]
+    fn one_bits() {
+        assert_eq!(call("java/lang/Integer", "highestOneBit", "(I)I", Value::Int(0)), Value::Int(0));
+        assert_eq!(call("java/lang/Integer", "highestOneBit", "(I)I", Value::Int(100)), Value::Int(64));
+        assert_eq!(call("java/lang/Integer", "highestOneBit", "(I)I", Value::Int(-5)), Value::Int(i32::MIN));
+        assert_eq!(call("java/lang/Integer", "lowestOneBit", "(I)I", Value::Int(100)), Value::Int(4));
+        assert_eq!(call("java/lang/Long", "highestOneBit", "(J)J", Value::Long(0)), Value::Long(0));
+        assert_eq!(call("java/lang/Long", "lowestOneBit", "(J)J", Value::Long(i64::MIN)), Value::Long(i64::MIN));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod boxing_tests {
+    use super::*;
+    use crate::runtime::test_runtime::test_runtime;
+
+    fn call(runtime: &mut Runtime, class_name: &str, name: &str, descriptor: &str, args: Vec<Value>) -> Value {
+        find_native(class_name, name, descriptor).unwrap()(runtime, args).unwrap().unwrap()
+    }
+
+    #[test]
+    fn long_cache() {
+        let mut runtime = test_runtime();
+
+        let first = call(&mut runtime, "java/lang/Long", "valueOf", "(J)Ljava/lang/Long;", vec![Value::Long(-128)]);
+        let second = call(&mut runtime, "java/lang/Long", "valueOf", "(J)Ljava/lang/Long;", vec![Value::Long(-128)]);
+        let large = call(&mut runtime, "java/lang/Long", "valueOf", "(J)Ljava/lang/Long;", vec![Value::Long(1 << 40)]);
+
+        assert_eq!(first, second);
+        assert_eq!(call(&mut runtime, "java/lang/Long", "longValue", "()J", vec![large]), Value::Long(1 << 40));
+    }
+
+    #[test]
+    fn doubles_are_not_cached() {
+        let mut runtime = test_runtime();
+
+        let first = call(&mut runtime, "java/lang/Double", "valueOf", "(D)Ljava/lang/Double;", vec![Value::Double(1.0)]);
+        let second = call(&mut runtime, "java/lang/Double", "valueOf", "(D)Ljava/lang/Double;", vec![Value::Double(1.0)]);
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn convert_between_boxes() {
+        let mut runtime = test_runtime();
+
+        let double = call(&mut runtime, "java/lang/Double", "valueOf", "(D)Ljava/lang/Double;", vec![Value::Double(-2.75)]);
+        let float = call(&mut runtime, "java/lang/Float", "valueOf", "(F)Ljava/lang/Float;", vec![Value::Float(f32::NAN)]);
+
+        assert_eq!(call(&mut runtime, "java/lang/Double", "intValue", "()I", vec![double]), Value::Int(-2));
+        assert_eq!(call(&mut runtime, "java/lang/Double", "floatValue", "()F", vec![double]), Value::Float(-2.75));
+        assert_eq!(call(&mut runtime, "java/lang/Float", "longValue", "()J", vec![float]), Value::Long(0));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod print_tests {
+    use super::*;
+    use crate::runtime::test_runtime::test_runtime;
+
+    fn stream(runtime: &mut Runtime) -> Value {
+        let class = runtime.get_class("java/io/PrintStream").unwrap().clone();
+        Value::Reference(runtime.heap.allocate(HeapObject::Object(Object {
+            class,
+            fields: HashMap::from([("fd".to_string(), Value::Int(1))]),
+        })).unwrap())
+    }
+
+    #[test]
+    fn print_primitives() {
+        let mut runtime = test_runtime();
+        let stream = stream(&mut runtime);
+
+        assert_eq!(print_text(&runtime, &[stream, Value::Int(1)], format_boolean, false).unwrap(), "true");
+        assert_eq!(print_text(&runtime, &[stream, Value::Int(0)], format_boolean, true).unwrap(), "false\n");
+        assert_eq!(print_text(&runtime, &[stream, Value::Int(0x20AC)], format_char, false).unwrap(), "€");
+        assert_eq!(print_text(&runtime, &[stream, Value::Int(-42)], format_int, true).unwrap(), "-42\n");
+        assert_eq!(print_text(&runtime, &[stream, Value::Long(1 << 40)], format_long, false).unwrap(), "1099511627776");
+    }
+
+    #[test]
+    fn print_strings() {
+        let mut runtime = test_runtime();
+        let stream = stream(&mut runtime);
+        let string = Value::Reference(runtime.new_string("hello").unwrap());
+
+        assert_eq!(print_text(&runtime, &[stream, string], format_string, true).unwrap(), "hello\n");
+        assert_eq!(print_text(&runtime, &[stream, Value::Null], format_string, false).unwrap(), "null");
+    }
+
+    #[test]
+    fn println_without_argument() {
+        let mut runtime = test_runtime();
+        let stream = stream(&mut runtime);
+
+        assert_eq!(print_text(&runtime, &[stream], format_nothing, true).unwrap(), "\n");
+    }
+
+    #[test]
+    fn every_overload_is_native() {
+        for descriptor in ["(Z)V", "(C)V", "(I)V", "(J)V", "(Ljava/lang/String;)V"] {
+            assert!(find_native("java/io/PrintStream", "print", descriptor).is_some());
+            assert!(find_native("java/io/PrintStream", "println", descriptor).is_some());
+        }
+        assert!(find_native("java/io/PrintStream", "println", "()V").is_some());
+    }
+}
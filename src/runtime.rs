@@ -0,0 +1,1343 @@
+use crate::class_file::{is_class_file_name, Annotation, BootstrapMethod, ClassFile, Code, ConstPool, Exceptions,
+                        ACC_ABSTRACT, ACC_ENUM, ACC_FINAL, ACC_INTERFACE, ACC_NATIVE, ACC_PRIVATE, ACC_PROTECTED, ACC_PUBLIC,
+                        ACC_STATIC, ACC_SUPER, ACC_SYNCHRONIZED};
+use crate::descriptor::{FieldType, MethodDescriptor};
+use crate::heap::{Array, Heap, HeapObject, Object, Reference};
+use crate::instruction::{verify_jumps, Instruction};
+#[cfg(feature = "jar")]
+use crate::jar::Jar;
+use anyhow::{anyhow, Context, Error};
+use crate::value::Value;
+use log::{debug, warn};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::fs::File;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Types every array type is assignable to, besides other array types.
+const ARRAY_SUPERTYPES: [&str; 3] = ["java/lang/Object", "java/lang/Cloneable", "java/io/Serializable"];
+
+/// The newest class file major version whose instructions and attributes the runtime implements,
+/// that of Java 8.
+pub const MAX_SUPPORTED_VERSION: u16 = 52;
+
+/// Implements a method of a host class, taking its arguments (with the receiver first for instance
+/// methods) and producing its return value.
+pub type HostMethod = Rc<dyn Fn(&mut Runtime, Vec<Value>) -> Result<Option<Value>, Error>>;
+
+/// Inspects or rewrites a class file before it's loaded, given the class's internal name, as a Java
+/// agent's ClassFileTransformer does. An error fails loading the class.
+pub type ClassTransformer = Box<dyn Fn(&str, &mut ClassFile) -> Result<(), Error>>;
+
+/// A method of a host class, a class defined by the embedder rather than a class file.
+pub struct HostMethodDefinition {
+    pub name: String,
+    pub descriptor: String,
+    pub is_static: bool,
+    pub implementation: HostMethod,
+}
+
+pub struct Runtime {
+    classes: HashMap<String, Rc<RuntimeClass>>,
+    pub heap: Heap,
+    /// Whether to log every executed instruction to `stderr`
+    pub trace: bool,
+    /// Where programs' standard output goes
+    pub stdout: Box<dyn Write>,
+    /// Where programs' standard error and the trace go
+    pub stderr: Box<dyn Write>,
+    /// The system properties `System.getProperty` reads, set with `-D` over a few defaults
+    pub properties: HashMap<String, String>,
+    /// How many times each opcode has run, when counting them for `--count-opcodes`
+    pub opcode_counts: Option<Box<[u64; 256]>>,
+    /// How many frames invoke instructions and `invoke_static` have pushed, which calls to methods
+    /// with nothing to run skip
+    pub frames_pushed: u64,
+    /// Set, from any thread, to stop the running program with an InterruptedException that it
+    /// can't catch. It's checked every so many instructions.
+    pub cancelled: Arc<AtomicBool>,
+    /// How many instructions have run since `cancelled` was last checked, counted across calls to
+    /// `execute` so that a program resuming at an exception handler can't keep restarting the count
+    pub unchecked_instructions: u32,
+    /// Boxes shared by `valueOf`, keyed by box class and value
+    pub box_cache: HashMap<(&'static str, i64), Reference>,
+    /// Whether `assert` statements run, as reported by `Class.desiredAssertionStatus`
+    pub assertions: bool,
+    /// Whether to check the structural constraints on instructions as they're decoded, raising a
+    /// VerifyError for those a malformed class violates
+    pub verify: bool,
+    /// Whether field accesses are checked against the field's access flags, raising an
+    /// IllegalAccessError for a private field of another class and the like. Off by default, as
+    /// Robusta's own classes and natives don't respect them
+    pub strict_access: bool,
+    /// The newest class file major version a class may be loaded from, or `None` to load any version
+    pub max_version: Option<u16>,
+    /// Whether loading a class already loaded from another class path entry is a LinkageError,
+    /// rather than a warning before the later class replaces the earlier
+    pub strict_class_path: bool,
+    /// Where each class was loaded from, such as the jar or class file, keyed by internal name
+    sources: HashMap<String, String>,
+    /// Classes whose initialization has started
+    initialized: HashSet<String>,
+    /// Static field values, keyed by class and field name. Fields not yet assigned are absent.
+    static_fields: HashMap<(String, String), Value>,
+    /// How many times the one thread has entered each object's monitor without exiting it
+    monitors: HashMap<Reference, usize>,
+    /// Objects created by `new` whose constructor hasn't yet been called
+    uninitialized: HashSet<Reference>,
+    /// The canonical string with each value, shared by string literals and `String.intern`
+    interned: HashMap<String, Reference>,
+    /// The `java/lang/Class` object of each type that has needed one, keyed by internal name
+    class_objects: HashMap<String, Reference>,
+    /// The implementations of host class methods, keyed by class, name and descriptor
+    host_methods: HashMap<(String, String, String), HostMethod>,
+    /// The transformers each class file is passed through before it's loaded, in the order added
+    transformers: Vec<ClassTransformer>,
+    /// Names looked up and not found since a class was last loaded, which fail again without a search
+    absent: RefCell<HashSet<String>>,
+    /// How many lookups have searched the loaded classes
+    class_scans: Cell<usize>,
+}
+
+#[derive(Debug)]
+pub struct RuntimeClass {
+    pub access_flags: u16,
+    pub this_class: String,
+    pub super_class: Option<String>,
+    /// Internal names of the interfaces the class directly implements, or an interface extends
+    pub interfaces: Vec<String>,
+    pub const_pool: ConstPool,
+    pub fields: Vec<RuntimeField>,
+    pub methods: Vec<Rc<RuntimeMethod>>,
+    pub bootstrap_methods: Vec<BootstrapMethod>,
+    /// The generic signature from the class's Signature attribute
+    pub(crate) signature: Option<String>,
+    /// The annotations from the class's RuntimeVisibleAnnotations attribute
+    pub(crate) annotations: Vec<Annotation>,
+    /// For a local or anonymous class, the internal name of the class it's declared in and the name
+    /// and descriptor of the method, unless it's declared in an initializer
+    pub(crate) enclosing_method: Option<(String, Option<(String, String)>)>,
+    /// For a record class, the name and descriptor of each of its components
+    pub(crate) record_components: Vec<(String, String)>,
+}
+
+impl RuntimeClass {
+    pub fn is_interface(&self) -> bool {
+        self.access_flags & ACC_INTERFACE != 0
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags & ACC_ABSTRACT != 0
+    }
+
+    pub fn is_enum(&self) -> bool {
+        self.access_flags & ACC_ENUM != 0
+    }
+
+    /// The generic signature the class's Signature attribute gives it, if it has one, such as
+    /// `<T:Ljava/lang/Object;>Ljava/lang/Object;` for a class with a type parameter.
+    pub fn signature(&self) -> Option<&str> {
+        self.signature.as_deref()
+    }
+
+    /// The annotations the class's RuntimeVisibleAnnotations attribute gives it, in the order
+    /// they're written.
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// For a local or anonymous class, the class its EnclosingMethod attribute says it's declared
+    /// in, with the name and descriptor of the method unless it's declared in an initializer.
+    pub fn enclosing_method(&self) -> Option<(&str, Option<(&str, &str)>)> {
+        self.enclosing_method.as_ref().map(|(class, method)| {
+            (class.as_str(), method.as_ref().map(|(name, descriptor)| (name.as_str(), descriptor.as_str())))
+        })
+    }
+
+    /// For a record class, the name and descriptor of each component its Record attribute lists, in
+    /// declaration order.
+    pub fn record_components(&self) -> &[(String, String)] {
+        &self.record_components
+    }
+
+    /// Finds a field declared by this class, ignoring superclasses.
+    pub fn find_field(&self, name: &str) -> Option<&RuntimeField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// The package the class is in, such as `java/lang`, which is empty for the unnamed package.
+    pub fn package(&self) -> &str {
+        self.this_class.rsplit_once('/').map_or("", |(package, _)| package)
+    }
+
+    /// Finds a method declared by this class, ignoring superclasses.
+    pub fn find_method(&self, name: &str, descriptor: &str) -> Option<&Rc<RuntimeMethod>> {
+        self.methods.iter().find(|method| method.name == name && method.descriptor.to_string() == descriptor)
+    }
+}
+
+/// A field a class declares, which its instances or the class itself hold a value of.
+#[derive(Debug, Clone)]
+pub struct RuntimeField {
+    pub access_flags: u16,
+    pub name: String,
+    pub field_type: FieldType,
+    /// The generic signature from the field's Signature attribute
+    pub(crate) signature: Option<String>,
+    /// The annotations from the field's RuntimeVisibleAnnotations attribute
+    pub(crate) annotations: Vec<Annotation>,
+    /// The index in the class's constant pool of the value a static final field's ConstantValue
+    /// attribute gives it
+    pub constant_value_idx: Option<u16>,
+}
+
+impl RuntimeField {
+    pub fn is_public(&self) -> bool {
+        self.access_flags & ACC_PUBLIC != 0
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.access_flags & ACC_PRIVATE != 0
+    }
+
+    pub fn is_protected(&self) -> bool {
+        self.access_flags & ACC_PROTECTED != 0
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    /// The generic signature the field's Signature attribute gives its type, if it has one.
+    pub fn signature(&self) -> Option<&str> {
+        self.signature.as_deref()
+    }
+
+    /// The annotations the field's RuntimeVisibleAnnotations attribute gives it.
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Whether the field is a compile-time constant, a static final field with a ConstantValue
+    /// attribute, whose value is known without initializing its class.
+    pub fn is_constant(&self) -> bool {
+        self.access_flags & (ACC_STATIC | ACC_FINAL) == ACC_STATIC | ACC_FINAL && self.constant_value_idx.is_some()
+    }
+}
+
+impl RuntimeMethod {
+    pub fn is_native(&self) -> bool {
+        self.access_flags & ACC_NATIVE != 0
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.access_flags & ACC_PUBLIC != 0
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.access_flags & ACC_SYNCHRONIZED != 0
+    }
+
+    /// The internal names of the checked exceptions the method's Exceptions attribute declares it
+    /// throws, in the order its `throws` clause lists them.
+    pub fn exceptions(&self) -> &[String] {
+        &self.exceptions
+    }
+
+    /// The generic signature the method's Signature attribute gives it, if it has one.
+    pub fn signature(&self) -> Option<&str> {
+        self.signature.as_deref()
+    }
+
+    /// The annotations the method's RuntimeVisibleAnnotations attribute gives it.
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// The switch or `wide` instruction at `pc`. The method's code is decoded in turn the first time
+    /// one is needed, so that each instruction is read from where the last ended, and those found
+    /// are cached for every later run of them.
+    pub fn instruction_at(&self, pc: usize, verify: bool) -> Result<Rc<Instruction>, Error> {
+        if let Some(instruction) = self.decoded.borrow().get(&pc) {
+            return Ok(instruction.clone());
+        }
+        let mut decoded = self.decoded.borrow_mut();
+        let mut failure = None;
+        for instruction in self.code.iter_instructions(&self.name, verify) {
+            match instruction {
+                Ok((_, Instruction::Other { .. } | Instruction::Branch { .. })) => {}
+                Ok((instruction_pc, instruction)) => {
+                    decoded.insert(instruction_pc, Rc::new(instruction));
+                }
+                Err(error) => failure = Some(error),
+            }
+        }
+        // Decoding stops at the first instruction that fails, which any after it are reported as
+        match (decoded.get(&pc), failure) {
+            (Some(instruction), _) => Ok(instruction.clone()),
+            (None, Some(error)) => Err(error),
+            (None, None) => Err(anyhow!("no switch or wide instruction starts at pc {} in {}", pc, self.name)),
+        }
+    }
+
+    /// Checks that every jump in the code lands on an instruction within it, the first time it's called.
+    pub fn verify_jumps(&self) -> Result<(), Error> {
+        if !self.jumps_verified.get() {
+            verify_jumps(&self.code, &self.name)?;
+            self.jumps_verified.set(true);
+        }
+        Ok(())
+    }
+
+    pub fn max_stack(&self) -> usize {
+        self.code.max_stack as usize
+    }
+
+    pub fn max_locals(&self) -> usize {
+        self.code.max_locals as usize
+    }
+
+    /// The length of the method's bytecode in bytes, 0 for abstract and native methods.
+    pub fn code_len(&self) -> usize {
+        self.code.code.len()
+    }
+
+    /// Whether the method's body is a lone `return`, like `Object.<init>`, so calling it has no effect
+    /// beyond evaluating its arguments.
+    pub fn is_trivial(&self) -> bool {
+        self.code.code == [0xB1]
+    }
+}
+
+/// A Java exception thrown by an instruction, which the interpreter unwinds to the nearest handler
+/// for its class.
+#[derive(Debug)]
+pub struct JavaException(pub Reference);
+
+impl Display for JavaException {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "java exception {:?}", self.0)
+    }
+}
+
+impl std::error::Error for JavaException {}
+
+// Need to think about how we name this
+#[derive(Debug)]
+pub struct RuntimeMethod {
+    pub access_flags: u16,
+    pub name: String,
+    pub descriptor: MethodDescriptor,
+    pub code: Code,
+    /// Internal names of the checked exceptions the method declares it throws
+    pub(crate) exceptions: Vec<String>,
+    /// The generic signature from the method's Signature attribute
+    pub(crate) signature: Option<String>,
+    /// The annotations from the method's RuntimeVisibleAnnotations attribute
+    pub(crate) annotations: Vec<Annotation>,
+    /// The switch and `wide` instructions decoded from the code, keyed by pc, once any has run
+    pub decoded: RefCell<HashMap<usize, Rc<Instruction>>>,
+    /// Whether the code's jumps have been checked to land on its instructions, as done once when
+    /// verifying
+    pub jumps_verified: Cell<bool>,
+}
+
+/// The paths of a directory's entries in name order, rather than whatever order the filesystem
+/// keeps them in, so that loading from and listing a directory go the same way on every run.
+pub(crate) fn sorted_dir_entries(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>, Error>>()?;
+    paths.sort();
+    Ok(paths)
+}
+
+/// The system properties every program sees unless `-D` overrides them.
+fn default_properties() -> HashMap<String, String> {
+    HashMap::from([
+        ("line.separator", "\n"),
+        ("file.separator", std::path::MAIN_SEPARATOR_STR),
+        ("path.separator", if cfg!(windows) { ";" } else { ":" }),
+        ("os.name", std::env::consts::OS),
+        ("java.vendor", "Robusta"),
+    ].map(|(key, value)| (key.to_string(), value.to_string())))
+}
+
+impl Runtime {
+    pub fn new() -> Self {
+        Runtime {
+            classes: HashMap::new(),
+            heap: Heap::new(),
+            trace: false,
+            stdout: Box::new(std::io::stdout()),
+            stderr: Box::new(std::io::stderr()),
+            properties: default_properties(),
+            opcode_counts: None,
+            frames_pushed: 0,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            unchecked_instructions: 0,
+            box_cache: HashMap::new(),
+            assertions: false,
+            verify: false,
+            strict_access: false,
+            max_version: Some(MAX_SUPPORTED_VERSION),
+            strict_class_path: false,
+            sources: HashMap::new(),
+            initialized: HashSet::new(),
+            static_fields: HashMap::new(),
+            monitors: HashMap::new(),
+            uninitialized: HashSet::new(),
+            interned: HashMap::new(),
+            class_objects: HashMap::new(),
+            host_methods: HashMap::new(),
+            transformers: vec![],
+            absent: RefCell::new(HashSet::new()),
+            class_scans: Cell::new(0),
+        }
+    }
+
+    /// Parses a class file, failing on trailing bytes after it when verifying. Without verification
+    /// they're ignored, as some tools pad jar entries.
+    pub fn read_class<R: std::io::Read>(&self, reader: &mut R) -> Result<ClassFile, Error> {
+        if self.verify {
+            ClassFile::read_exactly_from(reader)
+        } else {
+            ClassFile::read_from(reader)
+        }
+    }
+
+    /// Loads every class in a jar, giving the classes loaded. The source names the jar in diagnostics.
+    #[cfg(feature = "jar")]
+    pub fn load_jar<R: std::io::Read + std::io::Seek>(&mut self, jar: &mut Jar<R>, source: &str) -> Result<Vec<Rc<RuntimeClass>>, Error> {
+        let mut classes = vec![];
+        for file_name in jar.class_file_names() {
+            let class_file = jar.class_file(&file_name)
+                .and_then(|mut class_file| self.read_class(&mut class_file))
+                .with_context(|| format!("failed to read {}", file_name))?;
+            classes.push(self.insert_class(class_file, source)?);
+        }
+        Ok(classes)
+    }
+
+    /// Loads every class file in a directory tree laid out by package, as on a class path, giving the
+    /// classes loaded. A `module-info.class`, as in an exploded module, describes the module rather
+    /// than being a class, so is skipped.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<Vec<Rc<RuntimeClass>>, Error> {
+        let mut classes = vec![];
+        for path in sorted_dir_entries(dir)? {
+            if path.is_dir() {
+                classes.extend(self.load_dir(&path)?);
+            } else if is_class_file_name(&path.to_string_lossy()) && !path.ends_with("module-info.class") {
+                let class_file = self.read_class(&mut File::open(&path)?)?;
+                classes.push(self.insert_class(class_file, &path.display().to_string())?);
+            }
+        }
+        Ok(classes)
+    }
+
+    /// Loads a parsed class, from the given source such as a jar or class file path. A class of the
+    /// same name from another source is replaced, with a warning, or with `strict_class_path` is a
+    /// LinkageError naming both sources.
+    pub fn insert_class(&mut self, mut class_file: ClassFile, source: &str) -> Result<Rc<RuntimeClass>, Error> {
+        for transformer in &self.transformers {
+            let class_name = class_file.const_pool.resolve_class_name(class_file.this_class)?.to_string();
+            transformer(&class_name, &mut class_file)
+                .with_context(|| format!("failed to transform class {}", class_name.replace('/', ".")))?;
+        }
+        let class_name = class_file.const_pool.resolve_class_name(class_file.this_class)?;
+        match self.sources.get(class_name) {
+            Some(previous) if previous != source && self.strict_class_path => {
+                Err(anyhow!("java.lang.LinkageError: duplicate class {} in {}, already loaded from {}",
+                    class_name.replace('/', "."), source, previous))?
+            }
+            Some(previous) if previous != source => {
+                warn!("class {} in {} replaces the one loaded from {}", class_name.replace('/', "."), source, previous);
+            }
+            _ => {}
+        }
+        if let Some(max_version) = self.max_version.filter(|max_version| class_file.major_version > *max_version) {
+            Err(anyhow!("java.lang.UnsupportedClassVersionError: {} has been compiled by a more recent version of \
+                the Java Runtime (class file version {}.{}), this VM supports class file versions up to {}.0",
+                class_name.replace('/', "."), class_file.major_version, class_file.minor_version, max_version))?
+        }
+
+        // Object alone has no superclass, and no class may be its own
+        let super_class = if class_file.super_class == 0 {
+            None
+        } else {
+            Some(class_file.const_pool.resolve_class_name(class_file.super_class)?.to_string())
+        };
+        match super_class.as_deref() {
+            None if class_name != "java/lang/Object" =>
+                Err(anyhow!("java.lang.ClassFormatError: Invalid superclass index 0 in class file {}", class_name))?,
+            Some(_) if class_name == "java/lang/Object" =>
+                Err(anyhow!("java.lang.ClassFormatError: Bad superclass name in class file {}", class_name))?,
+            Some(super_class) if super_class == class_name =>
+                Err(anyhow!("java.lang.ClassCircularityError: {}", class_name.replace('/', ".")))?,
+            _ => {}
+        }
+
+        let mut interfaces = Vec::with_capacity(class_file.interfaces.len());
+        for interface in &class_file.interfaces {
+            interfaces.push(class_file.const_pool.resolve_class_name(*interface)?.to_string());
+        }
+
+        let signature = class_file.signature()?.map(String::from);
+        let annotations = class_file.annotations()?;
+        let enclosing = match &class_file.enclosing_method {
+            Some(enclosing_method) => {
+                let method = enclosing_method.method(&class_file.const_pool)?
+                    .map(|(name, descriptor)| (name.to_string(), descriptor.to_string()));
+                Some((enclosing_method.class(&class_file.const_pool)?.to_string(), method))
+            }
+            None => None,
+        };
+        let record_components = class_file.record_components.iter()
+            .map(|component| Ok((component.name(&class_file.const_pool)?.to_string(),
+                component.descriptor(&class_file.const_pool)?.to_string())))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let fields = class_file.fields.iter()
+            .map(|field| {
+                let name = class_file.const_pool.get_utf8(field.name_idx)?.bytes.clone();
+                let descriptor = &class_file.const_pool.get_utf8(field.descriptor_idx)?.bytes;
+                let field_type = FieldType::parse(descriptor).map_err(|error| {
+                    anyhow!("java.lang.ClassFormatError: Field \"{}\" in class {} has illegal signature \"{}\": {}",
+                        name, class_name.replace('/', "."), descriptor, error)
+                })?;
+                Ok(RuntimeField {
+                    access_flags: field.access_flags,
+                    name,
+                    field_type,
+                    signature: field.signature(&class_file.const_pool)?.map(String::from),
+                    annotations: field.annotations(&class_file.const_pool)?,
+                    constant_value_idx: field.constant_value_idx(&class_file.const_pool)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let mut methods = Vec::with_capacity(class_file.methods.len());
+        for method in class_file.methods {
+            let name = class_file.const_pool.get_utf8(method.name_idx)?;
+            let descriptor = &class_file.const_pool.get_utf8(method.descriptor_idx)?.bytes;
+            let descriptor = MethodDescriptor::parse(descriptor).map_err(|error| {
+                anyhow!("java.lang.ClassFormatError: Method \"{}\" in class {} has illegal signature \"{}\": {}",
+                    name.bytes, class_name.replace('/', "."), descriptor, error)
+            })?;
+
+            let mut code_attrs = method.attributes.iter().filter(|attr| {
+                class_file.const_pool.get_utf8(attr.name_idx).ok().map(|name_const| {
+                    name_const.bytes.eq("Code")
+                }).unwrap_or_else(|| false)
+            });
+            let code_attr = code_attrs.next();
+            if code_attrs.next().is_some() {
+                Err(anyhow!("java.lang.ClassFormatError: Multiple Code attributes in method \"{}\" in class {}",
+                    name.bytes, class_name.replace('/', ".")))?
+            }
+
+            let code = match code_attr {
+                Some(code_attr) => {
+                    let mut reader = Cursor::new(&code_attr.info);
+                    Code::read_within(&mut reader, &format!("the Code attribute of method {} in class {}",
+                        name.bytes, class_name.replace('/', ".")))?
+                }
+                // Abstract and native methods have no bytecode to run
+                None if method.access_flags & (ACC_ABSTRACT | ACC_NATIVE) != 0 => Code {
+                    max_stack: 0,
+                    max_locals: 0,
+                    code: vec![],
+                    exception_table: vec![],
+                },
+                None => Err(anyhow!("java.lang.ClassFormatError: Absent Code attribute in method \"{}\" in class {}",
+                    name.bytes, class_name.replace('/', ".")))?,
+            };
+
+            let exceptions_attr = method.attributes.iter().find(|attr| {
+                class_file.const_pool.get_utf8(attr.name_idx).is_ok_and(|name_const| name_const.bytes.eq("Exceptions"))
+            });
+            let mut exceptions = vec![];
+            if let Some(exceptions_attr) = exceptions_attr {
+                let mut reader = Cursor::new(&exceptions_attr.info);
+                let within = format!("the Exceptions attribute of method {} in class {}", name.bytes, class_name.replace('/', "."));
+                for class_idx in Exceptions::read_within(&mut reader, &within)?.exception_idxs {
+                    exceptions.push(class_file.const_pool.resolve_class_name(class_idx)?.to_string());
+                }
+            }
+
+            methods.push(Rc::new(RuntimeMethod {
+                access_flags: method.access_flags,
+                name: name.bytes.clone(),
+                descriptor,
+                code,
+                exceptions,
+                signature: method.signature(&class_file.const_pool)?.map(String::from),
+                annotations: method.annotations(&class_file.const_pool)?,
+                decoded: RefCell::default(),
+                jumps_verified: Cell::default(),
+            }));
+        }
+
+        let class = Rc::new(RuntimeClass {
+            access_flags: class_file.access_flags,
+            this_class: class_name.to_string(),
+            super_class,
+            interfaces,
+            const_pool: class_file.const_pool,
+            fields,
+            methods,
+            bootstrap_methods: class_file.bootstrap_methods,
+            signature,
+            annotations,
+            enclosing_method: enclosing,
+            record_components,
+        });
+
+        debug!("loaded class {}", class.this_class.replace('/', "."));
+        self.classes.insert(class.this_class.clone(), class.clone());
+        self.absent.borrow_mut().clear();
+        self.sources.insert(class.this_class.clone(), source.to_string());
+
+        Ok(class)
+    }
+
+    /// Adds a transformer for the class files loaded from now on, after those already added.
+    pub fn add_transformer(&mut self, transformer: ClassTransformer) {
+        self.transformers.push(transformer);
+    }
+
+    /// Defines a class whose methods are all native, implemented by the host, along with a
+    /// constructor that does nothing so that instances can be created. It replaces any class of the
+    /// same name.
+    pub fn define_host_class(&mut self, name: &str, methods: Vec<HostMethodDefinition>) -> Result<Rc<RuntimeClass>, Error> {
+        let mut runtime_methods = vec![];
+        let mut has_constructor = false;
+        for method in methods {
+            let descriptor = MethodDescriptor::parse(&method.descriptor)?;
+            has_constructor |= method.name == "<init>" && descriptor.parameters.is_empty();
+            let static_flag = if method.is_static { ACC_STATIC } else { 0 };
+            runtime_methods.push(Rc::new(RuntimeMethod {
+                access_flags: ACC_PUBLIC | ACC_NATIVE | static_flag,
+                name: method.name.clone(),
+                descriptor,
+                code: Code { max_stack: 0, max_locals: 0, code: vec![], exception_table: vec![] },
+                exceptions: vec![],
+                signature: None,
+                annotations: vec![],
+                decoded: RefCell::default(),
+                jumps_verified: Cell::default(),
+            }));
+            self.host_methods.insert((name.to_string(), method.name, method.descriptor), method.implementation);
+        }
+        if !has_constructor {
+            runtime_methods.push(Rc::new(RuntimeMethod {
+                access_flags: ACC_PUBLIC | ACC_NATIVE,
+                name: "<init>".to_string(),
+                descriptor: MethodDescriptor::parse("()V")?,
+                code: Code { max_stack: 0, max_locals: 0, code: vec![], exception_table: vec![] },
+                exceptions: vec![],
+                signature: None,
+                annotations: vec![],
+                decoded: RefCell::default(),
+                jumps_verified: Cell::default(),
+            }));
+            self.host_methods.insert((name.to_string(), "<init>".to_string(), "()V".to_string()), Rc::new(|_, _| Ok(None)));
+        }
+
+        let class = Rc::new(RuntimeClass {
+            access_flags: ACC_PUBLIC | ACC_SUPER,
+            this_class: name.to_string(),
+            super_class: Some("java/lang/Object".to_string()),
+            interfaces: vec![],
+            const_pool: ConstPool::default(),
+            fields: vec![],
+            methods: runtime_methods,
+            bootstrap_methods: vec![],
+            signature: None,
+            annotations: vec![],
+            enclosing_method: None,
+            record_components: vec![],
+        });
+        debug!("defined host class {}", name.replace('/', "."));
+        self.classes.insert(name.to_string(), class.clone());
+        self.absent.borrow_mut().clear();
+        Ok(class)
+    }
+
+    /// The implementation of a method of a host class.
+    pub fn host_method(&self, class_name: &str, name: &str, descriptor: &str) -> Option<HostMethod> {
+        self.host_methods.get(&(class_name.to_string(), name.to_string(), descriptor.to_string())).cloned()
+    }
+
+    /// Looks up a loaded class. A class found missing is remembered as absent, so looking it up
+    /// again, as probing for optional classes does, fails without searching the loaded classes
+    /// until another class is loaded.
+    pub fn get_class(&self, name: &str) -> Result<&Rc<RuntimeClass>, Error> {
+        let unknown = || anyhow!("unknown class {}", name.replace('/', "."));
+        if !self.absent.borrow().is_empty() && self.absent.borrow().contains(name) {
+            return Err(unknown());
+        }
+        self.class_scans.set(self.class_scans.get() + 1);
+        self.classes.get(name).ok_or_else(|| {
+            self.absent.borrow_mut().insert(name.to_string());
+            unknown()
+        })
+    }
+
+    /// How many lookups have searched the loaded classes, rather than failing straight away for a
+    /// class already known to be absent.
+    pub fn class_scans(&self) -> usize {
+        self.class_scans.get()
+    }
+
+    /// The storage of a new instance of the class: the default value of each instance field it
+    /// declares or inherits, keyed by name. A superclass's field hidden by a subclass's field of the
+    /// same name is keyed by its declaring class too, as [`Runtime::field_key`] gives.
+    pub fn instance_fields(&self, class: &RuntimeClass) -> Result<HashMap<String, Value>, Error> {
+        let mut fields = HashMap::new();
+        let mut class = class;
+        loop {
+            for field in class.fields.iter().filter(|field| !field.is_static()) {
+                let key = if fields.contains_key(&field.name) {
+                    format!("{}.{}", class.this_class, field.name)
+                } else {
+                    field.name.clone()
+                };
+                fields.insert(key, field.field_type.default_value());
+            }
+            match &class.super_class {
+                Some(super_class) => class = self.get_class(super_class)?,
+                None => return Ok(fields),
+            }
+        }
+    }
+
+    /// The key of an instance field in the storage of an object of the given class, for the field
+    /// a reference names by the referenced class and the field's name. That's the name, unless a
+    /// subclass of the class declaring the field hides it with a field of its own.
+    pub fn field_key(&self, object_class: &RuntimeClass, class_name: &str, name: &str) -> Result<String, Error> {
+        let mut owner = self.get_class(class_name)?;
+        while owner.find_field(name).is_none() {
+            match &owner.super_class {
+                Some(super_class) => owner = self.get_class(super_class)?,
+                None => return Ok(name.to_string()),
+            }
+        }
+        let mut class = object_class;
+        while class.this_class != owner.this_class {
+            if class.find_field(name).is_some_and(|field| !field.is_static()) {
+                return Ok(format!("{}.{}", owner.this_class, name));
+            }
+            match &class.super_class {
+                Some(super_class) => class = self.get_class(super_class)?,
+                None => break,
+            }
+        }
+        Ok(name.to_string())
+    }
+
+    /// Finds the class declaring a field, searching superclasses from the named class up, or `None`
+    /// for a field no class declares, such as those the runtime sets on its own objects.
+    pub fn resolve_field(&self, class_name: &str, name: &str) -> Result<Option<(Rc<RuntimeClass>, RuntimeField)>, Error> {
+        let mut class = self.get_class(class_name)?;
+        loop {
+            if let Some(field) = class.find_field(name) {
+                return Ok(Some((class.clone(), field.clone())));
+            }
+            match &class.super_class {
+                Some(super_class) => class = self.get_class(super_class)?,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Resolves a method as named by a method reference, searching the class and then its superclasses.
+    pub fn resolve_method(&self, class_name: &str, name: &str, descriptor: &str)
+                          -> Result<(Rc<RuntimeClass>, Rc<RuntimeMethod>), Error> {
+        let mut class = self.get_class(class_name)?;
+        loop {
+            if let Some(method) = class.find_method(name, descriptor) {
+                return Ok((class.clone(), method.clone()));
+            }
+            match &class.super_class {
+                Some(super_class) => class = self.get_class(super_class)?,
+                None => Err(anyhow!("java.lang.NoSuchMethodError: {}.{}{}",
+                    class_name.replace('/', "."), name, descriptor))?,
+            }
+        }
+    }
+
+    /// Marks a class as being initialized, returning false if its initialization has already started.
+    pub fn begin_initialization(&mut self, class_name: &str) -> bool {
+        self.initialized.insert(class_name.to_string())
+    }
+
+    /// The number of classes whose initialization has started.
+    pub fn initialized_count(&self) -> usize {
+        self.initialized.len()
+    }
+
+    pub fn get_static(&self, class_name: &str, name: &str) -> Option<Value> {
+        self.static_fields.get(&(class_name.to_string(), name.to_string())).copied()
+    }
+
+    pub fn put_static(&mut self, class_name: &str, name: &str, value: Value) {
+        self.static_fields.insert((class_name.to_string(), name.to_string()), value);
+    }
+
+    pub fn enter_monitor(&mut self, object: Reference) {
+        *self.monitors.entry(object).or_insert(0) += 1;
+    }
+
+    /// Exits an object's monitor once, returning false if it wasn't held.
+    pub fn exit_monitor(&mut self, object: Reference) -> bool {
+        match self.monitors.get_mut(&object) {
+            Some(count) if *count > 1 => *count -= 1,
+            Some(_) => {
+                self.monitors.remove(&object);
+            }
+            None => return false,
+        }
+        true
+    }
+
+    pub fn holds_monitor(&self, object: Reference) -> bool {
+        self.monitors.contains_key(&object)
+    }
+
+    /// Marks a new object as unusable until its constructor is called.
+    pub fn mark_uninitialized(&mut self, object: Reference) {
+        self.uninitialized.insert(object);
+    }
+
+    /// Marks an object as constructed once its constructor is called.
+    pub fn mark_initialized(&mut self, object: Reference) {
+        self.uninitialized.remove(&object);
+    }
+
+    /// Whether an object was created by `new` and its constructor hasn't been called yet.
+    pub fn is_uninitialized(&self, object: Reference) -> bool {
+        self.uninitialized.contains(&object)
+    }
+
+    /// Allocates an exception of the given class, returning an error which unwinds to the exception's
+    /// handler when propagated out of an instruction.
+    pub fn throw(&mut self, class_name: &str, message: &str) -> Error {
+        self.throw_exception(class_name, Some(message))
+    }
+
+    /// Throws an exception with no message, as created by its no-argument constructor.
+    pub fn throw_without_message(&mut self, class_name: &str) -> Error {
+        self.throw_exception(class_name, None)
+    }
+
+    fn throw_exception(&mut self, class_name: &str, message: Option<&str>) -> Error {
+        match self.new_exception(class_name, message) {
+            Ok(exception) => Error::new(JavaException(exception)),
+            Err(error) => error,
+        }
+    }
+
+    fn new_exception(&mut self, class_name: &str, message: Option<&str>) -> Result<Reference, Error> {
+        let class = self.get_class(class_name)?.clone();
+        let message = match message {
+            Some(message) => Value::Reference(self.new_string(message)?),
+            None => Value::Null,
+        };
+        self.heap.allocate(HeapObject::Object(Object {
+            class,
+            fields: HashMap::from([("detailMessage".to_string(), message)]),
+        }))
+    }
+
+    /// Describes an exception as the JVM reports an uncaught one: its class name, followed by its
+    /// message if it has one.
+    pub fn describe_exception(&self, exception: Reference) -> Result<String, Error> {
+        let HeapObject::Object(object) = self.heap.get(exception)? else {
+            Err(anyhow!("exception {:?} is not an object", exception))?
+        };
+        let class_name = object.class.this_class.replace('/', ".");
+        match object.fields.get("detailMessage").copied().unwrap_or(Value::Null).as_reference()? {
+            Some(message) => Ok(format!("{}: {}", class_name, self.read_string(message)?)),
+            None => Ok(class_name),
+        }
+    }
+
+    /// The Class object representing a class or array type, created the first time it's needed so
+    /// that each type has exactly one.
+    pub fn class_object(&mut self, name: &str) -> Result<Reference, Error> {
+        if let Some(class_object) = self.class_objects.get(name) {
+            return Ok(*class_object);
+        }
+        let class = self.get_class("java/lang/Class")?.clone();
+        let class_name = self.new_string(&name.replace('/', "."))?;
+        let class_object = self.heap.allocate(HeapObject::Object(Object {
+            class,
+            fields: HashMap::from([("name".to_string(), Value::Reference(class_name))]),
+        }))?;
+        self.class_objects.insert(name.to_string(), class_object);
+        Ok(class_object)
+    }
+
+    pub fn new_string(&mut self, value: &str) -> Result<Reference, Error> {
+        let class = self.get_class("java/lang/String")?.clone();
+        let chars = self.heap.allocate(HeapObject::Array(Array::Char(value.encode_utf16().collect())))?;
+        self.heap.allocate(HeapObject::Object(Object {
+            class,
+            fields: HashMap::from([("value".to_string(), Value::Reference(chars))]),
+        }))
+    }
+
+    /// Creates a String[] of new strings with the given values, such as the arguments passed to main.
+    pub fn new_string_array(&mut self, values: &[String]) -> Result<Reference, Error> {
+        let mut array = Array::new("[Ljava/lang/String;".to_string(), values.len());
+        for (idx, value) in values.iter().enumerate() {
+            array.store(idx, Value::Reference(self.new_string(value)?))?;
+        }
+        self.heap.allocate(HeapObject::Array(array))
+    }
+
+    /// Gives the canonical string with the given value, creating it the first time it's asked for.
+    pub fn intern(&mut self, value: &str) -> Result<Reference, Error> {
+        if let Some(string) = self.interned.get(value) {
+            return Ok(*string);
+        }
+        let string = self.new_string(value)?;
+        self.interned.insert(value.to_string(), string);
+        Ok(string)
+    }
+
+    pub fn read_string(&self, string: Reference) -> Result<String, Error> {
+        let HeapObject::Object(object) = self.heap.get(string)? else {
+            Err(anyhow!("string {:?} is not an object", string))?
+        };
+        let chars = object.fields.get("value").copied().unwrap_or(Value::Null).as_reference()?
+            .ok_or_else(|| anyhow!("string {:?} has no value", string))?;
+        let HeapObject::Array(Array::Char(chars)) = self.heap.get(chars)? else {
+            Err(anyhow!("string {:?} value is not a char array", string))?
+        };
+        Ok(String::from_utf16(chars)?)
+    }
+
+    /// Whether a value of type `from` can be assigned to type `to`, per the rules of checkcast and
+    /// instanceof. Both types are internal class names (`java/lang/String`) or array descriptors
+    /// (`[I`, `[Ljava/lang/Object;`).
+    pub fn is_assignable(&self, from: &str, to: &str) -> Result<bool, Error> {
+        if from == to {
+            return Ok(true);
+        }
+
+        match (from.strip_prefix('['), to.strip_prefix('[')) {
+            (Some(from_component), Some(to_component)) => {
+                match (component_class(from_component), component_class(to_component)) {
+                    (Some(from_class), Some(to_class)) => self.is_assignable(from_class, to_class),
+                    // Primitive components are only assignable to themselves, handled by the equality check
+                    _ => Ok(false),
+                }
+            }
+            (Some(_), None) => Ok(ARRAY_SUPERTYPES.contains(&to)),
+            (None, Some(_)) => Ok(false),
+            (None, None) => Ok(self.is_subclass(from, to)? || self.all_interfaces(from)?.contains(to)),
+        }
+    }
+
+    pub fn is_subclass(&self, from: &str, to: &str) -> Result<bool, Error> {
+        let mut class = self.get_class(from)?;
+        loop {
+            if class.this_class == to {
+                return Ok(true);
+            }
+            match &class.super_class {
+                Some(super_class) => class = self.get_class(super_class)?,
+                None => return Ok(false),
+            }
+        }
+    }
+
+    /// Every interface a class implements, or an interface extends: those it names itself, those its
+    /// superclasses name, and the interfaces those extend in turn.
+    pub fn all_interfaces(&self, class_name: &str) -> Result<HashSet<String>, Error> {
+        let mut interfaces = HashSet::new();
+        let mut pending = vec![class_name];
+        while let Some(class_name) = pending.pop() {
+            let class = self.get_class(class_name)?;
+            for interface in &class.interfaces {
+                if interfaces.insert(interface.clone()) {
+                    pending.push(interface);
+                }
+            }
+            pending.extend(class.super_class.as_deref());
+        }
+        Ok(interfaces)
+    }
+}
+
+/// The class name of a reference-typed array component descriptor, or `None` for a primitive.
+/// Nested array components are themselves array types, so are returned whole.
+fn component_class(component: &str) -> Option<&str> {
+    if component.starts_with('[') {
+        Some(component)
+    } else {
+        component.strip_prefix('L').and_then(|name| name.strip_suffix(';'))
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+pub mod test_runtime {
+    use super::*;
+
+    /// A runtime with every jar from the data directory loaded.
+    pub fn test_runtime() -> Runtime {
+        let mut runtime = Runtime::new();
+        let jar_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("data");
+        for path in fs::read_dir(jar_dir).unwrap() {
+            let path = path.unwrap().path();
+            runtime.load_jar(&mut Jar::open(&path).unwrap(), &path.display().to_string()).unwrap();
+        }
+        runtime
+    }
+}
+
+#[cfg(test)]
+mod logging_tests {
+    use super::*;
+    use log::{Level, Log, Metadata, Record};
+    use std::sync::Mutex;
+
+    /// Records every log message, from whichever test logs it.
+    struct CapturingLogger(Mutex<Vec<(Level, String)>>);
+
+    impl Log for CapturingLogger {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            self.0.lock().unwrap().push((record.level(), record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(vec![]));
+
+    #[test]
+    fn loading_class_is_logged() {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+        let mut runtime = Runtime::new();
+        let class_file = ClassFile::read_from(&mut Cursor::new(minimal_class("com/example/Logged"))).unwrap();
+
+        runtime.insert_class(class_file, "Logged.class").unwrap();
+
+        assert!(LOGGER.0.lock().unwrap().contains(&(Level::Debug, "loaded class com.example.Logged".to_string())));
+    }
+
+    /// A class with no members, whose only superclass is Object.
+    pub(super) fn minimal_class(name: &str) -> Vec<u8> {
+        let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x34, 0x00, 0x05];
+        for utf8 in [name, "java/lang/Object"] {
+            bytes.push(0x01);
+            bytes.extend((utf8.len() as u16).to_be_bytes());
+            bytes.extend(utf8.bytes());
+        }
+        bytes.extend([0x07, 0x00, 0x01, 0x07, 0x00, 0x02]);
+        bytes.extend([0x00, 0x21, 0x00, 0x03, 0x00, 0x04]);
+        bytes.extend([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod absent_class_tests {
+    use super::logging_tests::minimal_class;
+    use super::*;
+
+    #[test]
+    fn missing_class_found_absent_once() {
+        let mut runtime = Runtime::new();
+
+        assert!(runtime.get_class("com/example/Optional").is_err());
+        let scans = runtime.class_scans();
+        assert_eq!(runtime.get_class("com/example/Optional").unwrap_err().to_string(), "unknown class com.example.Optional");
+        assert_eq!(runtime.class_scans(), scans);
+
+        let class_file = ClassFile::read_from(&mut Cursor::new(minimal_class("com/example/Optional"))).unwrap();
+        runtime.insert_class(class_file, "Optional.class").unwrap();
+        assert_eq!(runtime.get_class("com/example/Optional").unwrap().this_class, "com/example/Optional");
+        assert_eq!(runtime.class_scans(), scans + 1);
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod is_assignable_tests {
+    use super::test_runtime::test_runtime;
+    use std::collections::HashSet;
+
+    #[test]
+    fn all_interfaces() {
+        let runtime = test_runtime();
+
+        let interfaces = runtime.all_interfaces("com/jkitch/robusta/test/Interfaces$Box").unwrap();
+
+        let expected = ["com/jkitch/robusta/test/Interfaces$Sized", "java/lang/Comparable"];
+        assert_eq!(interfaces, HashSet::from(expected.map(String::from)));
+    }
+
+    #[test]
+    fn class_to_superclass() {
+        let runtime = test_runtime();
+
+        assert!(runtime.is_assignable("java/lang/String", "java/lang/Object").unwrap());
+        assert!(!runtime.is_assignable("java/lang/Object", "java/lang/String").unwrap());
+    }
+
+    #[test]
+    fn reference_array_covariance() {
+        let runtime = test_runtime();
+
+        assert!(runtime.is_assignable("[Ljava/lang/String;", "[Ljava/lang/Object;").unwrap());
+        assert!(!runtime.is_assignable("[Ljava/lang/Object;", "[Ljava/lang/String;").unwrap());
+    }
+
+    #[test]
+    fn primitive_arrays() {
+        let runtime = test_runtime();
+
+        assert!(runtime.is_assignable("[I", "[I").unwrap());
+        assert!(!runtime.is_assignable("[I", "[J").unwrap());
+        assert!(!runtime.is_assignable("[I", "[Ljava/lang/Object;").unwrap());
+    }
+
+    #[test]
+    fn array_to_object_and_interfaces() {
+        let runtime = test_runtime();
+
+        assert!(runtime.is_assignable("[I", "java/lang/Object").unwrap());
+        assert!(runtime.is_assignable("[Ljava/lang/String;", "java/lang/Cloneable").unwrap());
+        assert!(runtime.is_assignable("[I", "java/io/Serializable").unwrap());
+        assert!(!runtime.is_assignable("[I", "java/lang/String").unwrap());
+    }
+
+    #[test]
+    fn nested_arrays() {
+        let runtime = test_runtime();
+
+        assert!(runtime.is_assignable("[[I", "[Ljava/lang/Object;").unwrap());
+        assert!(runtime.is_assignable("[[Ljava/lang/String;", "[[Ljava/lang/Object;").unwrap());
+        assert!(!runtime.is_assignable("[[I", "[[J").unwrap());
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod class_flags_tests {
+    use super::test_runtime::test_runtime;
+
+    #[test]
+    fn class_flags() {
+        let runtime = test_runtime();
+
+        let color = runtime.get_class("com/jkitch/robusta/test/Enums$Color").unwrap();
+        assert!(color.is_enum() && !color.is_interface());
+        let comparable = runtime.get_class("java/lang/Comparable").unwrap();
+        assert!(comparable.is_interface() && comparable.is_abstract() && !comparable.is_enum());
+        let object = runtime.get_class("java/lang/Object").unwrap();
+        assert!(!object.is_interface() && !object.is_abstract() && !object.is_enum());
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod code_tests {
+    use super::test_runtime::test_runtime;
+
+    #[test]
+    fn code_dimensions() {
+        let runtime = test_runtime();
+        let class = runtime.get_class("com/jkitch/robusta/test/Calls").unwrap();
+
+        // iload_0, iload_1, iadd, ireturn
+        let add = class.find_method("add", "(II)I").unwrap();
+        assert_eq!((add.max_stack(), add.max_locals(), add.code_len()), (2, 2, 4));
+    }
+
+    #[test]
+    fn native_code_dimensions() {
+        let runtime = test_runtime();
+        let class = runtime.get_class("java/lang/Object").unwrap();
+
+        let clone = class.find_method("clone", "()Ljava/lang/Object;").unwrap();
+        assert_eq!((clone.max_stack(), clone.max_locals(), clone.code_len()), (0, 0, 0));
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod exceptions_tests {
+    use super::test_runtime::test_runtime;
+
+    #[test]
+    fn declared_exceptions() {
+        let runtime = test_runtime();
+
+        let class = runtime.get_class("com/jkitch/robusta/test/Throws").unwrap();
+
+        assert_eq!(class.find_method("readNothing", "()V").unwrap().exceptions(), ["java/io/IOException"]);
+        assert!(class.find_method("throwsNothing", "()V").unwrap().exceptions().is_empty());
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod signature_tests {
+    use super::test_runtime::test_runtime;
+    use crate::class_file::ClassFile;
+    use crate::jar::Jar;
+    use std::path::Path;
+
+    #[test]
+    fn class_and_method_signatures() {
+        let runtime = test_runtime();
+
+        let class = runtime.get_class("com/jkitch/robusta/test/Generics").unwrap();
+
+        assert_eq!(class.signature(), Some("<T::Ljava/lang/Comparable<TT;>;>Ljava/lang/Object;"));
+        let identity = class.find_method("identity", "(Ljava/lang/Object;)Ljava/lang/Object;").unwrap();
+        assert_eq!(identity.signature(), Some("<E:Ljava/lang/Object;>(TE;)TE;"));
+        assert_eq!(class.find_method("plain", "(I)I").unwrap().signature(), None);
+        assert_eq!(runtime.get_class("java/lang/Object").unwrap().signature(), None);
+    }
+
+    #[test]
+    fn field_signatures() {
+        let runtime = test_runtime();
+
+        let class = runtime.get_class("com/jkitch/robusta/test/Generics").unwrap();
+
+        assert_eq!(class.find_field("value").unwrap().signature(), Some("TT;"));
+        assert_eq!(class.find_field("count").unwrap().signature(), None);
+    }
+
+    #[test]
+    fn field_signature_attributes() {
+        let mut jar = Jar::open(Path::new("data/robusta-java-tests-0.1.0.jar")).unwrap();
+        let class_file = ClassFile::read_from(&mut jar.class_file("com/jkitch/robusta/test/Generics.class").unwrap()).unwrap();
+
+        let signatures = class_file.fields.iter()
+            .map(|field| {
+                let name = &class_file.const_pool.get_utf8(field.name_idx).unwrap().bytes;
+                (name.as_str(), field.signature(&class_file.const_pool).unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(signatures, vec![("value", Some("TT;")), ("count", None)]);
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod annotation_tests {
+    use super::test_runtime::test_runtime;
+    use crate::class_file::{Annotation, ElementValue};
+
+    fn type_names(annotations: &[Annotation]) -> Vec<&str> {
+        annotations.iter().map(|annotation| annotation.type_name.as_str()).collect()
+    }
+
+    #[test]
+    fn class_annotations() {
+        let runtime = test_runtime();
+
+        let class = runtime.get_class("com/jkitch/robusta/test/Annotated").unwrap();
+
+        assert_eq!(type_names(class.annotations()), vec!["com/jkitch/robusta/test/Marker", "com/jkitch/robusta/test/Tag"]);
+        assert_eq!(class.annotations()[0].element_value_pairs, vec![]);
+        assert_eq!(class.annotations()[1].element("value"), Some(&ElementValue::String("example".to_string())));
+        assert_eq!(class.annotations()[1].element("priority"), None);
+    }
+
+    #[test]
+    fn field_and_method_annotations() {
+        let runtime = test_runtime();
+
+        let class = runtime.get_class("com/jkitch/robusta/test/Annotated").unwrap();
+
+        let tag = &class.find_field("count").unwrap().annotations()[0];
+        assert_eq!(tag.element_value_pairs, vec![
+            ("value".to_string(), ElementValue::String("field".to_string())),
+            ("priority".to_string(), ElementValue::Int(2)),
+        ]);
+        // SuppressWarnings is only retained in the source
+        let run = class.find_method("run", "()V").unwrap();
+        assert_eq!(type_names(run.annotations()), vec!["com/jkitch/robusta/test/Marker", "java/lang/Deprecated"]);
+        assert_eq!(class.find_method("plain", "()V").unwrap().annotations(), []);
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod enclosing_method_tests {
+    use super::test_runtime::test_runtime;
+    use super::RuntimeClass;
+    use std::rc::Rc;
+
+    const CLASS: &str = "com/jkitch/robusta/test/Anonymous";
+
+    fn class(name: &str) -> Rc<RuntimeClass> {
+        test_runtime().get_class(name).unwrap().clone()
+    }
+
+    #[test]
+    fn anonymous_class_in_method() {
+        assert_eq!(class("com/jkitch/robusta/test/Anonymous$2").enclosing_method(),
+            Some((CLASS, Some(("inMethod", "()Ljava/lang/Object;")))));
+    }
+
+    #[test]
+    fn local_class() {
+        assert_eq!(class("com/jkitch/robusta/test/Anonymous$1Local").enclosing_method(),
+            Some((CLASS, Some(("local", "(I)Ljava/lang/Object;")))));
+    }
+
+    #[test]
+    fn anonymous_class_in_initializer() {
+        assert_eq!(class("com/jkitch/robusta/test/Anonymous$1").enclosing_method(), Some((CLASS, None)));
+        assert_eq!(class(CLASS).enclosing_method(), None);
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod string_tests {
+    use super::test_runtime::test_runtime;
+
+    #[test]
+    fn string_round_trip() {
+        let mut runtime = test_runtime();
+
+        let string = runtime.new_string("hello wörld").unwrap();
+
+        assert_eq!(runtime.read_string(string).unwrap(), "hello wörld");
+    }
+
+    #[test]
+    fn one_class_object_per_type() {
+        let mut runtime = test_runtime();
+
+        let first = runtime.class_object("java/lang/String").unwrap();
+
+        assert_eq!(runtime.class_object("java/lang/String").unwrap(), first);
+        assert_ne!(runtime.class_object("[Ljava/lang/String;").unwrap(), first);
+    }
+
+    #[test]
+    fn describe_exception() {
+        let mut runtime = test_runtime();
+
+        let exception = runtime.new_exception("java/lang/NullPointerException", Some("oops")).unwrap();
+
+        assert_eq!(runtime.describe_exception(exception).unwrap(), "java.lang.NullPointerException: oops");
+    }
+}
@@ -0,0 +1,110 @@
+//! The `StringConcatFactory` bootstrap methods that javac compiles string concatenation to since
+//! Java 9, implemented by the interpreter itself rather than through method handles.
+
+use crate::class_file::{BootstrapMethod, Const, ConstPool};
+use crate::descriptor::{FieldType, MethodDescriptor};
+use crate::heap::Reference;
+use crate::runtime::Runtime;
+use crate::value::{java_floating_string, Value};
+use anyhow::{anyhow, Error};
+
+pub const STRING_CONCAT_FACTORY: &str = "java/lang/invoke/StringConcatFactory";
+
+/// Runs a string concatenation call site bootstrapped by the named `StringConcatFactory` method,
+/// giving a new String of its arguments, typed by the call site's descriptor, and any constants in
+/// the order its recipe gives.
+pub fn string_concat(runtime: &mut Runtime, const_pool: &ConstPool, bootstrap_name: &str,
+                     bootstrap_method: &BootstrapMethod, descriptor: &MethodDescriptor, args: Vec<Value>)
+                     -> Result<Reference, Error> {
+    let (recipe, constants) = match bootstrap_name {
+        // Arguments only, with no constants in between
+        "makeConcat" => ("\u{1}".repeat(args.len()), vec![]),
+        "makeConcatWithConstants" => {
+            let (recipe_idx, constant_idxs) = bootstrap_method.argument_idxs.split_first()
+                .ok_or_else(|| anyhow!("makeConcatWithConstants call site has no recipe"))?;
+            let recipe = constant_string(const_pool, *recipe_idx)?;
+            let constants = constant_idxs.iter()
+                .map(|idx| constant_string(const_pool, *idx))
+                .collect::<Result<Vec<_>, _>>()?;
+            (recipe, constants)
+        }
+        _ => Err(anyhow!("java.lang.BootstrapMethodError: unsupported bootstrap method {}.{}",
+            STRING_CONCAT_FACTORY.replace('/', "."), bootstrap_name))?,
+    };
+    let args = descriptor.parameters.iter().zip(args)
+        .map(|(parameter, arg)| java_string(runtime, parameter, arg))
+        .collect::<Result<Vec<_>, _>>()?;
+    let result = concat(&recipe, &constants, &args)?;
+    runtime.new_string(&result)
+}
+
+/// Fills in a recipe, which has `\u{1}` for each argument and `\u{2}` for each constant.
+fn concat(recipe: &str, constants: &[String], args: &[String]) -> Result<String, Error> {
+    let mut args = args.iter();
+    let mut constants = constants.iter();
+    let mut result = String::with_capacity(recipe.len());
+    for c in recipe.chars() {
+        match c {
+            '\u{1}' => result.push_str(args.next()
+                .ok_or_else(|| anyhow!("string concatenation recipe {:?} has more arguments than given", recipe))?),
+            '\u{2}' => result.push_str(constants.next()
+                .ok_or_else(|| anyhow!("string concatenation recipe {:?} has more constants than given", recipe))?),
+            _ => result.push(c),
+        }
+    }
+    Ok(result)
+}
+
+/// The text of a constant passed to a bootstrap method.
+fn constant_string(const_pool: &ConstPool, idx: u16) -> Result<String, Error> {
+    match const_pool.get_const(idx)? {
+        Const::String(string) => Ok(const_pool.get_utf8(string.string_idx)?.bytes.clone()),
+        Const::Integer(value) => Ok(value.to_string()),
+        Const::Long(value) => Ok(value.to_string()),
+        Const::Float(value) => Ok(java_floating_string(*value)),
+        Const::Double(value) => Ok(java_floating_string(*value)),
+        const_item => Err(anyhow!("unsupported string concatenation constant {:?}", const_item)),
+    }
+}
+
+/// Converts an argument of the given type to a string as `String.valueOf` would.
+fn java_string(runtime: &Runtime, field_type: &FieldType, value: Value) -> Result<String, Error> {
+    Ok(match (field_type, value) {
+        (FieldType::Boolean, Value::Int(value)) => (value != 0).to_string(),
+        (FieldType::Char, Value::Int(value)) => char::decode_utf16([value as u16])
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect(),
+        (_, Value::Int(value)) => value.to_string(),
+        (_, Value::Long(value)) => value.to_string(),
+        (_, Value::Float(value)) => java_floating_string(value),
+        (_, Value::Double(value)) => java_floating_string(value),
+        (_, Value::Null) => "null".to_string(),
+        (_, Value::Reference(reference)) => {
+            let type_name = runtime.heap.get(reference)?.type_name();
+            if type_name != "java/lang/String" {
+                // Other objects would need their toString method called
+                Err(anyhow!("string concatenation of {} isn't supported", type_name.replace('/', ".")))?
+            }
+            runtime.read_string(reference)?
+        }
+    })
+}
+
+#[cfg(test)]
+mod string_concat_tests {
+    use super::*;
+
+    #[test]
+    fn recipe() {
+        let args = ["42".to_string(), "true".to_string()];
+        let constants = ["\u{1}".to_string()];
+
+        assert_eq!(concat("n=\u{1}, \u{2}: \u{1}", &constants, &args).unwrap(), "n=42, \u{1}: true");
+    }
+
+    #[test]
+    fn recipe_missing_argument() {
+        assert!(concat("\u{1}\u{1}", &[], &["a".to_string()]).is_err());
+        assert!(concat("\u{2}", &[], &[]).is_err());
+    }
+}
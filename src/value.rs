@@ -0,0 +1,171 @@
+use crate::heap::{Heap, Reference};
+use anyhow::{anyhow, Error};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Reference(Reference),
+    Null,
+}
+
+impl Value {
+    /// The number of local variable slots the value occupies: two for longs and doubles, which the
+    /// JVM calls category 2 values, and one otherwise. The operand stack holds each value as a single
+    /// entry regardless.
+    pub fn slot_count(&self) -> usize {
+        match self {
+            Value::Long(_) | Value::Double(_) => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn as_int(self) -> Result<i32, Error> {
+        match self {
+            Value::Int(value) => Ok(value),
+            _ => Err(anyhow!("expected int, got {:?}", self))
+        }
+    }
+
+    pub fn as_long(self) -> Result<i64, Error> {
+        match self {
+            Value::Long(value) => Ok(value),
+            _ => Err(anyhow!("expected long, got {:?}", self))
+        }
+    }
+
+    pub fn as_float(self) -> Result<f32, Error> {
+        match self {
+            Value::Float(value) => Ok(value),
+            _ => Err(anyhow!("expected float, got {:?}", self))
+        }
+    }
+
+    pub fn as_double(self) -> Result<f64, Error> {
+        match self {
+            Value::Double(value) => Ok(value),
+            _ => Err(anyhow!("expected double, got {:?}", self))
+        }
+    }
+
+    /// Formats the value as Java would print it, for trace output: numbers as `String.valueOf` gives
+    /// them, and objects as `Object.toString` does, by class name and identity hash code, such as
+    /// `com.example.App@1`. Ints are shown as ints even when they hold a boolean or char.
+    pub(crate) fn display<'a>(&'a self, heap: &'a Heap) -> DisplayValue<'a> {
+        DisplayValue { value: self, heap }
+    }
+
+    /// Returns the referenced object, or `None` for `null`.
+    pub fn as_reference(self) -> Result<Option<Reference>, Error> {
+        match self {
+            Value::Reference(reference) => Ok(Some(reference)),
+            Value::Null => Ok(None),
+            _ => Err(anyhow!("expected reference, got {:?}", self))
+        }
+    }
+}
+
+/// A [`Value`] formatted as Java would print it, as given by [`Value::display`].
+pub(crate) struct DisplayValue<'a> {
+    value: &'a Value,
+    heap: &'a Heap,
+}
+
+impl fmt::Display for DisplayValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self.value {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Long(value) => write!(f, "{}", value),
+            Value::Float(value) => f.write_str(&java_floating_string(value)),
+            Value::Double(value) => f.write_str(&java_floating_string(value)),
+            Value::Null => f.write_str("null"),
+            // A dangling reference is a bug, but the trace of what led to it is still worth showing
+            Value::Reference(reference) => match self.heap.get(reference) {
+                Ok(object) => write!(f, "{}@{:x}", object.type_name().replace('/', "."), reference.id()),
+                Err(_) => write!(f, "<invalid reference {}>", reference.id()),
+            },
+        }
+    }
+}
+
+/// Formats a float or double as `Float.toString` and `Double.toString` do: in decimal from 10^-3 up
+/// to 10^7, and otherwise in scientific notation such as `1.0E10`, always with a fractional part.
+pub(crate) fn java_floating_string<T: Copy + Into<f64> + std::fmt::Debug + std::fmt::LowerExp>(value: T) -> String {
+    let double = value.into();
+    if double.is_nan() {
+        return "NaN".to_string();
+    }
+    if double.is_infinite() {
+        return if double > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if double == 0.0 || (1e-3..1e7).contains(&double.abs()) {
+        return format!("{:?}", value);
+    }
+    let scientific = format!("{:e}", value);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    if mantissa.contains('.') {
+        format!("{}E{}", mantissa, exponent)
+    } else {
+        format!("{}.0E{}", mantissa, exponent)
+    }
+}
+
+#[cfg(test)]
+mod display_tests {
+    use super::*;
+
+    #[test]
+    fn primitives() {
+        let heap = Heap::new();
+
+        assert_eq!(Value::Int(42).display(&heap).to_string(), "42");
+        assert_eq!(Value::Long(-7).display(&heap).to_string(), "-7");
+        assert_eq!(Value::Double(2.5).display(&heap).to_string(), "2.5");
+        assert_eq!(Value::Float(1e10).display(&heap).to_string(), "1.0E10");
+    }
+
+    #[test]
+    fn null() {
+        assert_eq!(Value::Null.display(&Heap::new()).to_string(), "null");
+    }
+
+    #[test]
+    fn array_references() {
+        let mut heap = Heap::new();
+        let arrays = (0..16).map(|_| heap.allocate_array("[I".to_string(), 1).unwrap()).collect::<Vec<_>>();
+
+        assert_eq!(Value::Reference(arrays[0]).display(&heap).to_string(), "[I@1");
+        assert_eq!(Value::Reference(arrays[15]).display(&heap).to_string(), "[I@10");
+    }
+
+    #[test]
+    fn floating_strings() {
+        assert_eq!(java_floating_string(1.0), "1.0");
+        assert_eq!(java_floating_string(-0.0), "-0.0");
+        assert_eq!(java_floating_string(0.001), "0.001");
+        assert_eq!(java_floating_string(1234567.5), "1234567.5");
+        assert_eq!(java_floating_string(1e7), "1.0E7");
+        assert_eq!(java_floating_string(1.5e-4), "1.5E-4");
+        assert_eq!(java_floating_string(0.1f32), "0.1");
+        assert_eq!(java_floating_string(f64::NEG_INFINITY), "-Infinity");
+        assert_eq!(java_floating_string(f32::NAN), "NaN");
+    }
+}
+
+#[cfg(all(test, feature = "jar"))]
+mod display_object_tests {
+    use super::*;
+    use crate::runtime::test_runtime::test_runtime;
+
+    #[test]
+    fn object_reference() {
+        let mut runtime = test_runtime();
+        let string = runtime.new_string("hello").unwrap();
+
+        let expected = format!("java.lang.String@{:x}", string.id());
+        assert_eq!(Value::Reference(string).display(&runtime.heap).to_string(), expected);
+    }
+}
@@ -0,0 +1,171 @@
+//! The embedding API: a [`Vm`] holds loaded classes and their state, and calls into them.
+
+use crate::check::link_classes;
+use crate::class_file::ClassFile;
+use crate::interpreter::{initialize_class, invoke_static};
+use crate::load_class_path_entry;
+use crate::runtime::{HostMethodDefinition, Runtime, RuntimeClass};
+use crate::value::Value;
+use anyhow::{anyhow, Error};
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+pub struct Vm {
+    runtime: Runtime,
+}
+
+impl Vm {
+    /// A VM with no classes loaded.
+    pub fn new() -> Self {
+        Vm { runtime: Runtime::new() }
+    }
+
+    /// Sends what programs print to standard output to the given sink rather than the process's
+    /// stdout, such as to capture it.
+    pub fn with_stdout(mut self, sink: Box<dyn Write>) -> Self {
+        self.runtime.stdout = sink;
+        self
+    }
+
+    /// Sends what programs print to standard error, and any other diagnostics such as the trace, to
+    /// the given sink rather than the process's stderr.
+    pub fn with_stderr(mut self, sink: Box<dyn Write>) -> Self {
+        self.runtime.stderr = sink;
+        self
+    }
+
+    /// A flag that stops whatever the VM is running when set, from any thread, failing the call
+    /// with a `java.lang.InterruptedException` error. The program can't catch it, and the frames
+    /// it unwinds release their monitors. Clear it again before running anything more.
+    pub fn cancellation_token(&self) -> Arc<AtomicBool> {
+        self.runtime.cancelled.clone()
+    }
+
+    /// Loads the classes of a class path entry: a directory of class files or, with the `jar`
+    /// feature, a jar.
+    pub fn load(&mut self, path: &Path) -> Result<(), Error> {
+        load_class_path_entry(&mut self.runtime, path)?;
+        Ok(())
+    }
+
+    /// Calls a static method, initializing its class first, and returns its result once it runs to
+    /// completion. The class name may be given in binary (`java.lang.Object`) or internal form.
+    pub fn invoke_static(&mut self, class_name: &str, name: &str, descriptor: &str, args: Vec<Value>)
+                         -> Result<Option<Value>, Error> {
+        invoke_static(&mut self.runtime, &class_name.replace('.', "/"), name, descriptor, args)
+    }
+
+    /// Links and initializes the named classes ahead of any call needing them, so that their
+    /// static initializers, and resolving the references of the classes they use, don't add to the
+    /// time of the first call. Class names may be given in binary or internal form.
+    pub fn preload(&mut self, class_names: &[&str]) -> Result<(), Error> {
+        for class_name in class_names {
+            let class_name = class_name.replace('.', "/");
+            link_classes(&self.runtime, &class_name)?;
+            let class = self.runtime.get_class(&class_name)?.clone();
+            initialize_class(&mut self.runtime, &class)?;
+        }
+        Ok(())
+    }
+
+    /// A loaded class, for reading what its class file declares, such as its generic signature.
+    /// The class name may be given in binary or internal form.
+    pub fn class(&self, class_name: &str) -> Result<Rc<RuntimeClass>, Error> {
+        Ok(self.runtime.get_class(&class_name.replace('.', "/"))?.clone())
+    }
+
+    /// The number of class lookups that have searched the loaded classes. Looking up a class
+    /// already found missing doesn't, until another class is loaded.
+    pub fn class_scan_count(&self) -> usize {
+        self.runtime.class_scans()
+    }
+
+    /// The number of classes whose static initialization has started, by a call or by [`Vm::preload`].
+    pub fn initialized_class_count(&self) -> usize {
+        self.runtime.initialized_count()
+    }
+
+    /// Passes each class file loaded from now on through a transformer, which may inspect or rewrite
+    /// it before it becomes a class, such as to instrument its methods. It's given the class's name
+    /// in binary form. Classes loaded before it was added, such as the boot classes, are unchanged.
+    pub fn add_transformer<F>(&mut self, transformer: F)
+        where F: Fn(&str, &mut ClassFile) -> Result<(), Error> + 'static {
+        self.runtime.add_transformer(Box::new(move |class_name, class_file| {
+            transformer(&class_name.replace('/', "."), class_file)
+        }));
+    }
+
+    /// Defines a class whose methods call into the host, for the loaded classes to call like any
+    /// other. Instances can be created with its no-argument constructor, which does nothing unless
+    /// the host class defines one.
+    pub fn register_host_class(&mut self, class: HostClass) -> Result<(), Error> {
+        self.runtime.define_host_class(&class.name.replace('.', "/"), class.methods)?;
+        Ok(())
+    }
+}
+
+/// A class implemented by Rust closures, defined with [`Vm::register_host_class`].
+pub struct HostClass {
+    name: String,
+    methods: Vec<HostMethodDefinition>,
+}
+
+impl HostClass {
+    /// A host class with the given name, in binary (`host.Console`) or internal form.
+    pub fn new(name: &str) -> Self {
+        HostClass { name: name.to_string(), methods: vec![] }
+    }
+
+    /// Adds a static method, called with its arguments.
+    pub fn static_method<F>(self, name: &str, descriptor: &str, implementation: F) -> Self
+        where F: Fn(&mut Host, Vec<Value>) -> Result<Option<Value>, Error> + 'static {
+        self.with_method(name, descriptor, true, implementation)
+    }
+
+    /// Adds an instance method, called with the receiver followed by its arguments.
+    pub fn method<F>(self, name: &str, descriptor: &str, implementation: F) -> Self
+        where F: Fn(&mut Host, Vec<Value>) -> Result<Option<Value>, Error> + 'static {
+        self.with_method(name, descriptor, false, implementation)
+    }
+
+    fn with_method<F>(mut self, name: &str, descriptor: &str, is_static: bool, implementation: F) -> Self
+        where F: Fn(&mut Host, Vec<Value>) -> Result<Option<Value>, Error> + 'static {
+        self.methods.push(HostMethodDefinition {
+            name: name.to_string(),
+            descriptor: descriptor.to_string(),
+            is_static,
+            implementation: Rc::new(move |runtime, args| implementation(&mut Host { runtime }, args)),
+        });
+        self
+    }
+}
+
+/// What a host method can do with the VM calling it: read and create the strings it passes and returns.
+pub struct Host<'a> {
+    runtime: &'a mut Runtime,
+}
+
+impl Host<'_> {
+    /// The contents of a String value, or `None` for null.
+    pub fn read_string(&self, value: Value) -> Result<Option<String>, Error> {
+        match value {
+            Value::Null => Ok(None),
+            Value::Reference(reference) => Ok(Some(self.runtime.read_string(reference)?)),
+            _ => Err(anyhow!("expected a String, got {:?}", value)),
+        }
+    }
+
+    /// Creates a String, such as for a host method to return.
+    pub fn new_string(&mut self, value: &str) -> Result<Value, Error> {
+        Ok(Value::Reference(self.runtime.new_string(value)?))
+    }
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "jar")]
+
+use anyhow::Error;
+use assert_cmd::Command;
+
+const CLASS: &str = "com.jkitch.robusta.test.Assertions";
+
+#[test]
+fn assertions_disabled_by_default() -> Result<(), Error> {
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg(CLASS)
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn failed_assertion_with_ea() -> Result<(), Error> {
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-ea")
+        .arg(CLASS)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Exception in thread \"main\" java.lang.AssertionError"));
+
+    Ok(())
+}
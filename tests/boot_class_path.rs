@@ -0,0 +1,95 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::{ClassBuilder, ACC_PUBLIC};
+use anyhow::Error;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+/// An App whose main constructs an App, then a PrintStream for stdout to print "hi" with.
+fn app() -> Vec<u8> {
+    let mut class = ClassBuilder::new("App");
+    let app_class = class.class("App");
+    let app_init = class.methodref("App", "<init>", "()V");
+    let object_init = class.methodref("java/lang/Object", "<init>", "()V");
+    let print_stream_class = class.class("java/io/PrintStream");
+    let print_stream_init = class.methodref("java/io/PrintStream", "<init>", "(I)V");
+    let println = class.methodref("java/io/PrintStream", "println", "(Ljava/lang/String;)V");
+    let hi = class.string("hi");
+
+    let mut main = vec![0xBB]; // new
+    main.extend(app_class.to_be_bytes());
+    main.push(0xB7); // invokespecial
+    main.extend(app_init.to_be_bytes());
+    main.push(0xBB); // new
+    main.extend(print_stream_class.to_be_bytes());
+    main.extend([0x59, 0x04, 0xB7]); // dup, iconst_1, invokespecial
+    main.extend(print_stream_init.to_be_bytes());
+    main.extend([0x12, hi as u8, 0xB6]); // ldc, invokevirtual
+    main.extend(println.to_be_bytes());
+    main.push(0xB1);
+
+    let mut init = vec![0x2A, 0xB7]; // aload_0, invokespecial
+    init.extend(object_init.to_be_bytes());
+    init.push(0xB1);
+
+    class.main(main).method(ACC_PUBLIC, "<init>", "()V", init);
+    class.build()
+}
+
+#[test]
+fn links_against_embedded_classes() -> Result<(), Error> {
+    // Outside the repository, so there's no data directory to load the runtime classes from
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), app())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .arg("-cp")
+        .arg(".")
+        .arg("App")
+        .assert()
+        .success()
+        .stdout("hi\n");
+
+    Ok(())
+}
+
+#[test]
+fn boot_class_path_replaces_embedded_classes() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), app())?;
+    fs::create_dir(dir.path().join("boot"))?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .arg("--bootclasspath")
+        .arg("boot")
+        .arg("-cp")
+        .arg(".")
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("unknown class java.lang.Object"));
+
+    Ok(())
+}
+
+#[test]
+fn data_runtime_jar_not_loaded_again() -> Result<(), Error> {
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--verbose")
+        .arg("com.jkitch.robusta.test.EmptyMain")
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("replaces").not()
+            .and(predicates::str::contains("robusta-java-runtime-0.1.0.jar").not()));
+
+    Ok(())
+}
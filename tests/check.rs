@@ -0,0 +1,155 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use std::fs::File;
+use std::io::{Cursor, Write};
+use tempfile::TempDir;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+#[test]
+fn check_reports_unparseable_class() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let jar_path = dir.path().join("app.jar");
+    let mut jar = ZipWriter::new(File::create(&jar_path)?);
+    for name in ["com/example/App", "com/example/Util"] {
+        jar.start_file(format!("{}.class", name), SimpleFileOptions::default())?;
+        jar.write_all(&ClassBuilder::new(name).main(vec![0xB1]).build())?;
+    }
+    let mut truncated = ClassBuilder::new("com/example/Broken").build();
+    truncated.truncate(20);
+    jar.start_file("com/example/Broken.class", SimpleFileOptions::default())?;
+    jar.write_all(&truncated)?;
+    jar.finish()?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--check")
+        .arg("-cp")
+        .arg(&jar_path)
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("FAILED com/example/Broken.class"))
+        .stdout(predicates::str::contains("Checked 3 classes: 2 loaded, 1 failed\n     1 failed to fill whole buffer\n"))
+        .stderr(predicates::str::contains("1 of 3 classes failed to load"));
+
+    Ok(())
+}
+
+#[test]
+fn check_passes_loadable_classes() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let jar_path = dir.path().join("app.jar");
+    let mut jar = ZipWriter::new(File::create(&jar_path)?);
+    jar.start_file("com/example/App.class", SimpleFileOptions::default())?;
+    jar.write_all(&ClassBuilder::new("com/example/App").main(vec![0xB1]).build())?;
+    jar.finish()?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--check")
+        .arg("-cp")
+        .arg(&jar_path)
+        .assert()
+        .success()
+        .stdout("Checked 1 classes: 1 loaded, 0 failed\n");
+
+    Ok(())
+}
+
+#[test]
+fn check_reports_directory_failures_in_name_order() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    for name in ["Zeta", "Alpha", "Mid", "Beta"] {
+        let mut truncated = ClassBuilder::new(name).build();
+        truncated.truncate(20);
+        fs::write(dir.path().join(format!("{}.class", name)), truncated)?;
+    }
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    let output = cmd.arg("--check")
+        .arg("-cp")
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let failed = String::from_utf8(output)?.lines()
+        .filter_map(|line| line.strip_prefix("FAILED "))
+        .map(|line| line.split(' ').next().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(failed, ["Alpha.class", "Beta.class", "Mid.class", "Zeta.class"]);
+
+    Ok(())
+}
+
+#[test]
+fn check_applies_verify_checks() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App").main(vec![0xB1]).build();
+    class.extend([0; 16]);
+    fs::write(dir.path().join("App.class"), class)?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-Xverify")
+        .arg("--check")
+        .arg("-cp")
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("FAILED App.class"))
+        .stdout(predicates::str::contains("     1 java.lang.ClassFormatError\n"));
+
+    Ok(())
+}
+
+#[test]
+fn check_skips_module_info_of_exploded_module() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("module-info.class"), b"not a class")?;
+    fs::write(dir.path().join("App.class"), ClassBuilder::new("App").main(vec![0xB1]).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--check")
+        .arg("-cp")
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout("Checked 1 classes: 1 loaded, 0 failed\n");
+
+    Ok(())
+}
+
+#[test]
+fn check_reads_jmod() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    zip.start_file("classes/module-info.class", SimpleFileOptions::default())?;
+    zip.write_all(b"not a class")?;
+    zip.start_file("classes/com/example/App.class", SimpleFileOptions::default())?;
+    zip.write_all(&ClassBuilder::new("com/example/App").main(vec![0xB1]).build())?;
+    let mut jmod = b"JM\x01\x00".to_vec();
+    jmod.extend(zip.finish()?.into_inner());
+    fs::write(dir.path().join("app.jmod"), jmod)?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--check")
+        .arg("-cp")
+        .arg(dir.path().join("app.jmod"))
+        .assert()
+        .success()
+        .stdout("Checked 1 classes: 1 loaded, 0 failed\n");
+
+    Ok(())
+}
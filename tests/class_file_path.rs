@@ -0,0 +1,64 @@
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn run_class_file_by_path() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let class_path = dir.path().join("App.class");
+    fs::write(&class_path, ClassBuilder::new("com/example/App").main(vec![0xB1]).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    // Run outside the repository, so there's no data directory either
+    cmd.current_dir(dir.path())
+        .arg(&class_path)
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn missing_class_file() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .arg("Missing.class")
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+#[test]
+fn run_class_file_from_stdin() -> Result<(), Error> {
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-")
+        .write_stdin(ClassBuilder::new("com/example/App").main(vec![0xB1]).build())
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn class_path_entry_from_stdin() -> Result<(), Error> {
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg("-")
+        .arg("com.example.App")
+        .write_stdin(ClassBuilder::new("com/example/App").main(vec![0xB1]).build())
+        .assert()
+        .success();
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn class_path_directory() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::create_dir_all(dir.path().join("com/example"))?;
+    fs::write(dir.path().join("com/example/App.class"), ClassBuilder::new("com/example/App").main(vec![0xB1]).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("com.example.App")
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn class_path_missing_class() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("com.example.App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("unknown class com.example.App"));
+
+    Ok(())
+}
+
+#[test]
+fn class_named_by_its_contents_not_its_path() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::create_dir_all(dir.path().join("COM/Example"))?;
+    fs::write(dir.path().join("COM/Example/app.CLASS"), ClassBuilder::new("com/example/App").main(vec![0xB1]).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("com.example.App")
+        .assert()
+        .success();
+
+    Ok(())
+}
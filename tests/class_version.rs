@@ -0,0 +1,46 @@
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn newer_class_version_rejected() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.major_version(65).main(vec![0xB1]);
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("java.lang.UnsupportedClassVersionError: App has been compiled by a more \
+            recent version of the Java Runtime (class file version 65.0), this VM supports class file versions up to 52.0"));
+
+    Ok(())
+}
+
+#[test]
+fn newer_class_version_loaded_when_ignored() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.major_version(65).main(vec![0xB1]);
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--ignore-class-version")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success();
+
+    Ok(())
+}
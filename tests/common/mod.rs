@@ -0,0 +1,291 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+pub const ACC_PUBLIC: u16 = 0x0001;
+pub const ACC_PRIVATE: u16 = 0x0002;
+pub const ACC_PROTECTED: u16 = 0x0004;
+pub const ACC_STATIC: u16 = 0x0008;
+pub const ACC_FINAL: u16 = 0x0010;
+pub const ACC_SUPER: u16 = 0x0020;
+pub const ACC_NATIVE: u16 = 0x0100;
+pub const ACC_INTERFACE: u16 = 0x0200;
+pub const ACC_ABSTRACT: u16 = 0x0400;
+
+pub const MAIN_DESCRIPTOR: &str = "([Ljava/lang/String;)V";
+
+/// Assembles minimal class files for tests that need classes not present in the test jars, or
+/// that are deliberately malformed.
+pub struct ClassBuilder {
+    major_version: u16,
+    max_locals: u16,
+    access_flags: u16,
+    this_class: u16,
+    super_class: u16,
+    consts: Vec<Vec<u8>>,
+    utf8s: HashMap<String, u16>,
+    fields: Vec<Vec<u8>>,
+    methods: Vec<Vec<u8>>,
+    attributes: Vec<Vec<u8>>,
+}
+
+impl ClassBuilder {
+    pub fn new(name: &str) -> Self {
+        let mut builder = ClassBuilder {
+            major_version: 52,
+            max_locals: 8,
+            access_flags: ACC_PUBLIC,
+            this_class: 0,
+            super_class: 0,
+            consts: vec![],
+            utf8s: HashMap::new(),
+            fields: vec![],
+            methods: vec![],
+            attributes: vec![],
+        };
+        builder.this_class = builder.class(name);
+        builder.super_class = builder.class("java/lang/Object");
+        builder
+    }
+
+    pub fn super_class(&mut self, name: &str) -> &mut Self {
+        self.super_class = self.class(name);
+        self
+    }
+
+    /// Gives the class no superclass, as only `java.lang.Object` has.
+    pub fn without_super_class(&mut self) -> &mut Self {
+        self.super_class = 0;
+        self
+    }
+
+    pub fn access_flags(&mut self, access_flags: u16) -> &mut Self {
+        self.access_flags = access_flags;
+        self
+    }
+
+    pub fn major_version(&mut self, major_version: u16) -> &mut Self {
+        self.major_version = major_version;
+        self
+    }
+
+    /// Sets the number of local variable slots of the methods added after it, 8 by default.
+    pub fn max_locals(&mut self, max_locals: u16) -> &mut Self {
+        self.max_locals = max_locals;
+        self
+    }
+
+    pub fn utf8(&mut self, value: &str) -> u16 {
+        if let Some(idx) = self.utf8s.get(value) {
+            return *idx;
+        }
+        let mut bytes = vec![1];
+        bytes.extend((value.len() as u16).to_be_bytes());
+        bytes.extend(value.bytes());
+        let idx = self.push_const(bytes);
+        self.utf8s.insert(value.to_string(), idx);
+        idx
+    }
+
+    pub fn class(&mut self, name: &str) -> u16 {
+        let name_idx = self.utf8(name);
+        let mut bytes = vec![7];
+        bytes.extend(name_idx.to_be_bytes());
+        self.push_const(bytes)
+    }
+
+    pub fn string(&mut self, value: &str) -> u16 {
+        let string_idx = self.utf8(value);
+        let mut bytes = vec![8];
+        bytes.extend(string_idx.to_be_bytes());
+        self.push_const(bytes)
+    }
+
+    pub fn fieldref(&mut self, class: &str, name: &str, descriptor: &str) -> u16 {
+        self.member_ref(9, class, name, descriptor)
+    }
+
+    pub fn methodref(&mut self, class: &str, name: &str, descriptor: &str) -> u16 {
+        self.member_ref(10, class, name, descriptor)
+    }
+
+    fn member_ref(&mut self, tag: u8, class: &str, name: &str, descriptor: &str) -> u16 {
+        let class_idx = self.class(class);
+        let name_and_type_idx = self.name_and_type(name, descriptor);
+
+        let mut bytes = vec![tag];
+        bytes.extend(class_idx.to_be_bytes());
+        bytes.extend(name_and_type_idx.to_be_bytes());
+        self.push_const(bytes)
+    }
+
+    fn name_and_type(&mut self, name: &str, descriptor: &str) -> u16 {
+        let name_idx = self.utf8(name);
+        let descriptor_idx = self.utf8(descriptor);
+        let mut bytes = vec![12];
+        bytes.extend(name_idx.to_be_bytes());
+        bytes.extend(descriptor_idx.to_be_bytes());
+        self.push_const(bytes)
+    }
+
+    pub fn method_handle(&mut self, reference_kind: u8, reference_idx: u16) -> u16 {
+        let mut bytes = vec![15, reference_kind];
+        bytes.extend(reference_idx.to_be_bytes());
+        self.push_const(bytes)
+    }
+
+    pub fn invoke_dynamic(&mut self, bootstrap_method_idx: u16, name: &str, descriptor: &str) -> u16 {
+        let name_and_type_idx = self.name_and_type(name, descriptor);
+        let mut bytes = vec![18];
+        bytes.extend(bootstrap_method_idx.to_be_bytes());
+        bytes.extend(name_and_type_idx.to_be_bytes());
+        self.push_const(bytes)
+    }
+
+    /// Adds a field without attributes.
+    pub fn field(&mut self, access_flags: u16, name: &str, descriptor: &str) -> &mut Self {
+        let name_idx = self.utf8(name);
+        let descriptor_idx = self.utf8(descriptor);
+
+        let mut field = vec![];
+        field.extend(access_flags.to_be_bytes());
+        field.extend(name_idx.to_be_bytes());
+        field.extend(descriptor_idx.to_be_bytes());
+        field.extend(0u16.to_be_bytes()); // attribute count
+        self.fields.push(field);
+        self
+    }
+
+    /// Adds a field with a ConstantValue attribute giving it the constant at `value_idx`.
+    pub fn constant_field(&mut self, access_flags: u16, name: &str, descriptor: &str, value_idx: u16) -> &mut Self {
+        let name_idx = self.utf8(name);
+        let descriptor_idx = self.utf8(descriptor);
+        let constant_value_idx = self.utf8("ConstantValue");
+
+        let mut field = vec![];
+        field.extend(access_flags.to_be_bytes());
+        field.extend(name_idx.to_be_bytes());
+        field.extend(descriptor_idx.to_be_bytes());
+        field.extend(1u16.to_be_bytes()); // attribute count
+        field.extend(constant_value_idx.to_be_bytes());
+        field.extend(2u32.to_be_bytes());
+        field.extend(value_idx.to_be_bytes());
+        self.fields.push(field);
+        self
+    }
+
+    pub fn method(&mut self, access_flags: u16, name: &str, descriptor: &str, code: Vec<u8>) -> &mut Self {
+        self.method_with_code_attributes(access_flags, name, descriptor, vec![code])
+    }
+
+    /// Adds a method with one Code attribute per body, which is only well formed for exactly one.
+    pub fn method_with_code_attributes(&mut self, access_flags: u16, name: &str, descriptor: &str,
+                                       codes: Vec<Vec<u8>>) -> &mut Self {
+        let name_idx = self.utf8(name);
+        let descriptor_idx = self.utf8(descriptor);
+        let code_idx = self.utf8("Code");
+
+        let mut method = vec![];
+        method.extend(access_flags.to_be_bytes());
+        method.extend(name_idx.to_be_bytes());
+        method.extend(descriptor_idx.to_be_bytes());
+        method.extend((codes.len() as u16).to_be_bytes());
+        for code in codes {
+            let mut code_attr = vec![];
+            code_attr.extend(8u16.to_be_bytes()); // max stack
+            code_attr.extend(self.max_locals.to_be_bytes());
+            code_attr.extend((code.len() as u32).to_be_bytes());
+            code_attr.extend(code);
+            code_attr.extend(0u16.to_be_bytes()); // exception table length
+            code_attr.extend(0u16.to_be_bytes()); // attribute count
+
+            method.extend(code_idx.to_be_bytes());
+            method.extend((code_attr.len() as u32).to_be_bytes());
+            method.extend(code_attr);
+        }
+        self.methods.push(method);
+        self
+    }
+
+    /// Adds a method with a single attribute of the given contents, such as a malformed Code attribute.
+    pub fn method_with_attribute(&mut self, access_flags: u16, name: &str, descriptor: &str, attribute_name: &str,
+                                 info: Vec<u8>) -> &mut Self {
+        let name_idx = self.utf8(name);
+        let descriptor_idx = self.utf8(descriptor);
+        let attribute_name_idx = self.utf8(attribute_name);
+
+        let mut method = vec![];
+        method.extend(access_flags.to_be_bytes());
+        method.extend(name_idx.to_be_bytes());
+        method.extend(descriptor_idx.to_be_bytes());
+        method.extend(1u16.to_be_bytes()); // attribute count
+        method.extend(attribute_name_idx.to_be_bytes());
+        method.extend((info.len() as u32).to_be_bytes());
+        method.extend(info);
+        self.methods.push(method);
+        self
+    }
+
+    /// Adds a class attribute with the given contents.
+    pub fn attribute(&mut self, name: &str, info: Vec<u8>) -> &mut Self {
+        let name_idx = self.utf8(name);
+        let mut attribute = vec![];
+        attribute.extend(name_idx.to_be_bytes());
+        attribute.extend((info.len() as u32).to_be_bytes());
+        attribute.extend(info);
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// Adds a BootstrapMethods attribute listing each method handle constant with its arguments.
+    pub fn bootstrap_methods(&mut self, methods: &[(u16, Vec<u16>)]) -> &mut Self {
+        let mut info = vec![];
+        info.extend((methods.len() as u16).to_be_bytes());
+        for (method_ref_idx, argument_idxs) in methods {
+            info.extend(method_ref_idx.to_be_bytes());
+            info.extend((argument_idxs.len() as u16).to_be_bytes());
+            for argument_idx in argument_idxs {
+                info.extend(argument_idx.to_be_bytes());
+            }
+        }
+        self.attribute("BootstrapMethods", info)
+    }
+
+    /// Adds `public static void main(String[])` with the given body.
+    pub fn main(&mut self, code: Vec<u8>) -> &mut Self {
+        self.method(ACC_PUBLIC | ACC_STATIC, "main", MAIN_DESCRIPTOR, code)
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend(0xCAFEBABEu32.to_be_bytes());
+        bytes.extend(0u16.to_be_bytes()); // minor version
+        bytes.extend(self.major_version.to_be_bytes());
+        bytes.extend((self.consts.len() as u16 + 1).to_be_bytes());
+        for const_item in &self.consts {
+            bytes.extend(const_item);
+        }
+        bytes.extend(self.access_flags.to_be_bytes());
+        bytes.extend(self.this_class.to_be_bytes());
+        bytes.extend(self.super_class.to_be_bytes());
+        bytes.extend(0u16.to_be_bytes()); // interface count
+        bytes.extend((self.fields.len() as u16).to_be_bytes());
+        for field in &self.fields {
+            bytes.extend(field);
+        }
+        bytes.extend((self.methods.len() as u16).to_be_bytes());
+        for method in &self.methods {
+            bytes.extend(method);
+        }
+        bytes.extend((self.attributes.len() as u16).to_be_bytes());
+        for attribute in &self.attributes {
+            bytes.extend(attribute);
+        }
+        bytes
+    }
+
+    fn push_const(&mut self, bytes: Vec<u8>) -> u16 {
+        self.consts.push(bytes);
+        self.consts.len() as u16
+    }
+}
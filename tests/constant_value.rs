@@ -0,0 +1,67 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::{ClassBuilder, ACC_FINAL, ACC_PUBLIC, ACC_STATIC};
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+/// Code printing the string constant at `text` to System.out.
+fn print(class: &mut ClassBuilder, text: u16) -> Vec<u8> {
+    let out = class.fieldref("java/lang/System", "out", "Ljava/io/PrintStream;");
+    let println = class.methodref("java/io/PrintStream", "println", "(Ljava/lang/String;)V");
+    let mut code = vec![0xB2]; // getstatic
+    code.extend(out.to_be_bytes());
+    code.extend([0x12, text as u8, 0xB6]); // ldc, invokevirtual
+    code.extend(println.to_be_bytes());
+    code
+}
+
+/// Config, whose GREETING is the constant "hi" and whose <clinit> prints "initialized", and an App
+/// whose main prints GREETING.
+fn classes() -> (Vec<u8>, Vec<u8>) {
+    let mut config = ClassBuilder::new("Config");
+    let greeting = config.string("hi");
+    let initialized = config.string("initialized");
+    let mut clinit = print(&mut config, initialized);
+    clinit.push(0xB1);
+    config.constant_field(ACC_PUBLIC | ACC_STATIC | ACC_FINAL, "GREETING", "Ljava/lang/String;", greeting)
+        .method(ACC_STATIC, "<clinit>", "()V", clinit);
+
+    let mut app = ClassBuilder::new("App");
+    let field = app.fieldref("Config", "GREETING", "Ljava/lang/String;");
+    let out = app.fieldref("java/lang/System", "out", "Ljava/io/PrintStream;");
+    let println = app.methodref("java/io/PrintStream", "println", "(Ljava/lang/String;)V");
+    let mut main = vec![0xB2]; // getstatic System.out
+    main.extend(out.to_be_bytes());
+    main.push(0xB2); // getstatic Config.GREETING
+    main.extend(field.to_be_bytes());
+    main.push(0xB6); // invokevirtual
+    main.extend(println.to_be_bytes());
+    main.push(0xB1);
+    app.main(main);
+
+    (config.build(), app.build())
+}
+
+#[test]
+fn constant_read_without_initializing() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let (config, app) = classes();
+    fs::write(dir.path().join("Config.class"), config)?;
+    fs::write(dir.path().join("App.class"), app)?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success()
+        .stdout("hi\n");
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs::{self, File};
+use std::io::Write;
+use tempfile::TempDir;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+#[test]
+fn not_a_zip() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let jar_path = dir.path().join("bad.jar");
+    fs::write(&jar_path, "not a zip")?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(&jar_path)
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!("failed to open jar {}", jar_path.display())));
+
+    Ok(())
+}
+
+#[test]
+fn truncated_jar() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let jar_path = dir.path().join("app.jar");
+    let mut jar = ZipWriter::new(File::create(&jar_path)?);
+    jar.start_file("App.class", SimpleFileOptions::default())?;
+    jar.write_all(&ClassBuilder::new("App").main(vec![0xB1]).build())?;
+    jar.finish()?;
+    let bytes = fs::read(&jar_path)?;
+    fs::write(&jar_path, &bytes[..bytes.len() / 2])?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(&jar_path)
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!("failed to open jar {}", jar_path.display())));
+
+    Ok(())
+}
+
+#[test]
+fn bad_class_in_jar() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let jar_path = dir.path().join("app.jar");
+    let mut jar = ZipWriter::new(File::create(&jar_path)?);
+    jar.start_file("com/example/App.class", SimpleFileOptions::default())?;
+    jar.write_all(&[0xCA, 0xFE])?;
+    jar.finish()?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(&jar_path)
+        .arg("com.example.App")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!("failed to load jar {}", jar_path.display()))
+            .and(predicate::str::contains("failed to read com/example/App.class")));
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+/// A main that counts local 1 up to 10.
+fn counting_loop() -> Vec<u8> {
+    vec![
+        0x03, 0x3C, // iconst_0, istore_1
+        0x84, 0x01, 0x01, // iinc 1 1
+        0x1B, 0x10, 10, // iload_1, bipush 10
+        0xA1, 0xFF, 0xFA, // if_icmplt -6
+        0xB1, // return
+    ]
+}
+
+#[test]
+fn counts_printed_most_frequent_first() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), ClassBuilder::new("App").main(counting_loop()).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    let output = cmd.arg("--count-opcodes")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success()
+        .get_output()
+        .stderr
+        .clone();
+
+    let counts = String::from_utf8(output)?.lines()
+        .map(|line| line.split_whitespace().map(String::from).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(counts, [
+        ["10", "bipush"], ["10", "iload_1"], ["10", "iinc"], ["10", "if_icmplt"],
+        ["1", "iconst_0"], ["1", "istore_1"], ["1", "return"],
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn no_counts_by_default() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), ClassBuilder::new("App").main(counting_loop()).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success()
+        .stderr("");
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use tempfile::TempDir;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Writes a jar holding a class com.foo.Dup with an empty main and, for the first jar, com.foo.App.
+fn write_jar(path: &Path, with_app: bool) -> Result<(), Error> {
+    let mut jar = ZipWriter::new(File::create(path)?);
+    jar.start_file("com/foo/Dup.class", SimpleFileOptions::default())?;
+    jar.write_all(&ClassBuilder::new("com/foo/Dup").main(vec![0xB1]).build())?;
+    if with_app {
+        jar.start_file("com/foo/App.class", SimpleFileOptions::default())?;
+        jar.write_all(&ClassBuilder::new("com/foo/App").main(vec![0xB1]).build())?;
+    }
+    jar.finish()?;
+    Ok(())
+}
+
+/// Runs com.foo.Dup from a class path of two jars that both define it, from a directory without
+/// the repository's data jars so that only these classes are duplicated.
+fn run_duplicated(args: &[&str]) -> Result<assert_cmd::assert::Assert, Error> {
+    let dir = TempDir::new()?;
+    write_jar(&dir.path().join("first.jar"), true)?;
+    write_jar(&dir.path().join("second.jar"), false)?;
+
+    Ok(Command::cargo_bin("robusta")?
+        .current_dir(dir.path())
+        .args(args)
+        .arg("-cp")
+        .arg(env::join_paths(["first.jar", "second.jar"])?)
+        .arg("com.foo.Dup")
+        .assert())
+}
+
+#[test]
+fn duplicate_rejected_under_strict_class_path() -> Result<(), Error> {
+    run_duplicated(&["--strict-class-path"])?
+        .failure()
+        .stderr(predicate::str::contains(
+            "java.lang.LinkageError: duplicate class com.foo.Dup in second.jar, already loaded from first.jar"));
+
+    Ok(())
+}
+
+#[test]
+fn duplicate_warned_otherwise() -> Result<(), Error> {
+    run_duplicated(&["--verbose"])?
+        .success()
+        .stderr(predicate::str::contains("class com.foo.Dup in second.jar replaces the one loaded from first.jar")
+            .and(predicate::str::contains("com.foo.App in").not()));
+
+    Ok(())
+}
+
+#[test]
+fn strict_class_path_accepts_default_layout() -> Result<(), Error> {
+    // The working directory's data jars hold a copy of the built-in runtime classes
+    Command::cargo_bin("robusta")?
+        .arg("--strict-class-path")
+        .arg("com.jkitch.robusta.test.EmptyMain")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("LinkageError").not());
+
+    Ok(())
+}
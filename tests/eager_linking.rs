@@ -0,0 +1,66 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+const NO_SUCH_METHOD: &str = "java.lang.NoSuchMethodError: App.missing()V";
+
+/// Writes an App whose main calls a method it doesn't declare, only when given arguments.
+fn app() -> Result<TempDir, Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    let missing = class.methodref("App", "missing", "()V");
+    let [high, low] = missing.to_be_bytes();
+    // aload_0, arraylength, ifeq +6, invokestatic App.missing, return
+    fs::write(dir.path().join("App.class"), class.main(vec![0x2A, 0xBE, 0x99, 0x00, 0x06, 0xB8, high, low, 0xB1]).build())?;
+    Ok(dir)
+}
+
+#[test]
+fn eager_fails_at_load() -> Result<(), Error> {
+    let dir = app()?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--eager")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("linking App").and(predicate::str::contains(NO_SUCH_METHOD)));
+
+    Ok(())
+}
+
+#[test]
+fn lazy_runs_until_the_call() -> Result<(), Error> {
+    let dir = app()?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--lazy")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .arg("call")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(NO_SUCH_METHOD));
+
+    Ok(())
+}
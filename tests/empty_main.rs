@@ -1,3 +1,5 @@
+#![cfg(feature = "jar")]
+
 use anyhow::Error;
 use assert_cmd::Command;
 
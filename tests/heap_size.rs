@@ -0,0 +1,90 @@
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+/// A main class allocating an int array of the given length.
+fn write_app(dir: &TempDir, length: i16) -> Result<(), Error> {
+    let [high, low] = length.to_be_bytes();
+    // sipush length; newarray int; astore_1; return
+    let code = vec![0x11, high, low, 0xBC, 10, 0x4C, 0xB1];
+    fs::write(dir.path().join("App.class"), ClassBuilder::new("App").main(code).build())?;
+    Ok(())
+}
+
+#[test]
+fn allocate_within_max_heap() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    write_app(&dir, 100)?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-Xmx1k")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn allocate_past_max_heap() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    write_app(&dir, 1000)?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-Xmx1k")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("java.lang.OutOfMemoryError: Java heap space"));
+
+    Ok(())
+}
+
+#[test]
+fn initial_heap_above_max() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    write_app(&dir, 1)?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-Xms2m")
+        .arg("-Xmx1m")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Initial heap size set to a larger value than the maximum heap size"));
+
+    Ok(())
+}
+
+#[test]
+fn initial_heap_reported() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    write_app(&dir, 1)?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--verbose")
+        .arg("-Xms2m")
+        .arg("-Xmx4m")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("heap starting at 2097152 bytes"));
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use crate::common::{ClassBuilder, ACC_PUBLIC};
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// An App whose main creates an Object.
+fn app() -> Vec<u8> {
+    let mut class = ClassBuilder::new("App");
+    let object_class = class.class("java/lang/Object");
+    let object_init = class.methodref("java/lang/Object", "<init>", "()V");
+
+    let mut main = vec![0xBB]; // new
+    main.extend(object_class.to_be_bytes());
+    main.push(0xB7); // invokespecial
+    main.extend(object_init.to_be_bytes());
+    main.push(0xB1);
+    class.main(main).build()
+}
+
+/// Creates a Robusta home whose lib directory has only a stub Object, and an App to run against it.
+fn home_with_stub_object(dir: &Path) -> Result<(), Error> {
+    fs::create_dir_all(dir.join("home/lib/java/lang"))?;
+    let object = ClassBuilder::new("java/lang/Object")
+        .without_super_class()
+        .method(ACC_PUBLIC, "<init>", "()V", vec![0xB1])
+        .build();
+    fs::write(dir.join("home/lib/java/lang/Object.class"), object)?;
+    fs::write(dir.join("App.class"), app())?;
+    Ok(())
+}
+
+#[test]
+fn home_flag() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    home_with_stub_object(dir.path())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .env_remove("ROBUSTA_HOME")
+        .arg("--home")
+        .arg("home")
+        .arg("-cp")
+        .arg(".")
+        .arg("App")
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn home_env() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    home_with_stub_object(dir.path())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .env("ROBUSTA_HOME", dir.path().join("home"))
+        .arg("-cp")
+        .arg(".")
+        .arg("App")
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn home_replaces_embedded_classes() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::create_dir_all(dir.path().join("home/lib"))?;
+    fs::write(dir.path().join("App.class"), app())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .arg("--home")
+        .arg("home")
+        .arg("-cp")
+        .arg(".")
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("unknown class java.lang.Object"));
+
+    Ok(())
+}
+
+#[test]
+fn home_without_lib() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .arg("--home")
+        .arg(".")
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("has no lib directory"));
+
+    Ok(())
+}
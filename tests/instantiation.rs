@@ -0,0 +1,42 @@
+mod common;
+
+use crate::common::{ClassBuilder, ACC_ABSTRACT, ACC_INTERFACE, ACC_PUBLIC};
+use anyhow::Error;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+/// Runs an App whose main creates an instance of a class with the given access flags.
+fn new_instance(access_flags: u16) -> Result<assert_cmd::assert::Assert, Error> {
+    let dir = TempDir::new()?;
+    let shape = ClassBuilder::new("Shape").access_flags(access_flags).build();
+    fs::write(dir.path().join("Shape.class"), shape)?;
+    let mut app = ClassBuilder::new("App");
+    let shape_class = app.class("Shape");
+    let mut main = vec![0xBB]; // new
+    main.extend(shape_class.to_be_bytes());
+    main.extend([0x57, 0xB1]); // pop, return
+    fs::write(dir.path().join("App.class"), app.main(main).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+    Ok(cmd.current_dir(dir.path()).arg("-cp").arg(".").arg("App").assert())
+}
+
+#[test]
+fn new_interface() -> Result<(), Error> {
+    new_instance(ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT)?
+        .failure()
+        .stderr(predicate::str::contains("java.lang.InstantiationError: Shape"));
+
+    Ok(())
+}
+
+#[test]
+fn new_abstract_class() -> Result<(), Error> {
+    new_instance(ACC_PUBLIC | ACC_ABSTRACT)?
+        .failure()
+        .stderr(predicate::str::contains("java.lang.InstantiationError: Shape"));
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::{ClassBuilder, ACC_PUBLIC, ACC_STATIC, ACC_SUPER};
+use anyhow::Error;
+use robusta::{Value, Vm};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A class with a constructor calling its superclass's, and `name()I` returning `name` if given.
+fn class(name: &str, super_class: &str, returns: Option<u8>) -> ClassBuilder {
+    let mut class = ClassBuilder::new(name);
+    class.super_class(super_class);
+    let super_init = class.methodref(super_class, "<init>", "()V");
+    let mut init = vec![0x2A, 0xB7]; // aload_0, invokespecial
+    init.extend(super_init.to_be_bytes());
+    init.push(0xB1);
+    class.method(ACC_PUBLIC, "<init>", "()V", init);
+    if let Some(returns) = returns {
+        class.method(ACC_PUBLIC, "name", "()I", vec![0x10, returns, 0xAC]); // bipush, ireturn
+    }
+    class
+}
+
+/// Loads A and B, whose `name` methods return 1 and 2, and C extending B, whose static `run` calls
+/// `A.name` on a new C with invokespecial, as if C were compiled before B overrode it.
+fn run(c_access_flags: u16) -> Result<Option<Value>, Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("A.class"), class("A", "java/lang/Object", Some(1)).build())?;
+    fs::write(dir.path().join("B.class"), class("B", "A", Some(2)).build())?;
+    let mut c = class("C", "B", None);
+    let c_class = c.class("C");
+    let c_init = c.methodref("C", "<init>", "()V");
+    let a_name = c.methodref("A", "name", "()I");
+    let mut run = vec![0xBB]; // new
+    run.extend(c_class.to_be_bytes());
+    run.extend([0x59, 0xB7]); // dup, invokespecial
+    run.extend(c_init.to_be_bytes());
+    run.push(0xB7); // invokespecial
+    run.extend(a_name.to_be_bytes());
+    run.push(0xAC); // ireturn
+    c.access_flags(c_access_flags).method(ACC_PUBLIC | ACC_STATIC, "run", "()I", run);
+    fs::write(dir.path().join("C.class"), c.build())?;
+
+    let mut vm = Vm::new();
+    vm.load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("data/robusta-java-runtime-0.1.0.jar"))?;
+    vm.load(dir.path())?;
+    vm.invoke_static("C", "run", "()I", vec![])
+}
+
+#[test]
+fn super_method_from_direct_superclass() -> Result<(), Error> {
+    assert_eq!(run(ACC_PUBLIC | ACC_SUPER)?, Some(Value::Int(2)));
+
+    Ok(())
+}
+
+#[test]
+fn super_method_as_resolved_without_acc_super() -> Result<(), Error> {
+    assert_eq!(run(ACC_PUBLIC)?, Some(Value::Int(1)));
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use tempfile::TempDir;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+fn write_jar(path: &Path, manifest: &str) -> Result<(), Error> {
+    let mut jar = ZipWriter::new(File::create(path)?);
+
+    jar.start_file("META-INF/MANIFEST.MF", SimpleFileOptions::default())?;
+    jar.write_all(manifest.as_bytes())?;
+
+    jar.start_file("com/example/App.class", SimpleFileOptions::default())?;
+    jar.write_all(&ClassBuilder::new("com/example/App").main(vec![0xB1]).build())?;
+
+    jar.finish()?;
+    Ok(())
+}
+
+#[test]
+fn jar_main_class() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let jar_path = dir.path().join("app.jar");
+    write_jar(&jar_path, "Manifest-Version: 1.0\r\nMain-Class: com.example.App\r\n\r\n")?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-jar")
+        .arg(&jar_path)
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn jar_without_main_class() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let jar_path = dir.path().join("app.jar");
+    write_jar(&jar_path, "Manifest-Version: 1.0\r\n\r\n")?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-jar")
+        .arg(&jar_path)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("no main manifest attribute"));
+
+    Ok(())
+}
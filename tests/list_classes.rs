@@ -0,0 +1,72 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use tempfile::TempDir;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+#[test]
+fn list_jar_and_directory_classes() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let jar_path = dir.path().join("app.jar");
+    let mut jar = ZipWriter::new(File::create(&jar_path)?);
+    for name in ["com/example/App", "com/example/Util", "com/example/model/Item"] {
+        jar.start_file(format!("{}.class", name), SimpleFileOptions::default())?;
+        jar.write_all(&ClassBuilder::new(name).build())?;
+    }
+    jar.finish()?;
+    let classes_dir = dir.path().join("classes");
+    fs::create_dir(&classes_dir)?;
+    fs::write(classes_dir.join("Extra.class"), ClassBuilder::new("Extra").build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .arg("--list-classes")
+        .arg("-cp")
+        .arg(std::env::join_paths(["app.jar", "classes"])?)
+        .assert()
+        .success()
+        .stdout("Extra (classes)\ncom.example.App (app.jar)\ncom.example.Util (app.jar)\ncom.example.model.Item (app.jar)\n");
+
+    Ok(())
+}
+
+#[test]
+fn listing_is_stable_across_runs() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    for name in ["Zeta", "alpha", "Mid", "pkg/Beta", "pkg/Alpha", "Omega", "delta"] {
+        let path = dir.path().join("classes").join(format!("{}.class", name));
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, ClassBuilder::new(name).build())?;
+    }
+
+    let list = || -> Result<Vec<u8>, Error> {
+        let mut cmd = Command::cargo_bin("robusta")?;
+        Ok(cmd.current_dir(dir.path())
+            .arg("--list-classes")
+            .arg("-cp")
+            .arg("classes")
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone())
+    };
+
+    let first = list()?;
+    assert_eq!(first, list()?);
+    let names = String::from_utf8(first)?.lines()
+        .map(|line| line.split(' ').next().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Mid", "Omega", "Zeta", "alpha", "delta", "pkg.Alpha", "pkg.Beta"]);
+
+    Ok(())
+}
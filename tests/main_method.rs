@@ -0,0 +1,45 @@
+mod common;
+
+use crate::common::{ClassBuilder, ACC_PUBLIC, ACC_STATIC, MAIN_DESCRIPTOR};
+use anyhow::Error;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn run_main(access_flags: u16) -> Result<assert_cmd::assert::Assert, Error> {
+    let class = ClassBuilder::new("com/example/App").method(access_flags, "main", MAIN_DESCRIPTOR, vec![0xB1]).build();
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+    Ok(cmd.arg("-").write_stdin(class).assert())
+}
+
+#[test]
+fn non_static_main() -> Result<(), Error> {
+    run_main(ACC_PUBLIC)?
+        .failure()
+        .stderr(predicate::str::contains("Main method not found in class com.example.App"))
+        .stderr(predicate::str::contains("public static void main(String[] args)"));
+
+    Ok(())
+}
+
+#[test]
+fn non_public_main() -> Result<(), Error> {
+    run_main(ACC_STATIC)?
+        .failure()
+        .stderr(predicate::str::contains("Main method not found in class com.example.App"));
+
+    Ok(())
+}
+
+#[test]
+fn missing_main() -> Result<(), Error> {
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-")
+        .write_stdin(ClassBuilder::new("com/example/App").build())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Main method not found in class com.example.App"));
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use crate::common::{ClassBuilder, ACC_NATIVE, ACC_PUBLIC, ACC_STATIC, MAIN_DESCRIPTOR};
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn garbled_method_descriptor() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.main(vec![0xB1])
+        .method(ACC_PUBLIC | ACC_STATIC, "broken", "(IQ)V", vec![0xB1]);
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "java.lang.ClassFormatError: Method \"broken\" in class App has illegal signature \"(IQ)V\""));
+
+    Ok(())
+}
+
+#[test]
+fn duplicate_code_attributes() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.method_with_code_attributes(ACC_PUBLIC | ACC_STATIC, "main", MAIN_DESCRIPTOR, vec![vec![0xB1], vec![0xB1]]);
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "java.lang.ClassFormatError: Multiple Code attributes in method \"main\" in class App"));
+
+    Ok(())
+}
+
+#[test]
+fn missing_code_attribute() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.main(vec![0xB1])
+        .method_with_code_attributes(ACC_PUBLIC | ACC_STATIC, "broken", "()V", vec![]);
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "java.lang.ClassFormatError: Absent Code attribute in method \"broken\" in class App"));
+
+    Ok(())
+}
+
+#[test]
+fn native_method_without_code() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.main(vec![0xB1])
+        .method_with_code_attributes(ACC_PUBLIC | ACC_STATIC | ACC_NATIVE, "native", "()V", vec![]);
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn own_superclass() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.super_class("App").main(vec![0xB1]);
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("java.lang.ClassCircularityError: App"));
+
+    Ok(())
+}
+
+#[test]
+fn missing_superclass() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.without_super_class().main(vec![0xB1]);
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("java.lang.ClassFormatError: Invalid superclass index 0 in class file App"));
+
+    Ok(())
+}
+
+#[test]
+fn truncated_code_attribute() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    // Max stack and max locals, without the code length after them
+    class.main(vec![0xB1])
+        .method_with_attribute(ACC_PUBLIC | ACC_STATIC, "broken", "()V", "Code", vec![0x00, 0x08, 0x00, 0x08]);
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "failed to read u32 at offset 0x4 into the Code attribute of method broken in class App"));
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use tempfile::TempDir;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// A descriptor that would fail to parse if it were loaded as a class.
+const MODULE_INFO: &[u8] = b"not a class";
+
+fn app() -> Vec<u8> {
+    ClassBuilder::new("com/example/App").main(vec![0xB1]).build()
+}
+
+/// Writes a jmod as the JDK's jmod tool does: a `JM` header followed by a zip whose offsets don't
+/// count it, with the class files under `classes/`.
+fn write_jmod(path: &Path) -> Result<(), Error> {
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    zip.start_file("classes/module-info.class", SimpleFileOptions::default())?;
+    zip.write_all(MODULE_INFO)?;
+    zip.start_file("classes/com/example/App.class", SimpleFileOptions::default())?;
+    zip.write_all(&app())?;
+    zip.start_file("conf/app.properties", SimpleFileOptions::default())?;
+    zip.write_all(b"name=app\n")?;
+
+    let mut jmod = b"JM\x01\x00".to_vec();
+    jmod.extend(zip.finish()?.into_inner());
+    fs::write(path, jmod)?;
+    Ok(())
+}
+
+#[test]
+fn jmod() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    write_jmod(&dir.path().join("app.jmod"))?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .arg("--module-path")
+        .arg("app.jmod")
+        .arg("com.example.App")
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn exploded_module() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::create_dir_all(dir.path().join("app/com/example"))?;
+    fs::write(dir.path().join("app/module-info.class"), MODULE_INFO)?;
+    fs::write(dir.path().join("app/com/example/App.class"), app())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .arg("-p")
+        .arg("app")
+        .arg("com.example.App")
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn directory_of_modules() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::create_dir_all(dir.path().join("mods/util"))?;
+    fs::write(dir.path().join("mods/util/module-info.class"), MODULE_INFO)?;
+    write_jmod(&dir.path().join("mods/app.jmod"))?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.current_dir(dir.path())
+        .arg("--module-path")
+        .arg("mods")
+        .arg("com.example.App")
+        .assert()
+        .success();
+
+    Ok(())
+}
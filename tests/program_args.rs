@@ -0,0 +1,81 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+const REF_INVOKE_STATIC: u8 = 6;
+const BOOTSTRAP_DESCRIPTOR: &str = "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;\
+    Ljava/lang/invoke/MethodType;Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/invoke/CallSite;";
+
+/// An App whose main throws a RuntimeException with the message `args[0] args[1] args[2] of
+/// args.length`.
+fn app() -> Vec<u8> {
+    let mut class = ClassBuilder::new("App");
+    let bootstrap = class.methodref("java/lang/invoke/StringConcatFactory", "makeConcatWithConstants", BOOTSTRAP_DESCRIPTOR);
+    let bootstrap = class.method_handle(REF_INVOKE_STATIC, bootstrap);
+    let recipe = class.string("\u{1} \u{1} \u{1} of \u{1}");
+    let call_site = class.invoke_dynamic(0, "makeConcatWithConstants",
+        "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;I)Ljava/lang/String;");
+    let exception = class.class("java/lang/RuntimeException");
+    let init = class.methodref("java/lang/RuntimeException", "<init>", "(Ljava/lang/String;)V");
+
+    let mut main = vec![0xBB];
+    main.extend(exception.to_be_bytes());
+    main.push(0x59); // dup
+    for idx in 0..3 {
+        main.extend([0x2A, 0x03 + idx, 0x32]); // aload_0, iconst_<idx>, aaload
+    }
+    main.extend([0x2A, 0xBE, 0xBA]); // aload_0, arraylength, invokedynamic
+    main.extend(call_site.to_be_bytes());
+    main.extend([0x00, 0x00, 0xB7]); // invokespecial
+    main.extend(init.to_be_bytes());
+    main.push(0xBF); // athrow
+    class.main(main).bootstrap_methods(&[(bootstrap, vec![recipe])]).build()
+}
+
+#[test]
+fn args_passed_to_main() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), app())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .args(["App", "a", "b", "c"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("java.lang.RuntimeException: a b c of 3"));
+
+    Ok(())
+}
+
+#[test]
+fn options_after_main_class_are_args() -> Result<(), Error> {
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.args(["-", "-cp", "--trace", "wörld"])
+        .write_stdin(app())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("java.lang.RuntimeException: -cp --trace wörld of 3"));
+
+    Ok(())
+}
+
+#[test]
+fn no_args() -> Result<(), Error> {
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Usage: robusta [options] <main class> [args...]"));
+
+    Ok(())
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use crate::common::{ClassBuilder, ACC_PUBLIC, ACC_STATIC};
+use anyhow::Error;
+use robusta::class_file::{ClassFile, Code};
+use robusta::descriptor::{FieldType, MethodDescriptor};
+use robusta::instruction::Instruction;
+
+#[test]
+fn enumerate_methods() -> Result<(), Error> {
+    let mut class = ClassBuilder::new("com/example/App");
+    class.main(vec![0xB1])
+        .method(ACC_PUBLIC | ACC_STATIC, "helper", "(I)I", vec![0x1A, 0xAC]);
+    let bytes = class.build();
+
+    let class_file = ClassFile::read_from(&mut bytes.as_slice())?;
+
+    let this_class = class_file.const_pool.get_class(class_file.this_class)?;
+    assert_eq!(class_file.const_pool.get_utf8(this_class.name_idx)?.bytes, "com/example/App");
+    let methods = class_file.methods.iter()
+        .map(|method| Ok((
+            class_file.const_pool.get_utf8(method.name_idx)?.bytes.as_str(),
+            class_file.const_pool.get_utf8(method.descriptor_idx)?.bytes.as_str(),
+        )))
+        .collect::<Result<Vec<_>, Error>>()?;
+    assert_eq!(methods, vec![("main", "([Ljava/lang/String;)V"), ("helper", "(I)I")]);
+
+    Ok(())
+}
+
+#[test]
+fn inner_classes() -> Result<(), Error> {
+    let mut class = ClassBuilder::new("com/example/Outer");
+    let inner_class_idx = class.class("com/example/Outer$Inner");
+    let outer_class_idx = class.class("com/example/Outer");
+    let inner_name_idx = class.utf8("Inner");
+    let mut info = vec![];
+    info.extend(1u16.to_be_bytes());
+    info.extend(inner_class_idx.to_be_bytes());
+    info.extend(outer_class_idx.to_be_bytes());
+    info.extend(inner_name_idx.to_be_bytes());
+    info.extend((ACC_PUBLIC | ACC_STATIC).to_be_bytes());
+    let bytes = class.attribute("InnerClasses", info).build();
+
+    let class_file = ClassFile::read_from(&mut bytes.as_slice())?;
+
+    let [inner_class] = class_file.inner_classes.as_slice() else {
+        panic!("expected one inner class, found {:?}", class_file.inner_classes);
+    };
+    assert_eq!(inner_class.inner_class(&class_file.const_pool)?, "com/example/Outer$Inner");
+    assert_eq!(inner_class.outer_class(&class_file.const_pool)?, Some("com/example/Outer"));
+    assert_eq!(inner_class.inner_name(&class_file.const_pool)?, Some("Inner"));
+    assert_eq!(inner_class.access_flags, ACC_PUBLIC | ACC_STATIC);
+
+    Ok(())
+}
+
+#[test]
+fn bootstrap_methods() -> Result<(), Error> {
+    let mut class = ClassBuilder::new("com/example/App");
+    let bootstrap = class.methodref("java/lang/invoke/StringConcatFactory", "makeConcat", "()V");
+    let bootstrap = class.method_handle(6, bootstrap);
+    let recipe = class.string("\u{1}");
+    let call_site = class.invoke_dynamic(0, "makeConcat", "(I)Ljava/lang/String;");
+    let bytes = class.bootstrap_methods(&[(bootstrap, vec![recipe])]).build();
+
+    let class_file = ClassFile::read_from(&mut bytes.as_slice())?;
+
+    let [bootstrap_method] = class_file.bootstrap_methods.as_slice() else {
+        panic!("expected one bootstrap method, found {:?}", class_file.bootstrap_methods);
+    };
+    assert_eq!(bootstrap_method.argument_idxs, vec![recipe]);
+    let method_handle = class_file.const_pool.get_method_handle(bootstrap_method.method_ref_idx)?;
+    assert_eq!(method_handle.reference_kind, 6);
+    let invoke_dynamic = class_file.const_pool.get_invoke_dynamic(call_site)?;
+    assert_eq!(invoke_dynamic.bootstrap_method_attr_idx, 0);
+
+    Ok(())
+}
+
+#[test]
+fn method_descriptors() -> Result<(), Error> {
+    let mut class = ClassBuilder::new("com/example/App");
+    class.method(ACC_PUBLIC | ACC_STATIC, "helper", "(I[[JLjava/lang/String;)[Z", vec![0x01, 0xB0]);
+    let bytes = class.build();
+
+    let class_file = ClassFile::read_from(&mut bytes.as_slice())?;
+
+    let descriptor = class_file.const_pool.get_utf8(class_file.methods[0].descriptor_idx)?;
+    let descriptor = MethodDescriptor::parse(&descriptor.bytes)?;
+    assert_eq!(descriptor.parameters[1], FieldType::Array(Box::new(FieldType::Array(Box::new(FieldType::Long)))));
+    assert_eq!(descriptor.to_string(), "(I[[JLjava/lang/String;)[Z");
+    assert_eq!(format!("{:#}", descriptor), "boolean[] (int, long[][], java.lang.String)");
+
+    Ok(())
+}
+
+#[test]
+fn trailing_bytes() -> Result<(), Error> {
+    let mut bytes = ClassBuilder::new("com/example/App").main(vec![0xB1]).build();
+
+    assert!(ClassFile::read_exactly_from(&mut bytes.as_slice()).is_ok());
+    bytes.extend([0, 0, 0]);
+    assert!(ClassFile::read_from(&mut bytes.as_slice()).is_ok());
+    let error = ClassFile::read_exactly_from(&mut bytes.as_slice()).unwrap_err();
+    assert_eq!(error.to_string(), "java.lang.ClassFormatError: Extra bytes at the end of class file, 3 after the last attribute");
+
+    Ok(())
+}
+
+#[test]
+fn record_components() -> Result<(), Error> {
+    let mut class = ClassBuilder::new("com/example/Point");
+    class.super_class("java/lang/Record");
+    let mut info = vec![];
+    info.extend(2u16.to_be_bytes());
+    for (name, descriptor) in [("x", "I"), ("label", "Ljava/lang/String;")] {
+        info.extend(class.utf8(name).to_be_bytes());
+        info.extend(class.utf8(descriptor).to_be_bytes());
+        info.extend(0u16.to_be_bytes()); // attribute count
+    }
+    let bytes = class.field(0x12, "x", "I")
+        .field(0x12, "label", "Ljava/lang/String;")
+        .attribute("Record", info)
+        .build();
+
+    let class_file = ClassFile::read_from(&mut bytes.as_slice())?;
+
+    let components = class_file.record_components.iter()
+        .map(|component| Ok((component.name(&class_file.const_pool)?, component.descriptor(&class_file.const_pool)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+    assert_eq!(components, vec![("x", "I"), ("label", "Ljava/lang/String;")]);
+    assert!(class_file.record_components[0].attributes.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn iterate_instructions() -> Result<(), Error> {
+    // iload_0, tableswitch of 0 and 1 padded by two bytes, iconst_1, ireturn, wide iload 256, ireturn
+    let mut code = vec![0x1A, 0xAA, 0x00, 0x00];
+    for operand in [23, 0, 1, 23, 25_i32] {
+        code.extend(operand.to_be_bytes());
+    }
+    code.extend([0x04, 0xAC, 0xC4, 0x15, 0x01, 0x00, 0xAC]);
+    let mut class = ClassBuilder::new("com/example/App");
+    class.method(ACC_PUBLIC | ACC_STATIC, "pick", "(I)I", code);
+    let bytes = class.build();
+
+    let class_file = ClassFile::read_from(&mut bytes.as_slice())?;
+    let method = &class_file.methods[0];
+    let code_attr = method.attributes.iter()
+        .find(|attr| class_file.const_pool.get_utf8(attr.name_idx).map(|name| name.bytes == "Code").unwrap_or(false))
+        .unwrap();
+    let code = Code::read_from(&mut code_attr.info.as_slice())?;
+
+    let instructions = code.iter_instructions("pick", false).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(instructions, vec![
+        (0, Instruction::Other { opcode: 0x1A }),
+        (1, Instruction::TableSwitch { default: 24, low: 0, targets: vec![24, 26] }),
+        (24, Instruction::Other { opcode: 0x04 }),
+        (25, Instruction::Other { opcode: 0xAC }),
+        (26, Instruction::Load { opcode: 0x15, index: 256 }),
+        (30, Instruction::Other { opcode: 0xAC }),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn instruction_errors_name_method() -> Result<(), Error> {
+    // iconst_0, then a bipush missing its operand
+    let code = Code { max_stack: 1, max_locals: 0, code: vec![0x03, 0x10], exception_table: vec![] };
+
+    let error = code.iter_instructions("pick", false).collect::<Result<Vec<_>, _>>().unwrap_err();
+
+    assert_eq!(error.to_string(), "java.lang.VerifyError: instruction at pc 1 in pick runs past the end of the code");
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use crate::common::{ClassBuilder, ACC_PRIVATE, ACC_PROTECTED, ACC_PUBLIC, ACC_STATIC};
+use anyhow::Error;
+use assert_cmd::assert::Assert;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+/// Runs an App whose main reads the int field `secret` of `owner`, a class declaring it with the
+/// given access flags, with `--strict-access` if `strict`.
+fn read_field(app: &str, owner: &str, access_flags: u16, strict: bool) -> Result<Assert, Error> {
+    let dir = TempDir::new()?;
+    let owner_class = ClassBuilder::new(owner)
+        .field(access_flags | ACC_STATIC, "secret", "I")
+        .build();
+    let mut app_class = ClassBuilder::new(app);
+    app_class.super_class(owner);
+    let mut main = vec![0xB2]; // getstatic
+    main.extend(app_class.fieldref(owner, "secret", "I").to_be_bytes());
+    main.extend([0x57, 0xB1]); // pop, return
+    app_class.main(main);
+    for (name, class) in [(app, app_class.build()), (owner, owner_class)] {
+        let path = dir.path().join(format!("{}.class", name));
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, class)?;
+    }
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+    if strict {
+        cmd.arg("--strict-access");
+    }
+    Ok(cmd.arg("-cp")
+        .arg(dir.path())
+        .arg(app.replace('/', "."))
+        .assert())
+}
+
+#[test]
+fn private_field_of_other_class_rejected() -> Result<(), Error> {
+    read_field("App", "Other", ACC_PRIVATE, true)?
+        .failure()
+        .stderr(predicates::str::contains("java.lang.IllegalAccessError: class App tried to access private field Other.secret"));
+
+    Ok(())
+}
+
+#[test]
+fn private_field_of_other_class_allowed_by_default() -> Result<(), Error> {
+    read_field("App", "Other", ACC_PRIVATE, false)?
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn public_field_allowed() -> Result<(), Error> {
+    read_field("com/example/App", "org/example/Other", ACC_PUBLIC, true)?
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn package_private_field() -> Result<(), Error> {
+    read_field("com/example/App", "com/example/Other", 0, true)?
+        .success();
+    read_field("com/example/App", "org/example/Other", 0, true)?
+        .failure()
+        .stderr(predicates::str::contains(
+            "java.lang.IllegalAccessError: class com.example.App tried to access field org.example.Other.secret"));
+
+    Ok(())
+}
+
+#[test]
+fn protected_field_of_superclass_allowed() -> Result<(), Error> {
+    read_field("com/example/App", "org/example/Other", ACC_PROTECTED, true)?
+        .success();
+
+    Ok(())
+}
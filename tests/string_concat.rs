@@ -0,0 +1,47 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+const REF_INVOKE_STATIC: u8 = 6;
+const BOOTSTRAP_DESCRIPTOR: &str = "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;\
+    Ljava/lang/invoke/MethodType;Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/invoke/CallSite;";
+
+/// A class whose main throws a RuntimeException with the message concatenated by an invokedynamic
+/// call site, as javac compiles `"n=" + 42 + suffix` for Java 9 and later.
+fn concat_class() -> Vec<u8> {
+    let mut class = ClassBuilder::new("com/example/App");
+    let bootstrap = class.methodref("java/lang/invoke/StringConcatFactory", "makeConcatWithConstants", BOOTSTRAP_DESCRIPTOR);
+    let bootstrap = class.method_handle(REF_INVOKE_STATIC, bootstrap);
+    let recipe = class.string("n=\u{1}\u{2}");
+    let suffix = class.string("!");
+    let call_site = class.invoke_dynamic(0, "makeConcatWithConstants", "(I)Ljava/lang/String;");
+    let exception = class.class("java/lang/RuntimeException");
+    let init = class.methodref("java/lang/RuntimeException", "<init>", "(Ljava/lang/String;)V");
+
+    let mut main = vec![0xBB];
+    main.extend(exception.to_be_bytes());
+    main.extend([0x59, 0x10, 42, 0xBA]); // dup, bipush 42, invokedynamic
+    main.extend(call_site.to_be_bytes());
+    main.extend([0x00, 0x00, 0xB7]); // invokespecial
+    main.extend(init.to_be_bytes());
+    main.push(0xBF); // athrow
+    class.main(main).bootstrap_methods(&[(bootstrap, vec![recipe, suffix])]).build()
+}
+
+#[test]
+fn concat_with_constants() -> Result<(), Error> {
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-")
+        .write_stdin(concat_class())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Exception in thread \"main\" java.lang.RuntimeException: n=42!"));
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+/// An App whose main prints "hi" to System.out and "oops" to System.err.
+fn app() -> Vec<u8> {
+    let mut class = ClassBuilder::new("App");
+    let println = class.methodref("java/io/PrintStream", "println", "(Ljava/lang/String;)V");
+    let mut main = vec![];
+    for (stream, text) in [("out", "hi"), ("err", "oops")] {
+        let field = class.fieldref("java/lang/System", stream, "Ljava/io/PrintStream;");
+        let text = class.string(text);
+        main.push(0xB2); // getstatic
+        main.extend(field.to_be_bytes());
+        main.extend([0x12, text as u8, 0xB6]); // ldc, invokevirtual
+        main.extend(println.to_be_bytes());
+    }
+    main.push(0xB1);
+    class.main(main).build()
+}
+
+#[test]
+fn println_to_standard_streams() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), app())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success()
+        .stdout("hi\n")
+        .stderr("oops\n");
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::assert::Assert;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+/// Runs an App whose main prints `System.getProperty(key)`, or `System.getProperty(key, default)`
+/// given a default, with the given options.
+fn print_property(options: &[&str], key: &str, default: Option<&str>) -> Result<Assert, Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    let out = class.fieldref("java/lang/System", "out", "Ljava/io/PrintStream;");
+    let println = class.methodref("java/io/PrintStream", "println", "(Ljava/lang/String;)V");
+    let key = class.string(key);
+    let mut main = vec![0xB2]; // getstatic
+    main.extend(out.to_be_bytes());
+    main.extend([0x12, key as u8]); // ldc
+    let get_property = match default {
+        Some(default) => {
+            main.extend([0x12, class.string(default) as u8]); // ldc
+            class.methodref("java/lang/System", "getProperty", "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;")
+        }
+        None => class.methodref("java/lang/System", "getProperty", "(Ljava/lang/String;)Ljava/lang/String;"),
+    };
+    main.push(0xB8); // invokestatic
+    main.extend(get_property.to_be_bytes());
+    main.push(0xB6); // invokevirtual
+    main.extend(println.to_be_bytes());
+    main.push(0xB1);
+    fs::write(dir.path().join("App.class"), class.main(main).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    Ok(cmd.args(options)
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert())
+}
+
+#[test]
+fn property_set_on_command_line() -> Result<(), Error> {
+    print_property(&["-Dfoo=bar"], "foo", None)?.success().stdout("bar\n");
+    print_property(&["-Dfoo=bar=baz"], "foo", None)?.success().stdout("bar=baz\n");
+    print_property(&["-Dfoo"], "foo", None)?.success().stdout("\n");
+
+    Ok(())
+}
+
+#[test]
+fn unset_property() -> Result<(), Error> {
+    print_property(&[], "foo", None)?.success().stdout("null\n");
+    print_property(&[], "foo", Some("fallback"))?.success().stdout("fallback\n");
+    print_property(&["-Dfoo=bar"], "foo", Some("fallback"))?.success().stdout("bar\n");
+
+    Ok(())
+}
+
+#[test]
+fn default_properties() -> Result<(), Error> {
+    print_property(&[], "java.vendor", None)?.success().stdout("Robusta\n");
+    print_property(&["-Djava.vendor=Other"], "java.vendor", None)?.success().stdout("Other\n");
+
+    Ok(())
+}
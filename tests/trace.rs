@@ -0,0 +1,47 @@
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+fn class_path() -> Result<TempDir, Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), ClassBuilder::new("App").main(vec![0x03, 0xB1]).build())?;
+    Ok(dir)
+}
+
+#[test]
+fn trace_each_instruction() -> Result<(), Error> {
+    let dir = class_path()?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    let output = cmd.arg("--trace")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .output()?;
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stderr)?, "App.main([Ljava/lang/String;)V 0: iconst_0 0\nApp.main([Ljava/lang/String;)V 1: return\n");
+
+    Ok(())
+}
+
+#[test]
+fn no_trace_by_default() -> Result<(), Error> {
+    let dir = class_path()?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success()
+        .stderr("");
+
+    Ok(())
+}
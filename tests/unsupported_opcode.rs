@@ -0,0 +1,35 @@
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+/// Runs an App whose main is the given code, expecting it to fail with the given message.
+fn run_main(code: Vec<u8>, message: &str) -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), ClassBuilder::new("App").main(code).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(message));
+
+    Ok(())
+}
+
+#[test]
+fn unimplemented_opcode_named() -> Result<(), Error> {
+    // iconst_0, jsr +3, return
+    run_main(vec![0x03, 0xA8, 0x00, 0x03, 0xB1], "jsr (0xa8) unsupported at App.main([Ljava/lang/String;)V+1")
+}
+
+#[test]
+fn unassigned_opcode() -> Result<(), Error> {
+    run_main(vec![0xCB], "unknown opcode 0xcb at App.main([Ljava/lang/String;)V+0")
+}
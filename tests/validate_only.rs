@@ -0,0 +1,84 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::ClassBuilder;
+use anyhow::Error;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn valid_class() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let class_path = dir.path().join("App.class");
+    // aconst_null, athrow: fails if it runs
+    fs::write(&class_path, ClassBuilder::new("com/example/App").main(vec![0x01, 0xBF]).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--validate-only")
+        .arg(&class_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Validated "));
+
+    Ok(())
+}
+
+#[test]
+fn truncated_class() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let class_path = dir.path().join("App.class");
+    let mut class = ClassBuilder::new("com/example/App").main(vec![0xB1]).build();
+    class.truncate(class.len() - 4);
+    fs::write(&class_path, class)?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--validate-only")
+        .arg(&class_path)
+        .assert()
+        .failure()
+        .stdout(predicate::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn missing_referenced_class() -> Result<(), Error> {
+    let mut class = ClassBuilder::new("com/example/App");
+    class.class("com/example/Missing");
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--validate-only")
+        .arg("-")
+        .write_stdin(class.main(vec![0xB1]).build())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("java.lang.NoClassDefFoundError: com.example.Missing (referenced by com.example.App)"));
+
+    Ok(())
+}
+
+#[test]
+fn branch_into_instruction() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let class_path = dir.path().join("App.class");
+    // iconst_0, ifeq +4 into bipush's operand, bipush 7, pop, return
+    let main = vec![0x03, 0x99, 0x00, 0x04, 0x10, 0x07, 0x57, 0xB1];
+    fs::write(&class_path, ClassBuilder::new("com/example/App").main(main).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("--validate-only")
+        .arg(&class_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "java.lang.VerifyError: jump at pc 1 in main to pc 5, which isn't the start of an instruction"));
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+mod common;
+
+use crate::common::{ClassBuilder, ACC_PUBLIC};
+use anyhow::Error;
+use assert_cmd::assert::Assert;
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+/// A main method switching on 2 over cases 3 and then 1, out of the order lookupswitch requires.
+fn unsorted_lookup_switch() -> Vec<u8> {
+    let mut code = vec![
+        0x05, // iconst_2
+        0xAB, 0x00, 0x00, // lookupswitch, padded to a multiple of four
+    ];
+    code.extend(27i32.to_be_bytes()); // default
+    code.extend(2i32.to_be_bytes()); // pair count
+    code.extend(3i32.to_be_bytes());
+    code.extend(27i32.to_be_bytes());
+    code.extend(1i32.to_be_bytes());
+    code.extend(27i32.to_be_bytes());
+    code.push(0xB1); // return
+    code
+}
+
+#[test]
+fn unsorted_lookup_switch_rejected_when_verifying() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.main(unsorted_lookup_switch());
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-Xverify")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("java.lang.VerifyError: lookupswitch at pc 1 in main has unsorted key 1"));
+
+    Ok(())
+}
+
+#[test]
+fn unsorted_lookup_switch_runs_without_verifying() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.main(unsorted_lookup_switch());
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+/// An App followed by padding that isn't part of the class file.
+fn app_with_trailing_bytes() -> Vec<u8> {
+    let mut class = ClassBuilder::new("App").main(vec![0xB1]).build();
+    class.extend([0; 16]);
+    class
+}
+
+#[test]
+fn trailing_bytes_rejected_when_verifying() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), app_with_trailing_bytes())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+
+    cmd.arg("-Xverify")
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("java.lang.ClassFormatError: Extra bytes at the end of class file, 16 after"));
+
+    Ok(())
+}
+
+#[test]
+fn trailing_bytes_ignored_without_verifying() -> Result<(), Error> {
+    run_app(app_with_trailing_bytes())?
+        .success();
+
+    Ok(())
+}
+
+/// An App with a constructor and an instance method `run`, whose main creates an App and then runs
+/// the code `use_new` assembles, with the new App on the operand stack.
+fn app_using_new_object(use_new: impl FnOnce(&mut ClassBuilder) -> Vec<u8>) -> Vec<u8> {
+    let mut class = ClassBuilder::new("App");
+    let app_class = class.class("App");
+    let mut main = vec![0xBB]; // new
+    main.extend(app_class.to_be_bytes());
+    main.extend(use_new(&mut class));
+    main.push(0xB1);
+
+    let object_init = class.methodref("java/lang/Object", "<init>", "()V");
+    let mut init = vec![0x2A, 0xB7]; // aload_0, invokespecial
+    init.extend(object_init.to_be_bytes());
+    init.push(0xB1);
+
+    class.main(main)
+        .method(ACC_PUBLIC, "<init>", "()V", init)
+        .method(ACC_PUBLIC, "run", "()V", vec![0xB1]);
+    class.build()
+}
+
+fn run_app(class: Vec<u8>) -> Result<Assert, Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), class)?;
+
+    Ok(Command::cargo_bin("robusta")?
+        .arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert())
+}
+
+#[test]
+fn invoke_before_constructor() -> Result<(), Error> {
+    let class = app_using_new_object(|class| {
+        let mut code = vec![0xB6]; // invokevirtual
+        code.extend(class.methodref("App", "run", "()V").to_be_bytes());
+        code
+    });
+
+    run_app(class)?
+        .failure()
+        .stderr(predicates::str::contains(
+            "java.lang.VerifyError: Calling App.run()V on an uninitialized object in App.main([Ljava/lang/String;)V"));
+
+    Ok(())
+}
+
+#[test]
+fn read_field_before_constructor() -> Result<(), Error> {
+    let class = app_using_new_object(|class| {
+        let mut code = vec![0xB4]; // getfield
+        code.extend(class.fieldref("App", "count", "I").to_be_bytes());
+        code
+    });
+
+    run_app(class)?
+        .failure()
+        .stderr(predicates::str::contains(
+            "java.lang.VerifyError: Reading field App.count of an uninitialized object in App.main([Ljava/lang/String;)V"));
+
+    Ok(())
+}
+
+#[test]
+fn invoke_after_constructor() -> Result<(), Error> {
+    let class = app_using_new_object(|class| {
+        let mut code = vec![0x59, 0xB7]; // dup, invokespecial
+        code.extend(class.methodref("App", "<init>", "()V").to_be_bytes());
+        code.push(0xB6); // invokevirtual
+        code.extend(class.methodref("App", "run", "()V").to_be_bytes());
+        code
+    });
+
+    run_app(class)?
+        .success();
+
+    Ok(())
+}
+
+/// Writes an App with the given main and runs it, verifying if asked to.
+fn run_main(code: Vec<u8>, verify: bool) -> Result<Assert, Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("App.class"), ClassBuilder::new("App").main(code).build())?;
+
+    let mut cmd = Command::cargo_bin("robusta")?;
+    if verify {
+        cmd.arg("-Xverify");
+    }
+    Ok(cmd.arg("-cp")
+        .arg(dir.path())
+        .arg("App")
+        .assert())
+}
+
+#[test]
+fn goto_past_end_rejected_when_verifying() -> Result<(), Error> {
+    // goto +10, return
+    run_main(vec![0xA7, 0x00, 0x0A, 0xB1], true)?
+        .failure()
+        .stderr(predicates::str::contains("java.lang.VerifyError: jump at pc 0 in main outside the 4 bytes of its code"));
+
+    Ok(())
+}
+
+#[test]
+fn goto_past_end_fails_without_verifying() -> Result<(), Error> {
+    run_main(vec![0xA7, 0x00, 0x0A, 0xB1], false)?
+        .failure()
+        .stderr(predicates::str::contains("pc 10 past the end of the 4 bytes of App.main"));
+
+    Ok(())
+}
+
+#[test]
+fn untaken_branch_into_an_instruction_rejected_when_verifying() -> Result<(), Error> {
+    // iconst_1, ifeq +4, bipush 7, pop, return: the branch would land on bipush's operand
+    let code = vec![0x04, 0x99, 0x00, 0x04, 0x10, 0x07, 0x57, 0xB1];
+
+    run_main(code.clone(), true)?
+        .failure()
+        .stderr(predicates::str::contains(
+            "java.lang.VerifyError: jump at pc 1 in main to pc 5, which isn't the start of an instruction"));
+    run_main(code, false)?
+        .success();
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use anyhow::Error;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn version_reports_max_class_version() -> Result<(), Error> {
+    for flag in ["--version", "-version"] {
+        let mut cmd = Command::cargo_bin("robusta")?;
+
+        cmd.arg(flag)
+            .assert()
+            .success()
+            .stdout(predicate::str::starts_with(format!("robusta {}\n", env!("CARGO_PKG_VERSION")))
+                .and(predicate::str::contains("Class file versions up to 52 (Java 8)"))
+                .and(predicate::str::is_match(r"\d+ of 202 opcodes implemented")?));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,334 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::{ClassBuilder, ACC_PUBLIC, ACC_STATIC};
+use anyhow::Error;
+use robusta::class_file::ElementValue;
+use robusta::{HostClass, Value, Vm};
+use std::cell::RefCell;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn test_vm() -> Result<Vm, Error> {
+    let mut vm = Vm::new();
+    for entry in fs::read_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("data"))? {
+        vm.load(&entry?.path())?;
+    }
+    Ok(vm)
+}
+
+#[test]
+fn invoke_static_method() -> Result<(), Error> {
+    let mut vm = test_vm()?;
+
+    let result = vm.invoke_static("com.jkitch.robusta.test.Calls", "add", "(II)I", vec![Value::Int(2), Value::Int(40)])?;
+
+    assert_eq!(result, Some(Value::Int(42)));
+
+    Ok(())
+}
+
+#[test]
+fn invoke_static_with_wrong_argument_count() -> Result<(), Error> {
+    let mut vm = test_vm()?;
+
+    let result = vm.invoke_static("com.jkitch.robusta.test.Calls", "add", "(II)I", vec![Value::Int(2)]);
+
+    assert_eq!(result.unwrap_err().to_string(), "com.jkitch.robusta.test.Calls.add(II)I takes 2 arguments but was given 1");
+
+    Ok(())
+}
+
+/// An App whose static `run()I` logs "hello" to host.Console then returns the count of a new Console.
+fn host_app() -> Vec<u8> {
+    let mut class = ClassBuilder::new("com/example/App");
+    let hello = class.string("hello");
+    let log = class.methodref("host/Console", "log", "(Ljava/lang/String;)V");
+    let console = class.class("host/Console");
+    let init = class.methodref("host/Console", "<init>", "()V");
+    let count = class.methodref("host/Console", "count", "()I");
+
+    let mut run = vec![0x12, hello as u8, 0xB8]; // ldc, invokestatic
+    run.extend(log.to_be_bytes());
+    run.push(0xBB); // new
+    run.extend(console.to_be_bytes());
+    run.extend([0x59, 0xB7]); // dup, invokespecial
+    run.extend(init.to_be_bytes());
+    run.push(0xB6); // invokevirtual
+    run.extend(count.to_be_bytes());
+    run.push(0xAC); // ireturn
+    class.method(ACC_PUBLIC | ACC_STATIC, "run", "()I", run).build()
+}
+
+#[test]
+fn call_host_methods() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::create_dir_all(dir.path().join("com/example"))?;
+    fs::write(dir.path().join("com/example/App.class"), host_app())?;
+    let mut vm = test_vm()?;
+    vm.load(dir.path())?;
+
+    let logged = Rc::new(RefCell::new(vec![]));
+    let log = logged.clone();
+    vm.register_host_class(HostClass::new("host.Console")
+        .static_method("log", "(Ljava/lang/String;)V", move |host, args| {
+            log.borrow_mut().push(host.read_string(args[0])?.unwrap());
+            Ok(None)
+        })
+        .method("count", "()I", |_, args| {
+            assert!(matches!(args[..], [Value::Reference(_)]));
+            Ok(Some(Value::Int(7)))
+        }))?;
+
+    let result = vm.invoke_static("com.example.App", "run", "()I", vec![])?;
+
+    assert_eq!(result, Some(Value::Int(7)));
+    assert_eq!(*logged.borrow(), vec!["hello".to_string()]);
+
+    Ok(())
+}
+
+/// A sink that keeps what's written to it where the test can still read it.
+#[derive(Clone, Default)]
+struct Capture(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A Printer whose static `run()V` prints "out" to a PrintStream for stdout and "err" to one for stderr.
+fn printer() -> Vec<u8> {
+    let mut class = ClassBuilder::new("Printer");
+    let print_stream_class = class.class("java/io/PrintStream");
+    let print_stream_init = class.methodref("java/io/PrintStream", "<init>", "(I)V");
+    let println = class.methodref("java/io/PrintStream", "println", "(Ljava/lang/String;)V");
+    let out = class.string("out");
+    let err = class.string("err");
+
+    let mut run = vec![];
+    for (fd_const, text) in [(0x04, out), (0x05, err)] {
+        run.push(0xBB); // new
+        run.extend(print_stream_class.to_be_bytes());
+        run.extend([0x59, fd_const, 0xB7]); // dup, iconst_1 or iconst_2, invokespecial
+        run.extend(print_stream_init.to_be_bytes());
+        run.extend([0x12, text as u8, 0xB6]); // ldc, invokevirtual
+        run.extend(println.to_be_bytes());
+    }
+    run.push(0xB1);
+    class.method(ACC_PUBLIC | ACC_STATIC, "run", "()V", run).build()
+}
+
+#[test]
+fn capture_output() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("Printer.class"), printer())?;
+    let stdout = Capture::default();
+    let stderr = Capture::default();
+    let mut vm = test_vm()?.with_stdout(Box::new(stdout.clone())).with_stderr(Box::new(stderr.clone()));
+    vm.load(dir.path())?;
+
+    vm.invoke_static("Printer", "run", "()V", vec![])?;
+
+    assert_eq!(*stdout.0.borrow(), b"out\n");
+    assert_eq!(*stderr.0.borrow(), b"err\n");
+
+    Ok(())
+}
+
+#[test]
+fn read_generic_signatures() -> Result<(), Error> {
+    let vm = test_vm()?;
+
+    let class = vm.class("com.jkitch.robusta.test.Generics")?;
+
+    assert_eq!(class.signature(), Some("<T::Ljava/lang/Comparable<TT;>;>Ljava/lang/Object;"));
+    assert_eq!(class.find_field("value").unwrap().signature(), Some("TT;"));
+    let identity = class.find_method("identity", "(Ljava/lang/Object;)Ljava/lang/Object;").unwrap();
+    assert_eq!(identity.signature(), Some("<E:Ljava/lang/Object;>(TE;)TE;"));
+
+    Ok(())
+}
+
+#[test]
+fn read_declared_exceptions() -> Result<(), Error> {
+    let vm = test_vm()?;
+
+    let class = vm.class("com.jkitch.robusta.test.Throws")?;
+
+    assert_eq!(class.find_method("readNothing", "()V").unwrap().exceptions(), ["java/io/IOException"]);
+
+    Ok(())
+}
+
+#[test]
+fn read_annotations() -> Result<(), Error> {
+    let vm = test_vm()?;
+
+    let class = vm.class("com.jkitch.robusta.test.Annotated")?;
+
+    assert_eq!(class.annotations()[1].type_name, "com/jkitch/robusta/test/Tag");
+    assert_eq!(class.annotations()[1].element("value"), Some(&ElementValue::String("example".to_string())));
+    assert_eq!(class.find_field("count").unwrap().annotations()[0].element("priority"), Some(&ElementValue::Int(2)));
+    assert_eq!(class.find_method("run", "()V").unwrap().annotations()[0].type_name, "com/jkitch/robusta/test/Marker");
+
+    Ok(())
+}
+
+#[test]
+fn read_enclosing_method() -> Result<(), Error> {
+    let vm = test_vm()?;
+
+    let class = vm.class("com.jkitch.robusta.test.Anonymous$1Local")?;
+
+    assert_eq!(class.enclosing_method(), Some(("com/jkitch/robusta/test/Anonymous", Some(("local", "(I)Ljava/lang/Object;")))));
+
+    Ok(())
+}
+
+#[test]
+fn read_record_components() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let mut point = ClassBuilder::new("Point");
+    point.super_class("java/lang/Record");
+    let mut info = 2u16.to_be_bytes().to_vec();
+    for (name, descriptor) in [("x", "I"), ("label", "Ljava/lang/String;")] {
+        info.extend(point.utf8(name).to_be_bytes());
+        info.extend(point.utf8(descriptor).to_be_bytes());
+        info.extend(0u16.to_be_bytes()); // attribute count
+    }
+    fs::write(dir.path().join("Point.class"), point.attribute("Record", info).build())?;
+    let mut vm = test_vm()?;
+    vm.load(dir.path())?;
+
+    let class = vm.class("Point")?;
+
+    assert_eq!(class.record_components(), [
+        ("x".to_string(), "I".to_string()),
+        ("label".to_string(), "Ljava/lang/String;".to_string()),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn cancel_from_another_thread() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    let spinner = ClassBuilder::new("Spinner")
+        .method(ACC_PUBLIC | ACC_STATIC, "spin", "()V", vec![0xA7, 0x00, 0x00]) // goto 0
+        .build();
+    fs::write(dir.path().join("Spinner.class"), spinner)?;
+    let mut vm = test_vm()?;
+    vm.load(dir.path())?;
+    let cancelled = vm.cancellation_token();
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cancelled.store(true, Ordering::Relaxed);
+    });
+
+    let result = vm.invoke_static("Spinner", "spin", "()V", vec![]);
+    canceller.join().unwrap();
+
+    assert_eq!(result.unwrap_err().to_string(), "java.lang.InterruptedException: execution was cancelled");
+
+    Ok(())
+}
+
+#[test]
+fn preload_initializes_up_front() -> Result<(), Error> {
+    let mut vm = test_vm()?;
+
+    let before = vm.initialized_class_count();
+    vm.invoke_static("com.jkitch.robusta.test.Enums", "redOrdinal", "()I", vec![])?;
+    // Without preloading, the first call initializes the class and the enum it uses
+    assert!(vm.initialized_class_count() > before);
+
+    let mut vm = test_vm()?;
+    vm.preload(&["com.jkitch.robusta.test.Enums", "com.jkitch.robusta.test.Enums$Color"])?;
+    let preloaded = vm.initialized_class_count();
+    let result = vm.invoke_static("com.jkitch.robusta.test.Enums", "redOrdinal", "()I", vec![])?;
+
+    assert_eq!(result, Some(Value::Int(0)));
+    assert_eq!(vm.initialized_class_count(), preloaded);
+
+    Ok(())
+}
+
+/// A Counter whose static `increment()I` adds one to its static count and returns it.
+fn counter() -> Vec<u8> {
+    let mut class = ClassBuilder::new("com/example/Counter");
+    let count = class.fieldref("com/example/Counter", "count", "I");
+
+    let mut increment = vec![0xB2]; // getstatic
+    increment.extend(count.to_be_bytes());
+    increment.extend([0x04, 0x60, 0x59, 0xB3]); // iconst_1, iadd, dup, putstatic
+    increment.extend(count.to_be_bytes());
+    increment.push(0xAC); // ireturn
+    class.field(ACC_STATIC, "count", "I")
+        .method(ACC_PUBLIC | ACC_STATIC, "increment", "()I", increment)
+        .build()
+}
+
+#[test]
+fn vms_are_isolated() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::create_dir_all(dir.path().join("com/example"))?;
+    fs::write(dir.path().join("com/example/Counter.class"), counter())?;
+    let mut first = test_vm()?;
+    first.load(dir.path())?;
+    let mut second = test_vm()?;
+    second.load(dir.path())?;
+
+    first.invoke_static("com.example.Counter", "increment", "()I", vec![])?;
+    let first_count = first.invoke_static("com.example.Counter", "increment", "()I", vec![])?;
+    let second_count = second.invoke_static("com.example.Counter", "increment", "()I", vec![])?;
+
+    assert_eq!(first_count, Some(Value::Int(2)));
+    assert_eq!(second_count, Some(Value::Int(1)));
+
+    Ok(())
+}
+
+#[test]
+fn transformer_renames_method() -> Result<(), Error> {
+    let dir = TempDir::new()?;
+    fs::create_dir_all(dir.path().join("com/example"))?;
+    let mut class = ClassBuilder::new("com/example/Answer");
+    class.method(ACC_PUBLIC | ACC_STATIC, "original", "()I", vec![0x10, 42, 0xAC]); // bipush, ireturn
+    fs::write(dir.path().join("com/example/Answer.class"), class.build())?;
+    let mut vm = test_vm()?;
+    let transformed = Rc::new(RefCell::new(vec![]));
+    let seen = transformed.clone();
+    vm.add_transformer(move |class_name, class_file| {
+        seen.borrow_mut().push(class_name.to_string());
+        let renamed = class_file.const_pool.push_utf8("renamed")?;
+        for method in &mut class_file.methods {
+            if class_file.const_pool.get_utf8(method.name_idx)?.bytes == "original" {
+                method.name_idx = renamed;
+            }
+        }
+        Ok(())
+    });
+    vm.load(dir.path())?;
+
+    let result = vm.invoke_static("com.example.Answer", "renamed", "()I", vec![])?;
+
+    assert_eq!(result, Some(Value::Int(42)));
+    assert!(vm.invoke_static("com.example.Answer", "original", "()I", vec![]).is_err());
+    assert_eq!(*transformed.borrow(), vec!["com.example.Answer"]);
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "jar")]
+
+mod common;
+
+use crate::common::{ClassBuilder, ACC_PUBLIC, ACC_STATIC};
+use anyhow::Error;
+use robusta::{Value, Vm};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Loads the runtime jar and a class whose static `run()I` is the given code, with 400 local variables.
+fn vm_running(code: Vec<u8>) -> Result<(Vm, TempDir), Error> {
+    let dir = TempDir::new()?;
+    let mut class = ClassBuilder::new("App");
+    class.max_locals(400).method(ACC_PUBLIC | ACC_STATIC, "run", "()I", code);
+    fs::write(dir.path().join("App.class"), class.build())?;
+
+    let mut vm = Vm::new();
+    vm.load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("data/robusta-java-runtime-0.1.0.jar"))?;
+    vm.load(dir.path())?;
+    Ok((vm, dir))
+}
+
+#[test]
+fn wide_locals() -> Result<(), Error> {
+    let code = vec![
+        0x10, 0x2A, // bipush 42
+        0xC4, 0x36, 0x01, 0x2C, // wide istore 300
+        0xC4, 0x84, 0x01, 0x2C, 0x03, 0xE8, // wide iinc 300 by 1000
+        0x08, 0xBC, 0x0A, // iconst_5, newarray int
+        0xC4, 0x3A, 0x01, 0x2D, // wide astore 301
+        0xC4, 0x19, 0x01, 0x2D, // wide aload 301
+        0xBE, // arraylength
+        0xC4, 0x15, 0x01, 0x2C, // wide iload 300
+        0x60, // iadd
+        0xAC, // ireturn
+    ];
+    let (mut vm, _dir) = vm_running(code)?;
+
+    assert_eq!(vm.invoke_static("App", "run", "()I", vec![])?, Some(Value::Int(1047)));
+
+    Ok(())
+}
+
+#[test]
+fn narrow_locals() -> Result<(), Error> {
+    // bipush 7, istore 200, iinc 200 by -1, iload 200, ireturn
+    let code = vec![0x10, 0x07, 0x36, 0xC8, 0x84, 0xC8, 0xFF, 0x15, 0xC8, 0xAC];
+    let (mut vm, _dir) = vm_running(code)?;
+
+    assert_eq!(vm.invoke_static("App", "run", "()I", vec![])?, Some(Value::Int(6)));
+
+    Ok(())
+}